@@ -1,8 +1,7 @@
-use rustls::pki_types::pem::PemObject;
-use rustls::pki_types::{CertificateDer, PrivateKeyDer};
-use silent::prelude::{HandlerAppend, Level, Listener, Route, Server, logger};
-use std::sync::Arc;
-use tokio_rustls::{TlsAcceptor, rustls};
+use silent::prelude::{
+    HandlerAppend, Level, Listener, ReloadableTlsAcceptor, Route, Server, TlsConfig, logger,
+};
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() {
@@ -12,24 +11,19 @@ async fn main() {
         "current dir: {}",
         std::env::current_dir().unwrap().display()
     );
-    let certs = CertificateDer::pem_file_iter("./examples/tls/certs/localhost+2.pem")
-        .expect("failed to load certificate file")
-        .collect::<Result<Vec<_>, _>>()
-        .expect("failed to parse certificate file");
-    let key = PrivateKeyDer::from_pem_file("./examples/tls/certs/localhost+2-key.pem")
-        .expect("failed to load private key file");
-
-    let config = rustls::ServerConfig::builder()
-        .with_no_client_auth()
-        .with_single_cert(certs, key)
-        .unwrap();
-    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let config = TlsConfig::new().cert(
+        "./examples/tls/certs/localhost+2.pem",
+        "./examples/tls/certs/localhost+2-key.pem",
+    );
+    let acceptor = ReloadableTlsAcceptor::new(config).expect("failed to load certificates");
+    // 证书文件变化时自动重新加载
+    acceptor.watch(Duration::from_secs(30));
     let listener: Listener = tokio::net::TcpListener::bind("127.0.0.1:8443")
         .await
         .expect("failed to bind")
         .into();
     Server::new()
-        .listen(listener.tls(acceptor))
+        .listen(listener.tls_reloadable(acceptor))
         .serve(route)
         .await;
 }
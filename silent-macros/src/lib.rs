@@ -0,0 +1,98 @@
+//! silent 的过程宏
+use proc_macro::TokenStream;
+use quote::quote;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::{LitStr, parse_macro_input};
+
+/// 预压缩文件的扩展名与对应的编码
+const ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gz", "gzip")];
+
+/// 在编译期将目录中的文件嵌入二进制，生成 `silent::prelude::EmbeddedAssets`
+///
+/// 路径相对于调用方 crate 的 `Cargo.toml` 所在目录。同目录下的 `.br` 与 `.gz`
+/// 同名文件作为原文件的预压缩版本嵌入。
+#[proc_macro]
+pub fn embed_assets(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as LitStr);
+    let root = std::env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(dir.value());
+    let mut files = BTreeMap::new();
+    if let Err(e) = collect(&root, &root, &mut files) {
+        let msg = format!("failed to read assets `{}`: {e}", root.display());
+        return syn::Error::new(dir.span(), msg).to_compile_error().into();
+    }
+
+    let assets = files.iter().filter_map(|(path, file)| {
+        // 存在原文件的预压缩文件只作为原文件的变体
+        if ENCODINGS.iter().any(|(extension, _)| {
+            path.strip_suffix(extension)
+                .and_then(|base| base.strip_suffix('.'))
+                .is_some_and(|base| files.contains_key(base))
+        }) {
+            return None;
+        }
+        let content_type = mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string();
+        let (data, etag) = embed(file)?;
+        let variants = ENCODINGS.iter().filter_map(|(extension, encoding)| {
+            let variant = files.get(&format!("{path}.{extension}"))?;
+            let (data, etag) = embed(variant)?;
+            Some(quote! {
+                ::silent::prelude::EmbeddedVariant::new(#encoding, #etag, #data)
+            })
+        });
+        Some(quote! {
+            ::silent::prelude::EmbeddedFile::new(
+                #path,
+                #content_type,
+                #etag,
+                #data,
+                &[#(#variants),*],
+            )
+        })
+    });
+    quote! {
+        ::silent::prelude::EmbeddedAssets::new(&[#(#assets),*])
+    }
+    .into()
+}
+
+/// 递归收集目录中的文件，键为使用 `/` 分隔的相对路径
+fn collect(root: &Path, dir: &Path, files: &mut BTreeMap<String, PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(root, &path, files)?;
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(relative, path);
+    }
+    Ok(())
+}
+
+/// 生成引用文件内容的 `include_bytes!` 与按内容计算的 ETag
+fn embed(path: &Path) -> Option<(proc_macro2::TokenStream, String)> {
+    let content = std::fs::read(path).ok()?;
+    let path = path.to_str()?;
+    let etag = format!("\"{:x}-{:016x}\"", content.len(), fnv1a(&content));
+    Some((quote! { include_bytes!(#path) }, etag))
+}
+
+/// FNV-1a 64 位散列
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
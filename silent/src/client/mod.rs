@@ -0,0 +1,572 @@
+mod connector;
+
+use crate::core::req_body::ReqBody;
+use crate::core::res_body::ResBody;
+use crate::{Method, Request, Response, Result, SilentError, StatusCode};
+use bytes::Bytes;
+use connector::Connector;
+use http::{HeaderMap, HeaderName, HeaderValue, Uri, Version, header};
+use hyper_util::client::legacy::Client as HyperClient;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::{TokioExecutor, TokioTimer};
+use serde::Serialize;
+use std::time::Duration;
+#[cfg(feature = "tls")]
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+/// 基于 hyper 的 HTTP 客户端，请求与响应使用 silent 的 [`Request`] 与 [`Response`]
+///
+/// 客户端内部维护连接池，克隆后共享连接。启用 `tls` 特性时支持 https，
+/// 默认使用 webpki 根证书。
+/// ```no_run
+/// use silent::prelude::*;
+/// # async fn call() -> Result<()> {
+/// let client = Client::new()?;
+/// let mut res = client
+///     .post("http://127.0.0.1:8000/users")
+///     .json(&serde_json::json!({ "name": "silent" }))
+///     .send()
+///     .await?;
+/// let user: serde_json::Value = res.json_parse().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Client {
+    inner: HyperClient<Connector, ReqBody>,
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// 使用默认配置创建客户端
+    pub fn new() -> Result<Self> {
+        Self::builder().build()
+    }
+    /// 创建客户端构造器
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+    /// 构造指定方法的请求
+    pub fn request(&self, method: Method, uri: &str) -> ClientRequest {
+        ClientRequest {
+            client: self.clone(),
+            builder: http::Request::builder().method(method).uri(uri),
+            body: Bytes::new(),
+            timeout: self.timeout,
+            error: None,
+        }
+    }
+    /// 构造 GET 请求
+    pub fn get(&self, uri: &str) -> ClientRequest {
+        self.request(Method::GET, uri)
+    }
+    /// 构造 POST 请求
+    pub fn post(&self, uri: &str) -> ClientRequest {
+        self.request(Method::POST, uri)
+    }
+    /// 构造 PUT 请求
+    pub fn put(&self, uri: &str) -> ClientRequest {
+        self.request(Method::PUT, uri)
+    }
+    /// 构造 PATCH 请求
+    pub fn patch(&self, uri: &str) -> ClientRequest {
+        self.request(Method::PATCH, uri)
+    }
+    /// 构造 DELETE 请求
+    pub fn delete(&self, uri: &str) -> ClientRequest {
+        self.request(Method::DELETE, uri)
+    }
+    /// 构造 HEAD 请求
+    pub fn head(&self, uri: &str) -> ClientRequest {
+        self.request(Method::HEAD, uri)
+    }
+
+    /// 发送请求，请求的 URI 需要是包含协议与主机的绝对地址
+    pub async fn send(&self, req: Request) -> Result<Response> {
+        self.execute(req.into_http(), self.timeout).await
+    }
+
+    /// 将收到的请求转发到上游服务
+    ///
+    /// 上游地址的路径作为前缀拼接请求的路径与查询参数，逐跳请求头会被移除，
+    /// `host` 使用上游地址。上游的响应体以流的方式返回。
+    /// ```no_run
+    /// use silent::prelude::*;
+    /// # fn main() -> Result<()> {
+    /// let client = Client::new()?;
+    /// let route = Route::new("<path:**>").get(move |req: Request| {
+    ///     let client = client.clone();
+    ///     async move { client.forward(req, "http://127.0.0.1:9000").await }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn forward(&self, req: Request, upstream: &str) -> Result<Response> {
+        let upstream: Uri = upstream.parse().map_err(|e| {
+            SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("invalid upstream `{upstream}`: {e}"),
+            )
+        })?;
+        let mut req = req.into_http();
+        let path_and_query = req.uri().path_and_query().map_or("/", |path| path.as_str());
+        *req.uri_mut() = join_uri(&upstream, path_and_query)?;
+        // 请求可能来自 HTTP/2 连接，由客户端按连接协商结果决定版本
+        *req.version_mut() = Version::HTTP_11;
+        remove_hop_headers(req.headers_mut());
+        req.headers_mut().remove(header::HOST);
+        let mut res = self.execute(req, self.timeout).await?;
+        remove_hop_headers(res.headers_mut());
+        Ok(res)
+    }
+
+    /// 客户端配置的请求超时时间
+    #[cfg(feature = "proxy")]
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        req: http::Request<ReqBody>,
+        timeout: Option<Duration>,
+    ) -> Result<Response> {
+        if req.uri().scheme().is_none() || req.uri().authority().is_none() {
+            return Err(SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("request uri must be absolute: {}", req.uri()),
+            ));
+        }
+        let uri = req.uri().clone();
+        let request = self.inner.request(req);
+        let res = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, request).await.map_err(|_| {
+                SilentError::business_error(
+                    StatusCode::GATEWAY_TIMEOUT,
+                    format!("request to {uri} timed out"),
+                )
+            })?,
+            None => request.await,
+        }
+        .map_err(|e| {
+            let source = std::error::Error::source(&e)
+                .map(|source| format!(": {source}"))
+                .unwrap_or_default();
+            SilentError::business_error(
+                StatusCode::BAD_GATEWAY,
+                format!("request to {uri} failed: {e}{source}"),
+            )
+        })?;
+        let (parts, body) = res.into_parts();
+        let mut res = Response::empty();
+        res.status = parts.status;
+        res.version = parts.version;
+        res.headers = parts.headers;
+        res.extensions = parts.extensions;
+        res.body = ResBody::Incoming(body);
+        Ok(res)
+    }
+}
+
+/// 拼接上游地址的路径前缀与请求的路径、查询参数
+pub(crate) fn join_uri(base: &Uri, path_and_query: &str) -> Result<Uri> {
+    let mut uri = base.clone().into_parts();
+    let prefix = uri
+        .path_and_query
+        .as_ref()
+        .map_or("", |path| path.path().trim_end_matches('/'));
+    uri.path_and_query = Some(
+        format!("{prefix}{path_and_query}")
+            .parse()
+            .map_err(|e| SilentError::business_error(StatusCode::BAD_REQUEST, format!("{e}")))?,
+    );
+    Uri::from_parts(uri)
+        .map_err(|e| SilentError::business_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// 移除仅对单个连接有效的逐跳请求头
+pub(crate) fn remove_hop_headers(headers: &mut HeaderMap) {
+    let listed = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect::<Vec<_>>();
+    for name in listed {
+        headers.remove(name);
+    }
+    for name in [
+        header::CONNECTION,
+        HeaderName::from_static("keep-alive"),
+        header::PROXY_AUTHENTICATE,
+        header::PROXY_AUTHORIZATION,
+        HeaderName::from_static("proxy-connection"),
+        header::TE,
+        header::TRAILER,
+        header::TRANSFER_ENCODING,
+        header::UPGRADE,
+    ] {
+        headers.remove(name);
+    }
+}
+
+/// [`Client`] 构造器
+#[derive(Default)]
+pub struct ClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    #[cfg(feature = "tls")]
+    tls_config: Option<ClientConfig>,
+}
+
+impl ClientBuilder {
+    /// 请求超时时间，从发送请求到收到响应头，超时返回 `504`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// 建立连接的超时时间
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// 连接池中空闲连接的保留时间，默认 90 秒
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+    /// 每个主机保留的最大空闲连接数
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+    /// 自定义 TLS 配置，未设置 ALPN 时默认协商 `h2` 与 `http/1.1`
+    #[cfg(feature = "tls")]
+    pub fn tls_config(mut self, config: ClientConfig) -> Self {
+        self.tls_config = Some(config);
+        self
+    }
+    /// 创建客户端
+    ///
+    /// 启用 `tls` 特性且未自定义 TLS 配置时，需要进程默认的加密实现或启用 `aws-lc-rs`、`ring` 特性，
+    /// 否则返回错误。
+    pub fn build(self) -> Result<Client> {
+        let mut http = HttpConnector::new();
+        http.set_connect_timeout(self.connect_timeout);
+        http.set_nodelay(true);
+        #[cfg(feature = "tls")]
+        let tls = {
+            let mut config = match self.tls_config {
+                Some(config) => config,
+                None => {
+                    let tls_error = |e: String| {
+                        SilentError::business_error(StatusCode::INTERNAL_SERVER_ERROR, e)
+                    };
+                    let provider = crate::core::tls::crypto_provider()
+                        .map_err(|e| tls_error(e.to_string()))?;
+                    let mut roots = RootCertStore::empty();
+                    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+                    ClientConfig::builder_with_provider(provider)
+                        .with_safe_default_protocol_versions()
+                        .map_err(|e| tls_error(e.to_string()))?
+                        .with_root_certificates(roots)
+                        .with_no_client_auth()
+                }
+            };
+            if config.alpn_protocols.is_empty() {
+                config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+            }
+            tokio_rustls::TlsConnector::from(std::sync::Arc::new(config))
+        };
+        let connector = Connector::new(
+            http,
+            #[cfg(feature = "tls")]
+            tls,
+        );
+        let mut builder = HyperClient::builder(TokioExecutor::new());
+        builder.pool_timer(TokioTimer::new());
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder.pool_max_idle_per_host(max);
+        }
+        Ok(Client {
+            inner: builder.build(connector),
+            timeout: self.timeout,
+        })
+    }
+}
+
+/// [`Client`] 的请求构造器
+pub struct ClientRequest {
+    client: Client,
+    builder: http::request::Builder,
+    body: Bytes,
+    timeout: Option<Duration>,
+    error: Option<SilentError>,
+}
+
+impl ClientRequest {
+    /// 添加请求头
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: Into<http::Error>,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: Into<http::Error>,
+    {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+    /// 追加查询参数
+    pub fn query<T: Serialize>(mut self, query: &T) -> Self {
+        match serde_html_form::to_string(query) {
+            Ok(query) => {
+                if let Some(uri) = self.builder.uri_ref().map(ToString::to_string) {
+                    let separator = if uri.contains('?') { '&' } else { '?' };
+                    self.builder = self.builder.uri(format!("{uri}{separator}{query}"));
+                }
+            }
+            Err(e) => self.fail(e.to_string()),
+        }
+        self
+    }
+    /// 设置请求体
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+    /// 设置 JSON 请求体
+    pub fn json<T: Serialize>(mut self, json: &T) -> Self {
+        match serde_json::to_vec(json) {
+            Ok(body) => {
+                self = self.content_type(mime::APPLICATION_JSON.as_ref());
+                self.body = body.into();
+            }
+            Err(e) => self.error = Some(e.into()),
+        }
+        self
+    }
+    /// 设置 `application/x-www-form-urlencoded` 请求体
+    pub fn form<T: Serialize>(mut self, form: &T) -> Self {
+        match serde_html_form::to_string(form) {
+            Ok(body) => {
+                self = self.content_type(mime::APPLICATION_WWW_FORM_URLENCODED.as_ref());
+                self.body = body.into();
+            }
+            Err(e) => self.fail(e.to_string()),
+        }
+        self
+    }
+    /// 设置本次请求的超时时间，覆盖客户端的配置
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// 发送请求
+    pub async fn send(self) -> Result<Response> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let body = match self.body.is_empty() {
+            true => ReqBody::Empty,
+            false => ReqBody::Once(self.body),
+        };
+        let req = self.builder.body(body).map_err(|e| {
+            SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("invalid request: {e}"),
+            )
+        })?;
+        self.client.execute(req, self.timeout).await
+    }
+
+    fn content_type(mut self, content_type: &str) -> Self {
+        if let Some(headers) = self.builder.headers_mut() {
+            headers
+                .entry(header::CONTENT_TYPE)
+                .or_insert(HeaderValue::from_str(content_type).unwrap());
+        }
+        self
+    }
+
+    fn fail(&mut self, msg: String) {
+        self.error = Some(SilentError::business_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            msg,
+        ));
+    }
+}
+
+// 启用 tls 时测试需要加密实现
+#[cfg(all(
+    test,
+    feature = "server",
+    any(not(feature = "tls"), feature = "aws-lc-rs", feature = "ring")
+))]
+mod tests {
+    use crate::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    fn upstream() -> Route {
+        Route::new("")
+            .append(Route::new("users").post(|mut req: Request| async move {
+                let user: User = req.json_parse().await?;
+                Ok(user)
+            }))
+            .append(Route::new("echo").get(|mut req: Request| async move {
+                let query = req.params().get("q").cloned().unwrap_or_default();
+                let hop = req.headers().contains_key("x-hop");
+                Ok(format!("{} {query} {hop}", req.uri().path()))
+            }))
+            .append(Route::new("slow").get(|_req| async {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                Ok("slow")
+            }))
+    }
+
+    #[tokio::test]
+    async fn test_client() {
+        let handle = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(upstream())
+            .unwrap();
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let client = Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let user = User {
+            id: 1,
+            name: "silent".to_string(),
+        };
+        let mut res = client
+            .post(&format!("http://{addr}/users"))
+            .json(&user)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status, StatusCode::OK);
+        assert_eq!(res.json_parse::<User>().await.unwrap(), user);
+
+        let mut res = client
+            .get(&format!("http://{addr}/echo"))
+            .query(&[("q", "a")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.body_text().await.unwrap(), "/echo a false");
+
+        let err = client
+            .get(&format!("http://{addr}/slow"))
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::GATEWAY_TIMEOUT);
+        let mut res = client
+            .get(&format!("http://{addr}/slow"))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.body_text().await.unwrap(), "slow");
+
+        let err = client.get("/relative").send().await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        handle.shutdown();
+        let err = client.get("http://127.0.0.1:1/").send().await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn test_forward() {
+        let upstream = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(upstream())
+            .unwrap();
+        let upstream_addr = upstream.local_addrs()[0].tcp_addr().unwrap();
+        let client = Client::new().unwrap();
+        let base = format!("http://{upstream_addr}/");
+        let forward = move |req: Request| {
+            let client = client.clone();
+            let base = base.clone();
+            async move { client.forward(req, &base).await }
+        };
+        let proxy = Route::new("<path:**>").get(forward.clone()).post(forward);
+        let proxy = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(proxy)
+            .unwrap();
+        let proxy_addr = proxy.local_addrs()[0].tcp_addr().unwrap();
+
+        let client = Client::new().unwrap();
+        let mut res = client
+            .get(&format!("http://{proxy_addr}/echo?q=b"))
+            .header("connection", "x-hop")
+            .header("x-hop", "1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.body_text().await.unwrap(), "/echo b false");
+
+        let user = User {
+            id: 2,
+            name: "proxy".to_string(),
+        };
+        let mut res = client
+            .post(&format!("http://{proxy_addr}/users"))
+            .json(&user)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.json_parse::<User>().await.unwrap(), user);
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_https() {
+        use crate::core::tls::crypto_provider;
+        use tokio_rustls::rustls::pki_types::CertificateDer;
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+        let certs = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/certs");
+        let tls =
+            TlsConfig::new().cert(certs.join("localhost.pem"), certs.join("localhost-key.pem"));
+        let handle = Server::new()
+            .bind_tls("127.0.0.1:0".parse().unwrap(), tls)
+            .start(upstream())
+            .unwrap();
+        let port = handle.local_addrs()[0].tcp_addr().unwrap().port();
+
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(certs.join("ca.pem")).unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let config = ClientConfig::builder_with_provider(crypto_provider().unwrap())
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let client = Client::builder().tls_config(config).build().unwrap();
+        let mut res = client
+            .get(&format!("https://localhost:{port}/echo?q=tls"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.body_text().await.unwrap(), "/echo tls false");
+    }
+}
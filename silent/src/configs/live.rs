@@ -0,0 +1,134 @@
+use arc_swap::ArcSwap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// 可在运行中原子替换的配置
+///
+/// 读取时无锁地获取当前值的 `Arc`，替换后新的请求立即读取到新值，已经持有旧值的请求不受影响。
+/// 克隆的句柄共享同一份配置，注册到 [`Configs`](crate::Configs) 后处理函数与中间件都可以读取，
+/// 也可以通过 [`LiveConfig::subscribe`] 订阅变更。
+/// ```rust
+/// use silent::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Flags {
+///     beta: bool,
+/// }
+///
+/// let flags = LiveConfig::new(Flags { beta: false });
+/// let mut configs = Configs::default();
+/// configs.insert(flags.clone());
+/// let route = Route::new("").get(|req: Request| async move {
+///     let flags = req.get_config::<LiveConfig<Flags>>()?.load();
+///     Ok(if flags.beta { "beta" } else { "stable" })
+/// });
+/// flags.store(Flags { beta: true });
+/// ```
+pub struct LiveConfig<T> {
+    inner: Arc<LiveInner<T>>,
+}
+
+struct LiveInner<T> {
+    value: ArcSwap<T>,
+    changes: watch::Sender<Arc<T>>,
+}
+
+impl<T> Clone for LiveConfig<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LiveConfig<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LiveConfig").field(&self.load()).finish()
+    }
+}
+
+impl<T> LiveConfig<T> {
+    pub fn new(value: T) -> Self {
+        let value = Arc::new(value);
+        Self {
+            inner: Arc::new(LiveInner {
+                value: ArcSwap::new(value.clone()),
+                changes: watch::Sender::new(value),
+            }),
+        }
+    }
+
+    /// 当前的值
+    #[inline]
+    pub fn load(&self) -> Arc<T> {
+        self.inner.value.load_full()
+    }
+
+    /// 替换为新的值并通知订阅者
+    pub fn store(&self, value: T) {
+        self.replace(|_| Arc::new(value));
+    }
+
+    /// 基于当前值计算新的值，并发的更新按顺序执行，不会互相覆盖
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&T) -> T,
+    {
+        self.replace(|current| Arc::new(f(current)));
+    }
+
+    /// 订阅变更，每次替换后接收端都会收到通知
+    pub fn subscribe(&self) -> watch::Receiver<Arc<T>> {
+        self.inner.changes.subscribe()
+    }
+
+    fn replace<F>(&self, f: F)
+    where
+        F: FnOnce(&T) -> Arc<T>,
+    {
+        // 在通知通道的写锁内更新，保证读取到的值与通知的顺序一致
+        self.inner.changes.send_modify(|current| {
+            let value = f(current);
+            self.inner.value.store(value.clone());
+            *current = value;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Configs;
+
+    #[tokio::test]
+    async fn test_live_config() {
+        let live = LiveConfig::new(1u32);
+        let mut configs = Configs::new();
+        configs.insert(live.clone());
+        let mut changes = live.subscribe();
+        let old = live.load();
+
+        live.store(2);
+        assert_eq!(*old, 1);
+        assert_eq!(*configs.clone().get::<LiveConfig<u32>>().unwrap().load(), 2);
+        changes.changed().await.unwrap();
+        assert_eq!(**changes.borrow_and_update(), 2);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let live = live.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        live.update(|value| value + 1);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*live.load(), 802);
+        assert_eq!(**changes.borrow_and_update(), 802);
+    }
+}
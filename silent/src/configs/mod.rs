@@ -1,3 +1,6 @@
+mod live;
+
+pub use live::LiveConfig;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt;
@@ -106,6 +109,38 @@ impl Configs {
             .and_then(|boxed| (boxed as Arc<dyn Any + 'static>).downcast_ref().cloned())
     }
 
+    /// Extends `self` with another `Configs`.
+    ///
+    /// If an instance of a specific type exists in both, the one in `self` is
+    /// overwritten with the one from `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use silent::Configs;
+    /// let mut cfg_a = Configs::new();
+    /// cfg_a.insert(8u8);
+    /// cfg_a.insert(16u16);
+    ///
+    /// let mut cfg_b = Configs::new();
+    /// cfg_b.insert(4u8);
+    /// cfg_b.insert("hello");
+    ///
+    /// cfg_a.extend(cfg_b);
+    /// assert_eq!(cfg_a.len(), 3);
+    /// assert_eq!(cfg_a.get::<u8>(), Some(&4u8));
+    /// assert_eq!(cfg_a.get::<u16>(), Some(&16u16));
+    /// assert_eq!(cfg_a.get::<&'static str>().copied(), Some("hello"));
+    /// ```
+    pub fn extend(&mut self, other: Self) {
+        if let Some(other) = other.map {
+            match &mut self.map {
+                Some(map) => map.extend(*other),
+                None => self.map = Some(other),
+            }
+        }
+    }
+
     /// Clear the `Configs` of all inserted extensions.
     ///
     /// # Example
@@ -1,7 +1,89 @@
-use crate::{Request, Response};
-use cookie::{Cookie, CookieJar};
+use crate::{Configs, Request, Response, Result, SilentError};
+use cookie::{Cookie, CookieJar, Key};
 use http_body::Body;
 
+/// 签名/加密 cookie 使用的密钥集合
+///
+/// 第一个密钥为当前密钥，用于签名与加密；其余为旧密钥，仅用于校验与解密，
+/// 以便在密钥轮换期间已签发的 cookie 仍然有效。
+/// 通过 `Configs` 注册后由 [`CookieExt`] 读取，也可以直接注册单个 [`Key`]。
+/// ```
+/// use silent::prelude::{Configs, CookieKeys, Key};
+/// let mut configs = Configs::default();
+/// configs.insert(CookieKeys::new(Key::generate()).with_old_key(Key::generate()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CookieKeys {
+    keys: Vec<Key>,
+}
+
+impl CookieKeys {
+    /// 使用当前密钥创建
+    pub fn new(key: Key) -> Self {
+        Self { keys: vec![key] }
+    }
+    /// 追加仅用于校验的旧密钥
+    pub fn with_old_key(mut self, key: Key) -> Self {
+        self.keys.push(key);
+        self
+    }
+    /// 轮换密钥，新密钥成为当前密钥，原密钥保留用于校验
+    pub fn rotate(&mut self, key: Key) {
+        self.keys.insert(0, key);
+    }
+    /// 当前用于签名与加密的密钥
+    #[inline]
+    pub fn current(&self) -> &Key {
+        &self.keys[0]
+    }
+    /// 所有密钥，当前密钥在前
+    #[inline]
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub(crate) fn from_configs(configs: &Configs) -> Option<Self> {
+        configs
+            .get::<CookieKeys>()
+            .cloned()
+            .or_else(|| configs.get::<Key>().cloned().map(CookieKeys::new))
+    }
+
+    pub(crate) fn verify(&self, jar: &CookieJar, name: &str) -> Option<Cookie<'static>> {
+        self.keys.iter().find_map(|key| jar.signed(key).get(name))
+    }
+
+    pub(crate) fn decrypt(&self, jar: &CookieJar, name: &str) -> Option<Cookie<'static>> {
+        self.keys.iter().find_map(|key| jar.private(key).get(name))
+    }
+}
+
+impl From<Key> for CookieKeys {
+    fn from(key: Key) -> Self {
+        Self::new(key)
+    }
+}
+
+/// 尚未签名或加密的响应 cookie
+///
+/// 处理器构造的响应不携带配置，由 `CookieMiddleware` 使用请求配置中的密钥完成签名与加密。
+#[derive(Clone, Default)]
+pub(crate) struct PendingCookies {
+    pub(crate) signed: Vec<Cookie<'static>>,
+    pub(crate) private: Vec<Cookie<'static>>,
+}
+
+impl PendingCookies {
+    pub(crate) fn apply(self, keys: &CookieKeys, jar: &mut CookieJar) {
+        for cookie in self.signed {
+            jar.signed_mut(keys.current()).add(cookie);
+        }
+        for cookie in self.private {
+            jar.private_mut(keys.current()).add(cookie);
+        }
+    }
+}
+
 pub trait CookieExt {
     /// Get `CookieJar` reference.
     fn cookies(&self) -> CookieJar;
@@ -9,6 +91,14 @@ pub trait CookieExt {
     fn cookies_mut(&mut self) -> &mut CookieJar;
     /// Get `Cookie` from cookies.
     fn cookie<T: AsRef<str>>(&self, name: T) -> Option<&Cookie<'static>>;
+    /// Get a signed `Cookie` verified with the configured [`CookieKeys`].
+    fn signed_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>>;
+    /// Get a private `Cookie` decrypted with the configured [`CookieKeys`].
+    fn private_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>>;
+    /// Add a `Cookie` signed with the current key.
+    fn add_signed(&mut self, cookie: Cookie<'static>) -> Result<()>;
+    /// Add a `Cookie` encrypted with the current key.
+    fn add_private(&mut self, cookie: Cookie<'static>) -> Result<()>;
 }
 
 impl CookieExt for Request {
@@ -26,6 +116,37 @@ impl CookieExt for Request {
     fn cookie<T: AsRef<str>>(&self, name: T) -> Option<&Cookie<'static>> {
         self.extensions().get::<CookieJar>()?.get(name.as_ref())
     }
+
+    fn signed_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>> {
+        let jar = self.extensions().get::<CookieJar>()?;
+        CookieKeys::from_configs(&self.configs)?.verify(jar, name.as_ref())
+    }
+
+    fn private_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>> {
+        let jar = self.extensions().get::<CookieJar>()?;
+        CookieKeys::from_configs(&self.configs)?.decrypt(jar, name.as_ref())
+    }
+
+    fn add_signed(&mut self, cookie: Cookie<'static>) -> Result<()> {
+        let keys = CookieKeys::from_configs(&self.configs).ok_or(SilentError::ConfigNotFound)?;
+        self.cookies_mut().signed_mut(keys.current()).add(cookie);
+        Ok(())
+    }
+
+    fn add_private(&mut self, cookie: Cookie<'static>) -> Result<()> {
+        let keys = CookieKeys::from_configs(&self.configs).ok_or(SilentError::ConfigNotFound)?;
+        self.cookies_mut().private_mut(keys.current()).add(cookie);
+        Ok(())
+    }
+}
+
+impl<B: Body> Response<B> {
+    fn pending_cookies_mut(&mut self) -> &mut PendingCookies {
+        if self.extensions().get::<PendingCookies>().is_none() {
+            self.extensions_mut().insert(PendingCookies::default());
+        }
+        self.extensions_mut().get_mut().unwrap()
+    }
 }
 
 impl<B: Body> CookieExt for Response<B> {
@@ -43,4 +164,103 @@ impl<B: Body> CookieExt for Response<B> {
     fn cookie<T: AsRef<str>>(&self, name: T) -> Option<&Cookie<'static>> {
         self.extensions().get::<CookieJar>()?.get(name.as_ref())
     }
+
+    fn signed_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>> {
+        let jar = self.extensions().get::<CookieJar>()?;
+        CookieKeys::from_configs(&self.configs)?.verify(jar, name.as_ref())
+    }
+
+    fn private_cookie<T: AsRef<str>>(&self, name: T) -> Option<Cookie<'static>> {
+        let jar = self.extensions().get::<CookieJar>()?;
+        CookieKeys::from_configs(&self.configs)?.decrypt(jar, name.as_ref())
+    }
+
+    /// 响应未携带密钥配置时，cookie 会在 `CookieMiddleware` 中完成签名
+    fn add_signed(&mut self, cookie: Cookie<'static>) -> Result<()> {
+        match CookieKeys::from_configs(&self.configs) {
+            Some(keys) => self.cookies_mut().signed_mut(keys.current()).add(cookie),
+            None => self.pending_cookies_mut().signed.push(cookie),
+        }
+        Ok(())
+    }
+
+    /// 响应未携带密钥配置时，cookie 会在 `CookieMiddleware` 中完成加密
+    fn add_private(&mut self, cookie: Cookie<'static>) -> Result<()> {
+        match CookieKeys::from_configs(&self.configs) {
+            Some(keys) => self.cookies_mut().private_mut(keys.current()).add(cookie),
+            None => self.pending_cookies_mut().private.push(cookie),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_with_keys(keys: CookieKeys) -> Request {
+        let mut req = Request::empty();
+        req.configs_mut().insert(keys);
+        req
+    }
+
+    #[test]
+    fn test_signed_cookie() {
+        let key = Key::generate();
+        let mut req = request_with_keys(CookieKeys::new(key.clone()));
+        req.add_signed(Cookie::new("user", "silent")).unwrap();
+        let signed = req.cookie("user").unwrap().value().to_string();
+        assert_ne!(signed, "silent");
+        assert_eq!(req.signed_cookie("user").unwrap().value(), "silent");
+
+        // 篡改后校验失败
+        req.cookies_mut()
+            .add(Cookie::new("user", signed.replace("silent", "admin")));
+        assert!(req.signed_cookie("user").is_none());
+    }
+
+    #[test]
+    fn test_private_cookie() {
+        let mut req = request_with_keys(CookieKeys::new(Key::generate()));
+        req.add_private(Cookie::new("token", "secret")).unwrap();
+        assert!(!req.cookie("token").unwrap().value().contains("secret"));
+        assert_eq!(req.private_cookie("token").unwrap().value(), "secret");
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old_key = Key::generate();
+        let mut req = request_with_keys(CookieKeys::new(old_key.clone()));
+        req.add_signed(Cookie::new("user", "silent")).unwrap();
+        req.add_private(Cookie::new("token", "secret")).unwrap();
+
+        let mut keys = CookieKeys::new(old_key);
+        keys.rotate(Key::generate());
+        req.configs_mut().insert(keys.clone());
+        assert_eq!(req.signed_cookie("user").unwrap().value(), "silent");
+        assert_eq!(req.private_cookie("token").unwrap().value(), "secret");
+
+        // 移除旧密钥后不再通过校验
+        req.configs_mut()
+            .insert(CookieKeys::new(keys.current().clone()));
+        assert!(req.signed_cookie("user").is_none());
+        assert!(req.private_cookie("token").is_none());
+    }
+
+    #[test]
+    fn test_missing_keys() {
+        let mut req = Request::empty();
+        assert!(req.add_signed(Cookie::new("user", "silent")).is_err());
+        let mut res = Response::empty();
+        assert!(res.add_signed(Cookie::new("user", "silent")).is_ok());
+        assert!(res.cookie("user").is_none());
+        assert_eq!(
+            res.extensions()
+                .get::<PendingCookies>()
+                .unwrap()
+                .signed
+                .len(),
+            1
+        );
+    }
 }
@@ -1,4 +1,5 @@
-use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result, SilentError};
+use crate::cookie::cookie_ext::{CookieKeys, PendingCookies};
+use crate::{CookieExt, Handler, MiddleWareHandler, Next, Request, Response, Result, SilentError};
 use async_trait::async_trait;
 use cookie::{Cookie, CookieJar};
 use http::{StatusCode, header};
@@ -34,7 +35,14 @@ impl MiddleWareHandler for CookieMiddleware {
             }
         }
         req.extensions_mut().insert(jar.clone());
+        let keys = CookieKeys::from_configs(&req.configs);
         let mut res = next.call(req).await?;
+        if let Some(pending) = res.extensions_mut().remove::<PendingCookies>() {
+            let keys = keys.ok_or(SilentError::ConfigNotFound)?;
+            let mut cookie_jar = res.cookies();
+            pending.apply(&keys, &mut cookie_jar);
+            res.extensions_mut().insert(cookie_jar);
+        }
         if let Some(cookie_jar) = res.extensions().get::<CookieJar>() {
             for cookie in cookie_jar.delta().cloned() {
                 jar.add(cookie)
@@ -46,3 +54,30 @@ impl MiddleWareHandler for CookieMiddleware {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{CookieKeys, HandlerAppend, Key, Route};
+
+    #[tokio::test]
+    async fn test_pending_signed_cookie() {
+        let keys = CookieKeys::new(Key::generate());
+        let mut configs = crate::Configs::default();
+        configs.insert(keys.clone());
+        let mut route = Route::new_root();
+        route.push(Route::new("").get(|_req| async {
+            let mut res = Response::text("hello");
+            res.add_signed(Cookie::new("user", "silent"))?;
+            Ok(res)
+        }));
+        route.set_configs(Some(configs));
+        route.check_cookie();
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        let res = route.call(req).await.unwrap();
+        let jar = res.cookies();
+        assert_ne!(jar.get("user").unwrap().value(), "silent");
+        assert_eq!(keys.verify(&jar, "user").unwrap().value(), "silent");
+    }
+}
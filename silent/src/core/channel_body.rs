@@ -0,0 +1,134 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Body, Frame};
+use tokio::sync::{mpsc, oneshot};
+
+use super::res_body::ResBody;
+use crate::error::BoxedError;
+
+/// 通道响应体缓冲的帧数，缓冲已满时发送会等待客户端读取
+///
+/// 限制的是帧的数量而不是字节数，每次 `send_data` 为一帧，缓冲占用的内存取决于每块数据的大小。
+const CHANNEL_CAPACITY: usize = 16;
+
+/// 向 [`Response::channel`](crate::Response::channel) 创建的响应体逐步写入内容
+///
+/// 通常在 `tokio::spawn` 的任务中使用。客户端读取较慢时 `send_data` 会等待，
+/// 客户端断开后返回错误；直接丢弃发送端视为正常结束。
+pub struct BodySender {
+    data: mpsc::Sender<Frame<Bytes>>,
+    abort: oneshot::Sender<()>,
+}
+
+impl BodySender {
+    /// 发送一块数据
+    pub async fn send_data(&mut self, data: impl Into<Bytes>) -> crate::Result<()> {
+        self.send(Frame::data(data.into())).await
+    }
+    /// 发送 trailers 并结束响应体
+    pub async fn send_trailers(mut self, trailers: HeaderMap) -> crate::Result<()> {
+        self.send(Frame::trailers(trailers)).await
+    }
+    /// 中止响应体，客户端会收到连接错误而不是完整的响应
+    pub fn abort(self) {
+        let _ = self.abort.send(());
+    }
+    /// 客户端是否已断开
+    pub fn is_closed(&self) -> bool {
+        self.data.is_closed()
+    }
+
+    async fn send(&mut self, frame: Frame<Bytes>) -> crate::Result<()> {
+        self.data
+            .send(frame)
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe).into())
+    }
+}
+
+pub(crate) fn channel() -> (BodySender, ResBody) {
+    let (data_tx, data_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let (abort_tx, abort_rx) = oneshot::channel();
+    let sender = BodySender {
+        data: data_tx,
+        abort: abort_tx,
+    };
+    let body = ChannelBody {
+        data: data_rx,
+        abort: Some(abort_rx),
+    };
+    (sender, ResBody::Boxed(Box::pin(body)))
+}
+
+struct ChannelBody {
+    data: mpsc::Receiver<Frame<Bytes>>,
+    abort: Option<oneshot::Receiver<()>>,
+}
+
+impl Body for ChannelBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(abort) = self.abort.as_mut() {
+            match Pin::new(abort).poll(cx) {
+                Poll::Ready(Ok(())) => {
+                    self.abort = None;
+                    self.data.close();
+                    return Poll::Ready(Some(Err("response body aborted".into())));
+                }
+                // 发送端被丢弃，不再检查中止
+                Poll::Ready(Err(_)) => self.abort = None,
+                Poll::Pending => {}
+            }
+        }
+        self.data.poll_recv(cx).map(|frame| frame.map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn test_channel() {
+        let (mut sender, body) = channel();
+        tokio::spawn(async move {
+            for chunk in ["a", "b", "c"] {
+                sender.send_data(chunk).await.unwrap();
+            }
+            let mut trailers = HeaderMap::new();
+            trailers.insert("x-count", "3".parse().unwrap());
+            sender.send_trailers(trailers).await.unwrap();
+        });
+        let collected = body.collect().await.unwrap();
+        assert_eq!(collected.trailers().unwrap()["x-count"], "3");
+        assert_eq!(collected.to_bytes(), "abc");
+
+        let (mut sender, body) = channel();
+        sender.send_data("a").await.unwrap();
+        sender.abort();
+        assert!(body.collect().await.is_err());
+
+        // 缓冲已满时等待读取，读取端关闭后返回错误
+        let (mut sender, body) = channel();
+        for _ in 0..CHANNEL_CAPACITY {
+            sender.send_data("a").await.unwrap();
+        }
+        let pending =
+            tokio::time::timeout(std::time::Duration::from_millis(10), sender.send_data("a"));
+        assert!(pending.await.is_err());
+        drop(body);
+        assert!(sender.is_closed());
+        assert!(sender.send_data("a").await.is_err());
+    }
+}
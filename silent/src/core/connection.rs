@@ -1,5 +1,6 @@
+use std::any::Any;
 use tokio::io::{AsyncRead, AsyncWrite};
 
-pub trait Connection: AsyncRead + AsyncWrite + Unpin {}
+pub trait Connection: AsyncRead + AsyncWrite + Unpin + Any {}
 
-impl<S> Connection for S where S: AsyncRead + AsyncWrite + Unpin {}
+impl<S> Connection for S where S: AsyncRead + AsyncWrite + Unpin + 'static {}
@@ -1,6 +1,6 @@
+use crate::core::multipart::{Multipart, MultipartField, MultipartLimits};
 use crate::core::req_body::ReqBody;
 use crate::header::{CONTENT_TYPE, HeaderMap};
-use crate::multer::{Field, Multipart};
 use crate::{SilentError, StatusCode};
 use multimap::MultiMap;
 use std::ffi::OsStr;
@@ -34,23 +34,31 @@ impl FormData {
     }
 
     /// Parse MIME `multipart/*` information from a stream as a [`FormData`].
-    pub(crate) async fn read(headers: &HeaderMap, body: ReqBody) -> Result<FormData, SilentError> {
+    ///
+    /// Built on [`Multipart`], so the given limits apply while reading.
+    pub(crate) async fn read(
+        headers: &HeaderMap,
+        body: ReqBody,
+        limits: MultipartLimits,
+    ) -> Result<FormData, SilentError> {
         let mut form_data = FormData::new();
-        if let Some(boundary) = headers
+        if headers
             .get(CONTENT_TYPE)
             .and_then(|ct| ct.to_str().ok())
             .and_then(|ct| multer::parse_boundary(ct).ok())
+            .is_none()
         {
-            let mut multipart = Multipart::new(body, boundary);
-            while let Some(mut field) = multipart.next_field().await? {
-                if let Some(name) = field.name().map(|s| s.to_owned()) {
-                    if field.headers().get(CONTENT_TYPE).is_some() {
-                        form_data
-                            .files
-                            .insert(name, FilePart::create(&mut field).await?);
-                    } else {
-                        form_data.fields.insert(name, field.text().await?);
-                    }
+            return Ok(form_data);
+        }
+        let mut multipart = Multipart::new(headers, body, limits)?;
+        while let Some(mut field) = multipart.next_field().await? {
+            if let Some(name) = field.name().map(|s| s.to_owned()) {
+                if field.is_file() {
+                    form_data
+                        .files
+                        .insert(name, FilePart::create(&mut field).await?);
+                } else {
+                    form_data.fields.insert(name, field.text().await?);
                 }
             }
         }
@@ -129,7 +137,7 @@ impl FilePart {
     /// Create a new temporary FilePart (when created this way, the file will be
     /// deleted once the FilePart object goes out of scope).
     #[inline]
-    pub async fn create(field: &mut Field<'_>) -> Result<FilePart, SilentError> {
+    pub async fn create(field: &mut MultipartField) -> Result<FilePart, SilentError> {
         // Set up a file to capture the contents.
         let mut path = tokio::task::spawn_blocking(|| {
             Builder::new().prefix("silent_http_multipart").tempdir()
@@ -152,6 +160,8 @@ impl FilePart {
             size += chunk.len() as u64;
             file.write_all(&chunk).await?;
         }
+        // tokio 的文件写入在后台完成，需要等待写入结束后再交给处理器读取
+        file.flush().await?;
         Ok(FilePart {
             name,
             headers: field.headers().to_owned(),
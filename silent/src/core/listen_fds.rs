@@ -0,0 +1,444 @@
+use super::listener::Listener;
+use std::ffi::{CString, OsStr, OsString};
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 继承的监听 socket 起始 fd，与 systemd 约定一致
+const LISTEN_FDS_START: RawFd = 3;
+
+/// 继承的 fd 只能被接管一次
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+impl Listener {
+    /// 接管通过 `LISTEN_FDS` 继承的监听 socket
+    ///
+    /// 支持 systemd socket activation 与 [`ServerHandle::handoff`](crate::prelude::ServerHandle::handoff)
+    /// 移交的监听器。设置了 `LISTEN_PID` 且与当前进程不符时忽略；
+    /// 继承的 fd 只会被接管一次，之后的调用返回空列表。不修改进程的环境变量，
+    /// 本进程启动的其他子进程因 `LISTEN_PID` 不符而忽略这些变量。
+    /// 任一 fd 不是监听 socket 时返回错误，且不会关闭任何 fd。需要在 tokio 运行时中调用。
+    pub fn from_env() -> io::Result<Vec<Listener>> {
+        if TAKEN.swap(true, Ordering::AcqRel) {
+            return Ok(vec![]);
+        }
+        if let Ok(pid) = std::env::var("LISTEN_PID")
+            && pid.parse::<u32>() != Ok(std::process::id())
+        {
+            return Ok(vec![]);
+        }
+        let Ok(count) = std::env::var("LISTEN_FDS") else {
+            return Ok(vec![]);
+        };
+        let count = count
+            .parse::<RawFd>()
+            .ok()
+            .filter(|count| *count >= 0)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid LISTEN_FDS: {count}"),
+                )
+            })?;
+        let fds = LISTEN_FDS_START..LISTEN_FDS_START + count;
+        // 全部检查通过后才接管，失败时不关闭任何 fd
+        for fd in fds.clone() {
+            check_listener_fd(fd)?;
+        }
+        fds.map(|fd| {
+            // 避免继承的 fd 泄漏给本进程启动的其他子进程
+            if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Listener::from_fd(unsafe { OwnedFd::from_raw_fd(fd) })
+        })
+        .collect()
+    }
+
+    /// 从监听 socket 的 fd 创建监听器，根据地址族区分 TCP 与 unix socket
+    pub fn from_fd(fd: OwnedFd) -> io::Result<Self> {
+        match check_listener_fd(fd.as_raw_fd())? {
+            libc::AF_INET | libc::AF_INET6 => {
+                let listener = std::net::TcpListener::from(fd);
+                listener.set_nonblocking(true)?;
+                Ok(Listener::TcpListener(tokio::net::TcpListener::from_std(
+                    listener,
+                )?))
+            }
+            libc::AF_UNIX => {
+                let listener = std::os::unix::net::UnixListener::from(fd);
+                listener.set_nonblocking(true)?;
+                Ok(Listener::UnixListener(tokio::net::UnixListener::from_std(
+                    listener,
+                )?))
+            }
+            family => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported socket family: {family}"),
+            )),
+        }
+    }
+}
+
+/// 检查 fd 是否为 TCP 或 unix 流式 socket，返回地址族，不获取 fd 的所有权
+fn check_listener_fd(fd: RawFd) -> io::Result<libc::c_int> {
+    if socket_type(fd)? != libc::SOCK_STREAM {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("fd {fd} is not a stream socket"),
+        ));
+    }
+    let family = socket_family(fd)?;
+    if !matches!(family, libc::AF_INET | libc::AF_INET6 | libc::AF_UNIX) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported socket family: {family}"),
+        ));
+    }
+    Ok(family)
+}
+
+fn socket_type(fd: RawFd) -> io::Result<libc::c_int> {
+    let mut value: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            &mut value as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(value)
+}
+
+fn socket_family(fd: RawFd) -> io::Result<libc::c_int> {
+    let mut addr: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockname(
+            fd,
+            &mut addr as *mut libc::sockaddr_storage as *mut libc::sockaddr,
+            &mut len,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(addr.ss_family as libc::c_int)
+}
+
+/// 服务器监听 socket 的 fd，停止接受连接后清空
+#[derive(Clone)]
+pub(crate) struct ListenFds(Arc<Mutex<Option<Vec<RawFd>>>>);
+
+impl ListenFds {
+    pub(crate) fn new(fds: Vec<RawFd>) -> Self {
+        Self(Arc::new(Mutex::new(Some(fds))))
+    }
+
+    /// 监听器关闭前调用，之后不再允许移交
+    pub(crate) fn close(&self) {
+        self.0.lock().unwrap().take();
+    }
+
+    /// 启动继承全部监听 socket 的子进程
+    pub(crate) fn spawn(&self, mut command: Command) -> io::Result<Child> {
+        // 持有锁期间监听器不会被关闭
+        let fds = self.0.lock().unwrap();
+        let Some(fds) = fds.as_ref() else {
+            return Err(io::Error::other(
+                "server is no longer accepting connections",
+            ));
+        };
+        let count = fds.len() as RawFd;
+        // 先复制到目标范围之外，避免子进程中 dup2 覆盖尚未移动的 fd
+        let dups = fds
+            .iter()
+            .map(|fd| {
+                let fd =
+                    unsafe { libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, LISTEN_FDS_START + count) };
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let raw_fds: Vec<RawFd> = dups.iter().map(AsRawFd::as_raw_fd).collect();
+        let mut exec = Exec::new(&command, count)?;
+        unsafe {
+            command.pre_exec(move || {
+                for (target, fd) in (LISTEN_FDS_START..).zip(raw_fds.iter()) {
+                    // dup2 得到的 fd 不带 CLOEXEC，会保留到 exec 之后
+                    if libc::dup2(*fd, target) < 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Err(exec.exec())
+            });
+        }
+        command.spawn()
+    }
+}
+
+/// 在子进程中直接 exec 所需的参数与环境变量
+///
+/// `LISTEN_PID` 需要子进程的 pid，只能在 fork 之后写入；而标准库在 `pre_exec`
+/// 之后才替换环境变量，因此在 fork 前准备好全部数据，由 `pre_exec` 填入 pid 后调用 `execve`。
+/// fork 之后只写入预先分配的缓冲区，不分配内存。不支持 `Command::env_clear`。
+struct Exec {
+    program: CString,
+    _args: Vec<CString>,
+    argv: Vec<*const libc::c_char>,
+    _envs: Vec<CString>,
+    pid: Box<[u8; 32]>,
+    envp: Vec<*const libc::c_char>,
+}
+
+// SAFETY: 指针均指向 `Exec` 自身持有的堆内存，只在子进程中使用
+unsafe impl Send for Exec {}
+unsafe impl Sync for Exec {}
+
+impl Exec {
+    const LISTEN_PID: &'static [u8] = b"LISTEN_PID=";
+
+    fn new(command: &Command, count: RawFd) -> io::Result<Self> {
+        let mut envs: Vec<(OsString, OsString)> = std::env::vars_os().collect();
+        for (key, value) in command.get_envs() {
+            envs.retain(|(k, _)| k != key);
+            if let Some(value) = value {
+                envs.push((key.to_owned(), value.to_owned()));
+            }
+        }
+        // 本进程继承的值不传给子进程，按子进程的 pid 与 fd 数量重新设置
+        envs.retain(|(k, _)| {
+            !matches!(
+                k.to_str(),
+                Some("LISTEN_PID" | "LISTEN_FDS" | "LISTEN_FDNAMES")
+            )
+        });
+        envs.push(("LISTEN_FDS".into(), count.to_string().into()));
+        let program = resolve_program(
+            command.get_program(),
+            envs.iter()
+                .find(|(k, _)| k == "PATH")
+                .map(|(_, v)| v.as_os_str()),
+        )?;
+        let args = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(cstring)
+            .collect::<io::Result<Vec<_>>>()?;
+        let envs = envs
+            .into_iter()
+            .map(|(mut key, value)| {
+                key.push("=");
+                key.push(value);
+                cstring(&key)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut pid = Box::new([0u8; 32]);
+        pid[..Self::LISTEN_PID.len()].copy_from_slice(Self::LISTEN_PID);
+        let argv = args
+            .iter()
+            .map(|arg| arg.as_ptr())
+            .chain(std::iter::once(std::ptr::null()))
+            .collect();
+        let envp = envs
+            .iter()
+            .map(|env| env.as_ptr())
+            .chain([pid.as_ptr() as *const libc::c_char, std::ptr::null()])
+            .collect();
+        Ok(Self {
+            program,
+            _args: args,
+            argv,
+            _envs: envs,
+            pid,
+            envp,
+        })
+    }
+
+    /// 在 fork 后的子进程中调用，成功时不返回
+    fn exec(&mut self) -> io::Error {
+        let mut digits = [0u8; 10];
+        let mut pid = unsafe { libc::getpid() } as u32;
+        let mut len = 0;
+        loop {
+            digits[len] = b'0' + (pid % 10) as u8;
+            len += 1;
+            pid /= 10;
+            if pid == 0 {
+                break;
+            }
+        }
+        let start = Self::LISTEN_PID.len();
+        for (i, digit) in digits[..len].iter().rev().enumerate() {
+            self.pid[start + i] = *digit;
+        }
+        self.pid[start + len] = 0;
+        unsafe {
+            libc::execve(
+                self.program.as_ptr(),
+                self.argv.as_ptr(),
+                self.envp.as_ptr(),
+            )
+        };
+        io::Error::last_os_error()
+    }
+}
+
+fn cstring(value: &OsStr) -> io::Result<CString> {
+    CString::new(value.as_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nul byte found in command argument or environment",
+        )
+    })
+}
+
+/// 与 `execvp` 一样在 `PATH` 中查找不含 `/` 的程序名
+fn resolve_program(program: &OsStr, path: Option<&OsStr>) -> io::Result<CString> {
+    if program.as_bytes().contains(&b'/') {
+        return cstring(program);
+    }
+    std::env::split_paths(path.unwrap_or(OsStr::new("/usr/bin:/bin")))
+        .map(|dir| dir.join(program))
+        .find(|candidate| {
+            std::fs::metadata(candidate)
+                .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("program not found: {}", program.to_string_lossy()),
+            )
+        })
+        .and_then(|candidate| cstring(candidate.as_os_str()))
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::core::listener::Listen;
+    use crate::core::socket_addr::SocketAddr;
+    use crate::prelude::*;
+    use std::process::Stdio;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn get(addr: std::net::SocketAddr) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_listener_from_fd() {
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let listener = Listener::from_fd(OwnedFd::from(tcp)).unwrap();
+        assert!(matches!(listener.local_addr().unwrap(), SocketAddr::Tcp(a) if a == addr));
+
+        let path = std::env::temp_dir().join(format!("silent-fd-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let listener = Listener::from_fd(OwnedFd::from(unix)).unwrap();
+        assert!(matches!(
+            listener.local_addr().unwrap(),
+            SocketAddr::Unix(_)
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert!(Listener::from_fd(OwnedFd::from(udp)).is_err());
+    }
+
+    #[test]
+    fn test_check_listener_fd() {
+        // 检查失败时不会关闭 fd
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert!(check_listener_fd(udp.as_raw_fd()).is_err());
+        assert!(unsafe { libc::fcntl(udp.as_raw_fd(), libc::F_GETFD) } >= 0);
+        let file = std::fs::File::open(std::env::current_exe().unwrap()).unwrap();
+        assert!(check_listener_fd(file.as_raw_fd()).is_err());
+        assert!(unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFD) } >= 0);
+    }
+
+    /// 由 `test_handoff` 以子进程方式启动，单独运行时直接返回
+    #[tokio::test]
+    async fn test_handoff_child() {
+        if std::env::var("SILENT_TEST_HANDOFF").is_err() {
+            return;
+        }
+        assert_eq!(
+            std::env::var("LISTEN_PID").unwrap(),
+            std::process::id().to_string()
+        );
+        assert_eq!(std::env::var("LISTEN_FDS").unwrap(), "1");
+        let route = Route::new("").get(|_req| async { Ok("child") });
+        let handle = Server::new().bind_inherited().start(route).unwrap();
+        assert_eq!(handle.local_addrs().len(), 1);
+        // 环境变量保持不变，继承的 fd 不会被再次接管
+        assert_eq!(std::env::var("LISTEN_FDS").unwrap(), "1");
+        assert!(Listener::from_env().unwrap().is_empty());
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handoff() {
+        let route = Route::new("").get(|_req| async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok("parent")
+        });
+        let handle = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(route)
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let in_flight = tokio::spawn(get(addr));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args([
+                "core::listen_fds::tests::test_handoff_child",
+                "--exact",
+                "--nocapture",
+            ])
+            .env("SILENT_TEST_HANDOFF", "1")
+            .stdout(Stdio::null());
+        let mut child = handle.handoff(command).unwrap();
+
+        // 移交前的请求由原进程处理完成
+        assert!(in_flight.await.unwrap().ends_with("parent"));
+        tokio::time::timeout(Duration::from_secs(5), handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+        // 原进程退出后由新进程继续接受连接
+        let response = tokio::time::timeout(Duration::from_secs(10), get(addr))
+            .await
+            .unwrap();
+        assert!(response.ends_with("child"));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
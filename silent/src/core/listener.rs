@@ -1,10 +1,15 @@
 use super::socket_addr::SocketAddr;
 use super::stream::Stream;
 use crate::core::connection::Connection;
+use crate::route::Route;
+#[cfg(feature = "server")]
+use crate::service::HttpConfig;
 use futures_util::StreamExt;
 use futures_util::stream::FuturesUnordered;
 use std::io::Result;
 #[cfg(not(target_os = "windows"))]
+use std::os::fd::{AsRawFd, RawFd};
+#[cfg(not(target_os = "windows"))]
 use std::path::Path;
 use std::pin::Pin;
 #[cfg(feature = "tls")]
@@ -14,9 +19,28 @@ pub type AcceptFuture<'a> = Pin<
     Box<dyn Future<Output = Result<(Box<dyn Connection + Send + Sync>, SocketAddr)>> + Send + 'a>,
 >;
 
+pub type HandshakeFuture =
+    Pin<Box<dyn Future<Output = Result<Box<dyn Connection + Send + Sync>>> + Send + 'static>>;
+
 pub trait Listen: Send + Sync {
     fn accept(&self) -> AcceptFuture;
+    /// 只接受底层连接，TLS 等握手延后到 [`Listen::handshake`]
+    ///
+    /// 服务器通过该方法接受连接，并在连接任务中握手，避免慢速客户端阻塞监听器。
+    /// 默认与 [`Listen::accept`] 相同。
+    fn accept_stream(&self) -> AcceptFuture<'_> {
+        self.accept()
+    }
+    /// 完成 [`Listen::accept_stream`] 返回连接的握手，默认直接返回连接
+    fn handshake(&self, stream: Box<dyn Connection + Send + Sync>) -> HandshakeFuture {
+        Box::pin(std::future::ready(Ok(stream)))
+    }
     fn local_addr(&self) -> Result<SocketAddr>;
+    /// 监听 socket 的 fd，用于向新进程移交监听器，不支持移交时返回 `None`
+    #[cfg(not(target_os = "windows"))]
+    fn raw_fd(&self) -> Option<RawFd> {
+        None
+    }
 }
 
 pub enum Listener {
@@ -92,6 +116,14 @@ impl Listen for Listener {
             Listener::UnixListener(listener) => Ok(SocketAddr::Unix(listener.local_addr()?.into())),
         }
     }
+
+    #[cfg(not(target_os = "windows"))]
+    fn raw_fd(&self) -> Option<RawFd> {
+        match self {
+            Listener::TcpListener(listener) => Some(listener.as_raw_fd()),
+            Listener::UnixListener(listener) => Some(listener.as_raw_fd()),
+        }
+    }
 }
 
 #[cfg(feature = "tls")]
@@ -124,13 +156,96 @@ impl Listen for TlsListener {
         Box::pin(accept_future)
     }
 
+    fn accept_stream(&self) -> AcceptFuture<'_> {
+        self.listener.accept()
+    }
+
+    fn handshake(&self, stream: Box<dyn Connection + Send + Sync>) -> HandshakeFuture {
+        let acceptor = self.acceptor.clone();
+        Box::pin(async move {
+            let tls_stream = acceptor.accept(stream).await?;
+            Ok(Box::new(tls_stream) as Box<dyn Connection + Send + Sync>)
+        })
+    }
+
     fn local_addr(&self) -> Result<SocketAddr> {
         self.listener.local_addr()?.tls()
     }
+
+    #[cfg(not(target_os = "windows"))]
+    fn raw_fd(&self) -> Option<RawFd> {
+        self.listener.raw_fd()
+    }
+}
+
+/// 待绑定的监听器，绑定延迟到 [`ListenersBuilder::listen`] 时进行，以便返回绑定错误
+enum PendingListener {
+    Tcp(std::net::SocketAddr),
+    #[cfg(not(target_os = "windows"))]
+    Unix(std::path::PathBuf),
+    /// 使用 TLS 的 TCP 监听器
+    #[cfg(feature = "tls")]
+    Tls(std::net::SocketAddr, crate::core::tls::TlsConfig),
+    /// 通过 `LISTEN_FDS` 继承的监听器，没有继承时绑定备用地址
+    #[cfg(not(target_os = "windows"))]
+    Inherited(Option<std::net::SocketAddr>),
+    Listener(Box<dyn Listen + Send + Sync + 'static>),
+}
+
+fn bind_tcp(addr: std::net::SocketAddr) -> Result<Listener> {
+    let listener = std::net::TcpListener::bind(addr)
+        .map_err(|e| std::io::Error::new(e.kind(), format!("failed to bind {addr}: {e}")))?;
+    listener.set_nonblocking(true)?;
+    Ok(Listener::TcpListener(tokio::net::TcpListener::from_std(
+        listener,
+    )?))
+}
+
+impl PendingListener {
+    fn bind(self) -> Result<Vec<Box<dyn Listen + Send + Sync + 'static>>> {
+        match self {
+            #[cfg(not(target_os = "windows"))]
+            PendingListener::Inherited(fallback) => {
+                let listeners = Listener::from_env()?;
+                match fallback {
+                    Some(addr) if listeners.is_empty() => PendingListener::Tcp(addr).bind(),
+                    _ => Ok(listeners
+                        .into_iter()
+                        .map(|listener| Box::new(listener) as Box<dyn Listen + Send + Sync>)
+                        .collect()),
+                }
+            }
+            PendingListener::Tcp(addr) => Ok(vec![Box::new(bind_tcp(addr)?)]),
+            #[cfg(not(target_os = "windows"))]
+            PendingListener::Unix(path) => {
+                let listener = std::os::unix::net::UnixListener::bind(&path).map_err(|e| {
+                    std::io::Error::new(e.kind(), format!("failed to bind {}: {e}", path.display()))
+                })?;
+                listener.set_nonblocking(true)?;
+                Ok(vec![Box::new(Listener::UnixListener(
+                    tokio::net::UnixListener::from_std(listener)?,
+                ))])
+            }
+            #[cfg(feature = "tls")]
+            PendingListener::Tls(addr, config) => {
+                let acceptor = crate::core::tls::ReloadableTlsAcceptor::new(config)?;
+                Ok(vec![Box::new(bind_tcp(addr)?.tls_reloadable(acceptor))])
+            }
+            PendingListener::Listener(listener) => Ok(vec![listener]),
+        }
+    }
+}
+
+/// 监听器独立的设置，未设置时使用服务器的默认值
+#[derive(Clone, Default)]
+pub(crate) struct ListenerOptions {
+    #[cfg(feature = "server")]
+    pub(crate) http_config: Option<HttpConfig>,
+    pub(crate) route: Option<Route>,
 }
 
 pub(crate) struct ListenersBuilder {
-    listeners: Vec<Box<dyn Listen + Send + Sync + 'static>>,
+    listeners: Vec<(PendingListener, ListenerOptions)>,
 }
 
 impl ListenersBuilder {
@@ -139,35 +254,78 @@ impl ListenersBuilder {
     }
 
     pub fn add_listener(&mut self, listener: Box<dyn Listen + Send + Sync>) {
-        self.listeners.push(listener);
+        self.listeners.push((
+            PendingListener::Listener(listener),
+            ListenerOptions::default(),
+        ));
     }
 
     pub fn bind(&mut self, addr: std::net::SocketAddr) {
-        self.listeners.push(Box::new(Listener::from(
-            std::net::TcpListener::bind(addr).expect("failed to bind listener"),
-        )));
+        self.listeners
+            .push((PendingListener::Tcp(addr), ListenerOptions::default()));
     }
 
     #[cfg(not(target_os = "windows"))]
     pub fn bind_unix<P: AsRef<Path>>(&mut self, path: P) {
-        self.listeners.push(Box::new(Listener::from(
-            std::os::unix::net::UnixListener::bind(path).expect("failed to bind listener"),
-        )));
+        self.listeners.push((
+            PendingListener::Unix(path.as_ref().to_path_buf()),
+            ListenerOptions::default(),
+        ));
+    }
+
+    /// 绑定使用 TLS 的地址，证书在绑定时加载
+    #[cfg(feature = "tls")]
+    pub fn bind_tls(&mut self, addr: std::net::SocketAddr, config: crate::core::tls::TlsConfig) {
+        self.listeners.push((
+            PendingListener::Tls(addr, config),
+            ListenerOptions::default(),
+        ));
+    }
+
+    /// 添加继承的监听器，`fallback` 为没有继承监听器时绑定的地址
+    #[cfg(not(target_os = "windows"))]
+    pub fn bind_inherited(&mut self, fallback: Option<std::net::SocketAddr>) {
+        self.listeners.push((
+            PendingListener::Inherited(fallback),
+            ListenerOptions::default(),
+        ));
     }
+
+    /// 为最近添加的监听器设置独立的 HTTP 参数
+    #[cfg(feature = "server")]
+    pub fn set_http_config(&mut self, config: HttpConfig) {
+        if let Some((_, options)) = self.listeners.last_mut() {
+            options.http_config = Some(config);
+        }
+    }
+
+    /// 为最近添加的监听器设置独立的路由
+    pub fn set_route(&mut self, route: Route) {
+        if let Some((_, options)) = self.listeners.last_mut() {
+            options.route = Some(route);
+        }
+    }
+
+    /// 绑定所有监听器，未设置监听器时绑定 `127.0.0.1:0`
     pub fn listen(mut self) -> Result<Listeners> {
         if self.listeners.is_empty() {
-            self.listeners.push(Box::new(Listener::from(
-                std::net::TcpListener::bind("127.0.0.1:0").expect("failed to bind listener"),
-            )));
+            self.bind(std::net::SocketAddr::from(([127, 0, 0, 1], 0)));
         }
-        let local_addrs = self
-            .listeners
+        let mut listeners = Vec::with_capacity(self.listeners.len());
+        let mut options = Vec::with_capacity(self.listeners.len());
+        for (listener, listener_options) in self.listeners {
+            for listener in listener.bind()? {
+                listeners.push(listener);
+                options.push(listener_options.clone());
+            }
+        }
+        let local_addrs = listeners
             .iter()
             .flat_map(|listener| listener.local_addr())
             .collect();
-        let listeners = self.listeners;
         Ok(Listeners {
             listeners,
+            options,
             local_addrs,
         })
     }
@@ -175,28 +333,52 @@ impl ListenersBuilder {
 
 pub(crate) struct Listeners {
     listeners: Vec<Box<dyn Listen + Send + Sync + 'static>>,
+    options: Vec<ListenerOptions>,
     local_addrs: Vec<SocketAddr>,
 }
 
+/// 接受连接的结果，附带监听器序号
+pub(crate) type Accepted = (
+    usize,
+    Result<(Box<dyn Connection + Send + Sync>, SocketAddr)>,
+);
+
 impl Listeners {
-    pub(crate) async fn accept(
-        &mut self,
-    ) -> Option<Result<(Box<dyn Connection + Send + Sync>, SocketAddr)>> {
-        let mut listener_futures: FuturesUnordered<AcceptFuture<'_>> = self
+    /// 接受任一监听器的底层连接，握手需要通过 [`Listeners::handshake`] 完成
+    pub(crate) async fn accept(&self) -> Option<Accepted> {
+        let mut listener_futures: FuturesUnordered<_> = self
             .listeners
             .iter()
-            .map(|listener| {
-                let fut: AcceptFuture<'_> = Box::pin(async move {
-                    let listener = listener.as_ref();
-                    listener.accept().await
-                });
-                fut
-            })
+            .enumerate()
+            .map(|(index, listener)| async move { (index, listener.accept_stream().await) })
             .collect();
         listener_futures.next().await
     }
 
+    /// 由接受连接的监听器完成握手
+    pub(crate) fn handshake(
+        &self,
+        index: usize,
+        stream: Box<dyn Connection + Send + Sync>,
+    ) -> HandshakeFuture {
+        self.listeners[index].handshake(stream)
+    }
+
     pub(crate) fn local_addrs(&self) -> &Vec<SocketAddr> {
         &self.local_addrs
     }
+
+    /// 可移交给新进程的监听 socket
+    #[cfg(not(target_os = "windows"))]
+    pub(crate) fn raw_fds(&self) -> Vec<RawFd> {
+        self.listeners
+            .iter()
+            .filter_map(|listener| listener.raw_fd())
+            .collect()
+    }
+
+    /// 各监听器独立的设置，按监听器序号排列
+    pub(crate) fn options(&self) -> &[ListenerOptions] {
+        &self.options
+    }
 }
@@ -1,9 +1,15 @@
 pub mod adapt;
 
+#[cfg(feature = "server")]
+pub(crate) mod channel_body;
 pub(crate) mod connection;
 #[cfg(feature = "multipart")]
 pub(crate) mod form;
+#[cfg(not(target_os = "windows"))]
+pub(crate) mod listen_fds;
 pub(crate) mod listener;
+#[cfg(feature = "multipart")]
+pub(crate) mod multipart;
 pub(crate) mod next;
 pub(crate) mod path_param;
 pub(crate) mod req_body;
@@ -14,3 +20,5 @@ pub(crate) mod response;
 mod serde;
 pub(crate) mod socket_addr;
 pub(crate) mod stream;
+#[cfg(feature = "tls")]
+pub(crate) mod tls;
@@ -0,0 +1,385 @@
+use crate::core::req_body::ReqBody;
+use crate::header::{CONTENT_TYPE, HeaderMap};
+use crate::{Result, SilentError, StatusCode};
+use bytes::{Bytes, BytesMut};
+use futures_util::stream::{BoxStream, Stream, StreamExt, TryStreamExt};
+use mime::Mime;
+use multer::{Constraints, SizeLimit};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// `multipart/form-data` 请求的限制，超出大小或数量时返回 `413`，文件类型不允许时返回 `415`
+///
+/// 默认不做限制。放入路由或服务器的 [`Configs`](crate::Configs) 后对
+/// [`Request::multipart`](crate::Request::multipart) 与 [`Request::form_data`](crate::Request::form_data) 生效。
+/// ```
+/// use silent::prelude::*;
+/// let limits = MultipartLimits::new()
+///     .total_size(20 * 1024 * 1024)
+///     .file_size(10 * 1024 * 1024)
+///     .field_size(64 * 1024)
+///     .fields(16)
+///     .allowed_types(["image/*", "application/pdf"]);
+/// let mut configs = Configs::new();
+/// configs.insert(limits);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MultipartLimits {
+    total_size: Option<u64>,
+    file_size: Option<u64>,
+    field_size: Option<u64>,
+    fields: Option<usize>,
+    allowed_types: Option<Vec<Mime>>,
+}
+
+impl MultipartLimits {
+    /// 不做任何限制
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 整个请求体的最大字节数
+    pub fn total_size(mut self, limit: u64) -> Self {
+        self.total_size = Some(limit);
+        self
+    }
+    /// 单个文件的最大字节数
+    pub fn file_size(mut self, limit: u64) -> Self {
+        self.file_size = Some(limit);
+        self
+    }
+    /// 单个文本字段的最大字节数
+    pub fn field_size(mut self, limit: u64) -> Self {
+        self.field_size = Some(limit);
+        self
+    }
+    /// 字段数量上限，包括文件
+    pub fn fields(mut self, limit: usize) -> Self {
+        self.fields = Some(limit);
+        self
+    }
+    /// 允许上传的文件类型，支持 `image/*` 形式的通配
+    ///
+    /// 无法解析的类型会被忽略。
+    pub fn allowed_types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.allowed_types = Some(
+            types
+                .into_iter()
+                .filter_map(|mime| mime.as_ref().parse().ok())
+                .collect(),
+        );
+        self
+    }
+
+    fn allows(&self, content_type: &Mime) -> bool {
+        self.allowed_types.as_ref().is_none_or(|types| {
+            types.iter().any(|allowed| {
+                allowed.type_() == content_type.type_()
+                    && (allowed.subtype() == mime::STAR
+                        || allowed.subtype() == content_type.subtype())
+            })
+        })
+    }
+}
+
+/// 流式读取的 `multipart/form-data` 请求体，按顺序产出 [`MultipartField`]
+///
+/// 字段内容在读取时才从连接中接收，可以直接写入对象存储或边接收边计算摘要。
+/// 读取下一个字段前需要读完或丢弃当前字段。
+/// ```
+/// use futures_util::TryStreamExt;
+/// use silent::prelude::*;
+///
+/// async fn upload(mut req: Request) -> Result<String> {
+///     let mut multipart = req.multipart()?;
+///     let mut received = vec![];
+///     while let Some(mut field) = multipart.try_next().await? {
+///         let mut size = 0;
+///         while let Some(chunk) = field.chunk().await? {
+///             size += chunk.len();
+///         }
+///         received.push(format!("{}: {size}", field.name().unwrap_or_default()));
+///     }
+///     Ok(received.join("\n"))
+/// }
+/// ```
+pub struct Multipart {
+    fields: BoxStream<'static, Result<MultipartField>>,
+}
+
+struct State {
+    inner: multer::Multipart<'static>,
+    limits: MultipartLimits,
+    count: usize,
+}
+
+impl Multipart {
+    pub(crate) fn new(headers: &HeaderMap, body: ReqBody, limits: MultipartLimits) -> Result<Self> {
+        let boundary = headers
+            .get(CONTENT_TYPE)
+            .and_then(|ct| ct.to_str().ok())
+            .and_then(|ct| multer::parse_boundary(ct).ok())
+            .ok_or(SilentError::ContentTypeError)?;
+        let mut size_limit = SizeLimit::new();
+        if let Some(limit) = limits.total_size {
+            size_limit = size_limit.whole_stream(limit);
+        }
+        let inner = multer::Multipart::with_constraints(
+            body,
+            boundary,
+            Constraints::new().size_limit(size_limit),
+        );
+        let state = State {
+            inner,
+            limits,
+            count: 0,
+        };
+        let fields = futures_util::stream::try_unfold(state, |mut state| async move {
+            let Some(field) = state.inner.next_field().await.map_err(multer_error)? else {
+                return Ok(None);
+            };
+            state.count += 1;
+            if state.limits.fields.is_some_and(|limit| state.count > limit) {
+                return Err(SilentError::business_error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "too many multipart fields".to_string(),
+                ));
+            }
+            let field = MultipartField::new(field, &state.limits)?;
+            Ok(Some((field, state)))
+        });
+        Ok(Self {
+            fields: fields.boxed(),
+        })
+    }
+
+    /// 读取下一个字段，请求体结束时返回 `None`
+    pub async fn next_field(&mut self) -> Result<Option<MultipartField>> {
+        self.fields.try_next().await
+    }
+}
+
+impl Stream for Multipart {
+    type Item = Result<MultipartField>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.fields.poll_next_unpin(cx)
+    }
+}
+
+/// `multipart/form-data` 中的单个字段，内容以流的方式读取
+pub struct MultipartField {
+    inner: multer::Field<'static>,
+    limit: Option<u64>,
+    read: u64,
+}
+
+impl MultipartField {
+    fn new(inner: multer::Field<'static>, limits: &MultipartLimits) -> Result<Self> {
+        let is_file = inner.file_name().is_some() || inner.content_type().is_some();
+        if is_file {
+            let content_type = inner
+                .content_type()
+                .cloned()
+                .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+            if !limits.allows(&content_type) {
+                return Err(SilentError::business_error(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    format!("file type `{content_type}` is not allowed"),
+                ));
+            }
+        }
+        Ok(Self {
+            limit: if is_file {
+                limits.file_size
+            } else {
+                limits.field_size
+            },
+            inner,
+            read: 0,
+        })
+    }
+    /// 字段名
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+    /// 上传文件的文件名
+    #[inline]
+    pub fn file_name(&self) -> Option<&str> {
+        self.inner.file_name()
+    }
+    /// 字段的 `content-type`
+    #[inline]
+    pub fn content_type(&self) -> Option<&Mime> {
+        self.inner.content_type()
+    }
+    /// 字段的头部
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+    /// 是否为文件，即带有文件名或 `content-type`
+    #[inline]
+    pub fn is_file(&self) -> bool {
+        self.file_name().is_some() || self.content_type().is_some()
+    }
+    /// 字段在请求中的序号，从 0 开始
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+    /// 读取下一块内容，字段结束时返回 `None`
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>> {
+        self.try_next().await
+    }
+    /// 读取完整内容
+    pub async fn bytes(mut self) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes.freeze())
+    }
+    /// 按 UTF-8 读取完整内容
+    pub async fn text(self) -> Result<String> {
+        let bytes = self.bytes().await?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| SilentError::business_error(StatusCode::BAD_REQUEST, e.to_string()))
+    }
+}
+
+impl Stream for MultipartField {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let chunk = match self.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(chunk))) => chunk,
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(multer_error(e)))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        self.read += chunk.len() as u64;
+        if let Some(limit) = self.limit
+            && self.read > limit
+        {
+            let name = self.name().unwrap_or_default().to_string();
+            return Poll::Ready(Some(Err(SilentError::business_error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("multipart field `{name}` exceeds {limit} bytes"),
+            ))));
+        }
+        Poll::Ready(Some(Ok(chunk)))
+    }
+}
+
+fn multer_error(e: multer::Error) -> SilentError {
+    match e {
+        multer::Error::StreamSizeExceeded { .. } | multer::Error::FieldSizeExceeded { .. } => {
+            SilentError::business_error(StatusCode::PAYLOAD_TOO_LARGE, e.to_string())
+        }
+        e => e.into(),
+    }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use crate::prelude::*;
+    use futures_util::TryStreamExt;
+
+    async fn upload(mut req: Request) -> Result<String> {
+        let mut multipart = req.multipart()?;
+        let mut received = vec![];
+        while let Some(mut field) = multipart.try_next().await? {
+            let name = field.name().unwrap_or_default().to_string();
+            let mut size = 0;
+            while let Some(chunk) = field.chunk().await? {
+                size += chunk.len();
+            }
+            received.push(format!("{name}:{}:{size}", field.is_file()));
+        }
+        Ok(received.join(","))
+    }
+
+    fn form() -> MultipartForm {
+        MultipartForm::new()
+            .text("title", "report")
+            .file("doc", "a.pdf", "application/pdf", vec![0u8; 64])
+            .file("image", "b.png", "image/png", vec![0u8; 32])
+    }
+
+    #[tokio::test]
+    async fn test_multipart_stream() {
+        let client = TestClient::new(Route::new("upload").post(upload));
+        let res = client.post("/upload").multipart(form()).send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text(), "title:false:6,doc:true:64,image:true:32");
+
+        let res = client
+            .post("/upload")
+            .header("content-type", "text/plain")
+            .body("plain")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_limits() {
+        let client = |limits: MultipartLimits| {
+            let mut configs = Configs::new();
+            configs.insert(limits);
+            TestClient::new(Route::new("upload").post(upload)).with_configs(configs)
+        };
+        let status = |limits: MultipartLimits| async move {
+            client(limits)
+                .post("/upload")
+                .multipart(form())
+                .send()
+                .await
+                .status()
+        };
+        assert_eq!(
+            status(MultipartLimits::new().file_size(64).field_size(6).fields(3)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(MultipartLimits::new().file_size(63)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(MultipartLimits::new().field_size(5)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(MultipartLimits::new().fields(2)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(MultipartLimits::new().total_size(100)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(MultipartLimits::new().allowed_types(["image/*", "application/pdf"])).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(MultipartLimits::new().allowed_types(["image/*"])).await,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+
+        // form_data 同样受限制
+        let route = Route::new("upload").post(|mut req: Request| async move {
+            let form_data = req.form_data().await?;
+            Ok(form_data.files.len().to_string())
+        });
+        let mut configs = Configs::new();
+        configs.insert(MultipartLimits::new().file_size(63));
+        let client = TestClient::new(route).with_configs(configs);
+        let res = client.post("/upload").multipart(form()).send().await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::Error as IoError;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 
 use bytes::Bytes;
 use futures_util::Stream;
@@ -16,6 +16,9 @@ pub enum ReqBody {
     Once(Bytes),
     /// Incoming default body.
     Incoming(Incoming),
+    /// HTTP/3 request body.
+    #[cfg(feature = "http3")]
+    Http3(crate::service::Http3Body),
 }
 
 impl From<Incoming> for ReqBody {
@@ -40,8 +43,17 @@ impl Body for ReqBody {
     ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
         match &mut *self {
             ReqBody::Empty => Poll::Ready(None),
-            ReqBody::Once(bytes) => Poll::Ready(Some(Ok(Frame::data(bytes.clone())))),
+            ReqBody::Once(bytes) => {
+                if bytes.is_empty() {
+                    Poll::Ready(None)
+                } else {
+                    let bytes = std::mem::take(bytes);
+                    Poll::Ready(Some(Ok(Frame::data(bytes))))
+                }
+            }
             ReqBody::Incoming(body) => Pin::new(body).poll_frame(cx).map_err(IoError::other),
+            #[cfg(feature = "http3")]
+            ReqBody::Http3(body) => Pin::new(body).poll_frame(cx),
         }
     }
 
@@ -50,6 +62,8 @@ impl Body for ReqBody {
             ReqBody::Empty => true,
             ReqBody::Once(bytes) => bytes.is_empty(),
             ReqBody::Incoming(body) => body.is_end_stream(),
+            #[cfg(feature = "http3")]
+            ReqBody::Http3(body) => body.is_end_stream(),
         }
     }
 
@@ -58,6 +72,8 @@ impl Body for ReqBody {
             ReqBody::Empty => SizeHint::with_exact(0),
             ReqBody::Once(bytes) => SizeHint::with_exact(bytes.len() as u64),
             ReqBody::Incoming(body) => body.size_hint(),
+            #[cfg(feature = "http3")]
+            ReqBody::Http3(body) => body.size_hint(),
         }
     }
 }
@@ -65,12 +81,76 @@ impl Body for ReqBody {
 impl Stream for ReqBody {
     type Item = Result<Bytes, IoError>;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match Body::poll_frame(self, cx) {
-            Poll::Ready(Some(Ok(frame))) => Poll::Ready(frame.into_data().map(Ok).ok()),
-            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(IoError::other(e)))),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+    /// 只产出数据帧，trailers 需要通过 [`Body::poll_frame`] 读取
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match ready!(Body::poll_frame(self.as_mut(), cx)) {
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        return Poll::Ready(Some(Ok(data)));
+                    }
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            }
         }
     }
 }
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use http_body_util::BodyExt;
+    use hyper_util::rt::TokioIo;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_once_single_frame() {
+        let mut body = ReqBody::Once(Bytes::from("hello"));
+        let frame = body.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), "hello");
+        assert!(body.is_end_stream());
+        assert!(body.frame().await.is_none());
+
+        let body = ReqBody::Once(Bytes::from("hello"));
+        let chunks: Vec<_> = body.map(|chunk| chunk.unwrap()).collect().await;
+        assert_eq!(chunks, vec![Bytes::from("hello")]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_skips_trailers() {
+        let (client, server) = tokio::io::duplex(1024);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let service = hyper::service::service_fn(move |req: hyper::Request<Incoming>| {
+            let tx = tx.clone();
+            async move {
+                let body = ReqBody::from(req.into_body());
+                let chunks: Vec<_> = StreamExt::collect::<Vec<_>>(body).await;
+                let _ = tx.send(chunks);
+                Ok::<_, hyper::Error>(hyper::Response::new(http_body_util::Empty::<Bytes>::new()))
+            }
+        });
+        tokio::spawn(
+            hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(server), service),
+        );
+        let (mut read, mut write) = tokio::io::split(client);
+        write
+            .write_all(
+                b"POST / HTTP/1.1\r\nhost: localhost\r\ntransfer-encoding: chunked\r\n\
+                  trailer: x-checksum\r\nconnection: close\r\n\r\n\
+                  5\r\nhello\r\n0\r\nx-checksum: abc\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let chunks = rx.recv().await.unwrap();
+        assert_eq!(
+            chunks.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            vec![Bytes::from("hello")]
+        );
+        let mut response = vec![];
+        read.read_to_end(&mut response).await.unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 200"));
+    }
+}
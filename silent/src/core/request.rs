@@ -1,5 +1,7 @@
 #[cfg(feature = "multipart")]
 use crate::core::form::{FilePart, FormData};
+#[cfg(feature = "multipart")]
+use crate::core::multipart::{Multipart, MultipartLimits};
 use crate::core::path_param::PathParam;
 use crate::core::req_body::ReqBody;
 #[cfg(feature = "multipart")]
@@ -206,6 +208,9 @@ impl Request {
     pub(crate) fn set_path_params(&mut self, key: String, value: PathParam) {
         self.path_params.insert(key, value);
     }
+    pub(crate) fn path_params_mut(&mut self) -> &mut HashMap<String, PathParam> {
+        &mut self.path_params
+    }
 
     /// 获取配置
     #[inline]
@@ -274,6 +279,16 @@ impl Request {
         std::mem::replace(&mut self.body, body)
     }
 
+    /// 读取完整的请求体与 trailers
+    ///
+    /// 需要流式读取时，可取出 [`ReqBody`] 后通过 [`BodyExt::frame`](http_body_util::BodyExt::frame)
+    /// 逐帧读取，最后一帧为 trailers。
+    pub async fn body_with_trailers(&mut self) -> Result<(Bytes, Option<HeaderMap>)> {
+        let collected = self.take_body().collect().await?;
+        let trailers = collected.trailers().cloned();
+        Ok((collected.to_bytes(), trailers))
+    }
+
     /// 获取请求body
     #[inline]
     pub fn take_body(&mut self) -> ReqBody {
@@ -300,12 +315,44 @@ impl Request {
             return Err(SilentError::ContentTypeError);
         }
         let body = self.take_body();
+        let limits = self.multipart_limits();
         let headers = self.headers();
         self.form_data
-            .get_or_try_init(|| async { FormData::read(headers, body).await })
+            .get_or_try_init(|| async { FormData::read(headers, body, limits).await })
             .await
     }
 
+    /// 以流的方式读取 `multipart/form-data` 请求体
+    ///
+    /// 使用 [`Configs`] 中的 [`MultipartLimits`]，未配置时不做限制。请求体只能读取一次，
+    /// 之后调用 [`Request::form_data`] 将得到空的表单。
+    #[cfg(feature = "multipart")]
+    pub fn multipart(&mut self) -> Result<Multipart> {
+        let limits = self.multipart_limits();
+        self.multipart_with_limits(limits)
+    }
+
+    /// 使用指定的限制以流的方式读取 `multipart/form-data` 请求体
+    #[cfg(feature = "multipart")]
+    pub fn multipart_with_limits(&mut self, limits: MultipartLimits) -> Result<Multipart> {
+        let content_type = self
+            .content_type()
+            .ok_or(SilentError::ContentTypeMissingError)?;
+        if content_type.subtype() != mime::FORM_DATA {
+            return Err(SilentError::ContentTypeError);
+        }
+        let body = self.take_body();
+        Multipart::new(self.headers(), body, limits)
+    }
+
+    #[cfg(feature = "multipart")]
+    fn multipart_limits(&self) -> MultipartLimits {
+        self.configs
+            .get::<MultipartLimits>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// 解析表单数据（支持 multipart/form-data 和 application/x-www-form-urlencoded）
     pub async fn form_parse<T>(&mut self) -> Result<T>
     where
@@ -340,6 +387,12 @@ impl Request {
                         .to_bytes(),
                     ReqBody::Once(bytes) => bytes,
                     ReqBody::Empty => return Err(SilentError::BodyEmpty),
+                    #[cfg(feature = "http3")]
+                    ReqBody::Http3(body) => body
+                        .collect()
+                        .await
+                        .or(Err(SilentError::BodyEmpty))?
+                        .to_bytes(),
                 };
 
                 if bytes.is_empty() {
@@ -411,6 +464,12 @@ impl Request {
                 .to_bytes(),
             ReqBody::Once(bytes) => bytes,
             ReqBody::Empty => return Err(SilentError::JsonEmpty),
+            #[cfg(feature = "http3")]
+            ReqBody::Http3(body) => body
+                .collect()
+                .await
+                .or(Err(SilentError::JsonEmpty))?
+                .to_bytes(),
         };
 
         if bytes.is_empty() {
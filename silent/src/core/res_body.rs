@@ -1,11 +1,12 @@
 use std::collections::VecDeque;
 use std::error::Error as StdError;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 
 use bytes::Bytes;
 use futures_util::TryStreamExt;
 use futures_util::stream::{BoxStream, Stream};
+use http::HeaderMap;
 use http_body::{Body, Frame, SizeHint};
 use hyper::body::Incoming;
 
@@ -32,6 +33,57 @@ pub fn full<T: Into<Bytes>>(chunk: T) -> ResBody {
     ResBody::Once(chunk.into())
 }
 
+/// 在响应体结束后追加 trailers，与响应体自身的 trailers 合并
+pub(crate) fn with_trailers(body: ResBody, trailers: TrailersFn) -> ResBody {
+    ResBody::Boxed(Box::pin(TrailersBody {
+        body,
+        trailers: Some(trailers),
+    }))
+}
+
+pub(crate) type TrailersFn = Box<dyn FnOnce() -> Option<HeaderMap> + Send>;
+
+struct TrailersBody {
+    body: ResBody,
+    trailers: Option<TrailersFn>,
+}
+
+impl Body for TrailersBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = match ready!(Pin::new(&mut self.body).poll_frame(cx)) {
+            Some(Ok(frame)) => frame,
+            Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+            None => {
+                let trailers = self.trailers.take().and_then(|trailers| trailers());
+                return Poll::Ready(trailers.map(|trailers| Ok(Frame::trailers(trailers))));
+            }
+        };
+        match frame.into_trailers() {
+            Ok(mut trailers) => {
+                if let Some(extra) = self.trailers.take().and_then(|trailers| trailers()) {
+                    trailers.extend(extra);
+                }
+                Poll::Ready(Some(Ok(Frame::trailers(trailers))))
+            }
+            Err(frame) => Poll::Ready(Some(Ok(frame))),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.trailers.is_none() && Body::is_end_stream(&self.body)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        Body::size_hint(&self.body)
+    }
+}
+
 /// 转换数据为响应Body
 pub fn stream_body<S, O, E>(stream: S) -> ResBody
 where
@@ -59,19 +111,27 @@ impl Stream for ResBody {
                 }
             }
             ResBody::Chunks(chunks) => Poll::Ready(chunks.pop_front().map(Ok)),
-            ResBody::Incoming(body) => match Body::poll_frame(Pin::new(body), cx) {
-                Poll::Ready(Some(Ok(frame))) => Poll::Ready(frame.into_data().map(Ok).ok()),
-                Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e.into()))),
-                Poll::Ready(None) => Poll::Ready(None),
-                Poll::Pending => Poll::Pending,
-            },
+            ResBody::Incoming(body) => poll_data(body, cx).map_err(Into::into),
             ResBody::Stream(stream) => stream.as_mut().poll_next(cx).map_err(Into::into),
-            ResBody::Boxed(body) => match Body::poll_frame(Pin::new(body), cx) {
-                Poll::Ready(Some(Ok(frame))) => Poll::Ready(frame.into_data().map(Ok).ok()),
-                Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
-                Poll::Ready(None) => Poll::Ready(None),
-                Poll::Pending => Poll::Pending,
-            },
+            ResBody::Boxed(body) => poll_data(body, cx),
+        }
+    }
+}
+
+/// 读取下一个数据帧，跳过 trailers 等其他帧
+fn poll_data<B>(body: &mut B, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, B::Error>>>
+where
+    B: Body<Data = Bytes> + Unpin,
+{
+    loop {
+        match ready!(Pin::new(&mut *body).poll_frame(cx)) {
+            Some(Ok(frame)) => {
+                if let Ok(data) = frame.into_data() {
+                    return Poll::Ready(Some(Ok(data)));
+                }
+            }
+            Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+            None => return Poll::Ready(None),
         }
     }
 }
@@ -171,3 +231,27 @@ impl From<Box<[u8]>> for ResBody {
         ResBody::Once(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn test_stream_skips_trailers() {
+        let body = ResBody::Once(Bytes::from("hello"));
+        let chunks: Vec<_> = body.map(|chunk| chunk.unwrap()).collect().await;
+        assert_eq!(chunks, vec![Bytes::from("hello")]);
+
+        let body = with_trailers(
+            ResBody::Chunks(VecDeque::from([Bytes::from("a"), Bytes::from("b")])),
+            Box::new(|| {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("x-count", "2".parse().unwrap());
+                Some(trailers)
+            }),
+        );
+        let chunks: Vec<_> = body.map(|chunk| chunk.unwrap()).collect().await;
+        assert_eq!(chunks, vec![Bytes::from("a"), Bytes::from("b")]);
+    }
+}
@@ -1,13 +1,30 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-use crate::core::res_body::{ResBody, full};
+#[cfg(feature = "static")]
+use crate::Request;
+#[cfg(feature = "server")]
+use crate::core::channel_body::{self, BodySender};
+use crate::core::res_body::{ResBody, full, with_trailers};
+#[cfg(feature = "static")]
+use crate::handler::static_serve::{Content, StaticFile, content_disposition, not_found};
+#[cfg(feature = "static")]
+use crate::header::HeaderValue;
 use crate::headers::{ContentType, Header, HeaderMap, HeaderMapExt};
 use crate::{Configs, Result, SilentError, StatusCode, header};
+use bytes::Bytes;
 use http::{Extensions, Version};
 use http_body::{Body, SizeHint};
+use http_body_util::BodyExt;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+#[cfg(feature = "static")]
+use std::io;
+#[cfg(feature = "static")]
+use std::path::Path;
+#[cfg(feature = "static")]
+use tokio::io::AsyncRead;
 
 /// 响应体
 /// ```
@@ -52,6 +69,31 @@ impl Response {
             configs: Configs::default(),
         }
     }
+    /// 创建以通道为响应体的响应，通过返回的 [`BodySender`] 逐步写入内容
+    ///
+    /// 适合进度推送、NDJSON 导出等边生成边发送的场景，配合 `text/event-stream` 也可用于 SSE。
+    /// 需要启用 `server` feature。
+    /// ```
+    /// use silent::prelude::*;
+    ///
+    /// async fn export(_req: Request) -> Result<Response> {
+    ///     let (mut sender, res) = Response::channel();
+    ///     tokio::spawn(async move {
+    ///         for id in 0..3 {
+    ///             let line = format!("{{\"id\":{id}}}\n");
+    ///             if sender.send_data(line).await.is_err() {
+    ///                 return;
+    ///             }
+    ///         }
+    ///     });
+    ///     Ok(res.with_header(header::CONTENT_TYPE, "application/x-ndjson".parse().unwrap()))
+    /// }
+    /// ```
+    #[cfg(feature = "server")]
+    pub fn channel() -> (BodySender, Self) {
+        let (sender, body) = channel_body::channel();
+        (sender, Self::empty().with_body(body))
+    }
     #[inline]
     /// 设置响应重定向
     pub fn redirect(url: &str) -> Result<Self> {
@@ -94,6 +136,140 @@ impl Response {
     }
 }
 
+#[cfg(feature = "static")]
+impl Response {
+    /// 生成文件响应，浏览器可直接显示
+    ///
+    /// 根据扩展名设置 `Content-Type`，带有 `Content-Disposition`、`Content-Length`、
+    /// `ETag` 与 `Last-Modified`，并按 `req` 处理 `Range` 与条件请求。文件不存在时返回 `404`。
+    /// ```no_run
+    /// use silent::prelude::*;
+    ///
+    /// async fn report(req: Request) -> Result<Response> {
+    ///     Response::file(&req, "reports/2024.pdf").await
+    /// }
+    /// ```
+    pub async fn file(req: &Request, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content_type = mime_guess::from_path(path).first_or_octet_stream();
+        let content_type = HeaderValue::from_str(content_type.as_ref())
+            .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+        let file = StaticFile::open(path.to_path_buf(), content_type, None)
+            .await
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => not_found(),
+                _ => e.into(),
+            })?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        Ok(file.into_download(req, content_disposition("inline", &filename)))
+    }
+
+    /// 从任意 [`AsyncRead`] 生成下载响应，浏览器以 `filename` 保存
+    ///
+    /// `len` 为内容的字节数。根据文件名设置 `Content-Type`，`req` 中单个区间的 `Range` 请求通过
+    /// 跳过之前的内容实现，多个区间时返回完整内容。
+    /// ```
+    /// use silent::prelude::*;
+    ///
+    /// async fn export(req: Request) -> Result<Response> {
+    ///     let csv = b"id,name\n1,silent\n";
+    ///     Ok(Response::attachment(&req, &csv[..], "用户.csv", csv.len() as u64))
+    /// }
+    /// ```
+    pub fn attachment<R>(req: &Request, reader: R, filename: &str, len: u64) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        let content_type = mime_guess::from_path(filename).first_or_octet_stream();
+        let file = StaticFile {
+            content: Content::Reader(Box::pin(reader)),
+            len,
+            content_type: HeaderValue::from_str(content_type.as_ref())
+                .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            etag: None,
+            last_modified: None,
+            encoding: None,
+        };
+        file.into_download(req, content_disposition("attachment", filename))
+    }
+}
+
+impl Response {
+    /// 取出响应体，原响应体置为空
+    #[inline]
+    pub fn take_body(&mut self) -> ResBody {
+        std::mem::replace(&mut self.body, ResBody::None)
+    }
+    /// 读取完整的响应体
+    pub async fn body_bytes(&mut self) -> Result<Bytes> {
+        Ok(self.take_body().collect().await?.to_bytes())
+    }
+    /// 读取完整的响应体与 trailers
+    pub async fn body_with_trailers(&mut self) -> Result<(Bytes, Option<HeaderMap>)> {
+        let collected = self.take_body().collect().await?;
+        let trailers = collected.trailers().cloned();
+        Ok((collected.to_bytes(), trailers))
+    }
+    /// 设置响应体结束后发送的 trailers，并通过 `Trailer` 头声明字段名
+    ///
+    /// 需要在设置响应体之后调用。HTTP/1.1 只有请求带有 `TE: trailers` 时才会发送。
+    pub fn set_trailers(&mut self, trailers: HeaderMap) {
+        let names = trailers
+            .keys()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(names) = names.parse() {
+            self.headers.insert(header::TRAILER, names);
+        }
+        self.set_trailers_with(move || Some(trailers));
+    }
+    /// 设置在响应体结束后生成 trailers 的函数，适合发送流式计算的校验值
+    ///
+    /// 需要在设置响应体之后调用，字段名需要自行通过 `Trailer` 头声明。
+    /// ```
+    /// use futures_util::StreamExt;
+    /// use silent::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let size = Arc::new(Mutex::new(0));
+    /// let counter = size.clone();
+    /// let chunks = futures_util::stream::iter(["hello", " ", "world"]).map(move |chunk| {
+    ///     *counter.lock().unwrap() += chunk.len();
+    ///     Ok::<_, std::io::Error>(chunk)
+    /// });
+    /// let mut res = Response::empty().with_body(stream_body(chunks));
+    /// res.set_header(header::TRAILER, header::HeaderValue::from_static("x-size"));
+    /// res.set_trailers_with(move || {
+    ///     let mut trailers = header::HeaderMap::new();
+    ///     trailers.insert("x-size", size.lock().unwrap().to_string().parse().ok()?);
+    ///     Some(trailers)
+    /// });
+    /// ```
+    pub fn set_trailers_with<F>(&mut self, trailers: F)
+    where
+        F: FnOnce() -> Option<HeaderMap> + Send + 'static,
+    {
+        let body = self.take_body();
+        self.body = with_trailers(body, Box::new(trailers));
+    }
+    /// 读取响应体文本
+    pub async fn body_text(&mut self) -> Result<String> {
+        let bytes = self.body_bytes().await?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| {
+            SilentError::business_error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+        })
+    }
+    /// 按 JSON 解析响应体
+    pub async fn json_parse<T: DeserializeOwned>(&mut self) -> Result<T> {
+        let bytes = self.body_bytes().await?;
+        serde_json::from_slice(&bytes).map_err(Into::into)
+    }
+}
+
 impl<B: Body> Response<B> {
     /// 设置响应状态
     #[inline]
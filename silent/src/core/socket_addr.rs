@@ -7,11 +7,27 @@ pub enum SocketAddr {
     Tcp(std::net::SocketAddr),
     #[cfg(feature = "tls")]
     TlsTcp(std::net::SocketAddr),
+    /// HTTP/3 使用的 QUIC（UDP）地址
+    #[cfg(feature = "http3")]
+    Quic(std::net::SocketAddr),
     #[cfg(not(target_os = "windows"))]
     Unix(std::os::unix::net::SocketAddr),
 }
 
 impl SocketAddr {
+    /// TCP 或 QUIC 地址，Unix 套接字返回 `None`
+    pub fn tcp_addr(&self) -> Option<std::net::SocketAddr> {
+        match self {
+            SocketAddr::Tcp(addr) => Some(*addr),
+            #[cfg(feature = "tls")]
+            SocketAddr::TlsTcp(addr) => Some(*addr),
+            #[cfg(feature = "http3")]
+            SocketAddr::Quic(addr) => Some(*addr),
+            #[cfg(not(target_os = "windows"))]
+            SocketAddr::Unix(_) => None,
+        }
+    }
+
     #[cfg(feature = "tls")]
     pub(crate) fn tls(self) -> Result<Self> {
         match self {
@@ -27,6 +43,8 @@ impl Debug for SocketAddr {
             SocketAddr::Tcp(addr) => write!(f, "http://{addr}"),
             #[cfg(feature = "tls")]
             SocketAddr::TlsTcp(addr) => write!(f, "https://{addr}"),
+            #[cfg(feature = "http3")]
+            SocketAddr::Quic(addr) => write!(f, "quic://{addr}"),
             #[cfg(not(target_os = "windows"))]
             SocketAddr::Unix(addr) => write!(f, "UnixSocketAddr({addr:?})"),
         }
@@ -40,6 +58,8 @@ impl Display for SocketAddr {
             SocketAddr::Tcp(addr) => write!(f, "{addr}"),
             #[cfg(feature = "tls")]
             SocketAddr::TlsTcp(addr) => write!(f, "{addr}"),
+            #[cfg(feature = "http3")]
+            SocketAddr::Quic(addr) => write!(f, "{addr}"),
             #[cfg(not(target_os = "windows"))]
             SocketAddr::Unix(addr) => {
                 write!(f, "{:?}", addr.as_pathname())
@@ -0,0 +1,623 @@
+use super::listener::{AcceptFuture, HandshakeFuture, Listen, Listener};
+use super::socket_addr::SocketAddr;
+use crate::core::connection::Connection;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+
+fn tls_error<T: std::fmt::Display>(msg: T) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+/// rustls 加密实现
+///
+/// 优先使用进程默认实现，未安装时使用 `aws-lc-rs` 或 `ring` 特性对应的实现。
+pub(crate) fn crypto_provider() -> Result<Arc<CryptoProvider>> {
+    if let Some(provider) = CryptoProvider::get_default() {
+        return Ok(provider.clone());
+    }
+    #[cfg(feature = "aws-lc-rs")]
+    let provider = Some(tokio_rustls::rustls::crypto::aws_lc_rs::default_provider());
+    #[cfg(all(feature = "ring", not(feature = "aws-lc-rs")))]
+    let provider = Some(tokio_rustls::rustls::crypto::ring::default_provider());
+    #[cfg(not(any(feature = "aws-lc-rs", feature = "ring")))]
+    let provider = None;
+    provider.map(Arc::new).ok_or_else(|| {
+        Error::new(
+            ErrorKind::Unsupported,
+            "no rustls crypto provider: enable the `aws-lc-rs` or `ring` feature, \
+             or install a process default with `CryptoProvider::install_default`",
+        )
+    })
+}
+
+/// 证书链与私钥文件
+#[derive(Debug, Clone)]
+struct CertFiles {
+    cert: PathBuf,
+    key: PathBuf,
+}
+
+impl CertFiles {
+    fn load(&self, provider: &CryptoProvider) -> Result<Arc<CertifiedKey>> {
+        let certs = CertificateDer::pem_file_iter(&self.cert)
+            .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+            .map_err(|e| tls_error(format!("load {} failed: {e}", self.cert.display())))?;
+        if certs.is_empty() {
+            return Err(tls_error(format!(
+                "no certificate found in {}",
+                self.cert.display()
+            )));
+        }
+        let key = PrivateKeyDer::from_pem_file(&self.key)
+            .map_err(|e| tls_error(format!("load {} failed: {e}", self.key.display())))?;
+        let key = provider
+            .key_provider
+            .load_private_key(key)
+            .map_err(|e| tls_error(format!("invalid key {}: {e}", self.key.display())))?;
+        Ok(Arc::new(CertifiedKey::new(certs, key)))
+    }
+}
+
+/// 按 SNI 选择证书，未匹配时使用默认证书
+#[derive(Debug)]
+struct SniResolver {
+    default: Option<Arc<CertifiedKey>>,
+    certs: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl SniResolver {
+    fn find(&self, server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+        if let Some(name) = server_name.map(str::to_ascii_lowercase) {
+            if let Some(cert) = self.certs.get(&name) {
+                return Some(cert.clone());
+            }
+            // 通配符证书只匹配一级子域名
+            if let Some((_, parent)) = name.split_once('.')
+                && let Some(cert) = self.certs.get(&format!("*.{parent}"))
+            {
+                return Some(cert.clone());
+            }
+        }
+        self.default.clone()
+    }
+}
+
+impl ResolvesServerCert for SniResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.find(client_hello.server_name())
+    }
+}
+
+/// TLS 配置
+///
+/// 从 PEM 文件加载证书，支持按 SNI 选择证书以及校验客户端证书（mTLS）。
+/// 默认 ALPN 为 `h2`、`http/1.1`。
+/// ```no_run
+/// use silent::prelude::*;
+/// let config = TlsConfig::new()
+///     .cert("certs/default.pem", "certs/default-key.pem")
+///     .sni_cert("api.example.com", "certs/api.pem", "certs/api-key.pem")
+///     .sni_cert("*.example.com", "certs/wildcard.pem", "certs/wildcard-key.pem")
+///     .client_ca("certs/ca.pem");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    default_cert: Option<CertFiles>,
+    sni_certs: Vec<(String, CertFiles)>,
+    client_ca: Option<PathBuf>,
+    client_auth_optional: bool,
+    alpn_protocols: Vec<Vec<u8>>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self {
+            default_cert: None,
+            sni_certs: vec![],
+            client_ca: None,
+            client_auth_optional: false,
+            alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+        }
+    }
+    /// 默认证书，客户端未发送 SNI 或未匹配时使用
+    pub fn cert<C: AsRef<Path>, K: AsRef<Path>>(mut self, cert: C, key: K) -> Self {
+        self.default_cert = Some(CertFiles {
+            cert: cert.as_ref().to_path_buf(),
+            key: key.as_ref().to_path_buf(),
+        });
+        self
+    }
+    /// 指定域名使用的证书，支持 `*.example.com` 形式的通配符
+    pub fn sni_cert<C: AsRef<Path>, K: AsRef<Path>>(
+        mut self,
+        server_name: &str,
+        cert: C,
+        key: K,
+    ) -> Self {
+        self.sni_certs.push((
+            server_name.to_ascii_lowercase(),
+            CertFiles {
+                cert: cert.as_ref().to_path_buf(),
+                key: key.as_ref().to_path_buf(),
+            },
+        ));
+        self
+    }
+    /// 要求客户端证书，并使用 CA 证书文件校验
+    pub fn client_ca<P: AsRef<Path>>(mut self, ca: P) -> Self {
+        self.client_ca = Some(ca.as_ref().to_path_buf());
+        self.client_auth_optional = false;
+        self
+    }
+    /// 允许客户端不提供证书，提供时使用 CA 证书文件校验
+    pub fn client_ca_optional<P: AsRef<Path>>(mut self, ca: P) -> Self {
+        self.client_ca = Some(ca.as_ref().to_path_buf());
+        self.client_auth_optional = true;
+        self
+    }
+    /// 设置 ALPN 协议
+    pub fn alpn_protocols(mut self, protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = protocols;
+        self
+    }
+
+    /// 配置引用的所有文件
+    fn files(&self) -> Vec<&Path> {
+        self.default_cert
+            .iter()
+            .chain(self.sni_certs.iter().map(|(_, files)| files))
+            .flat_map(|files| [files.cert.as_path(), files.key.as_path()])
+            .chain(self.client_ca.as_deref())
+            .collect()
+    }
+
+    /// 读取文件并构建 rustls 配置
+    ///
+    /// 优先使用进程默认的加密实现，其次使用 `aws-lc-rs` 或 `ring` 特性对应的实现，都没有时返回错误。
+    pub fn build(&self) -> Result<ServerConfig> {
+        if self.default_cert.is_none() && self.sni_certs.is_empty() {
+            return Err(tls_error("no certificate configured"));
+        }
+        let provider = crypto_provider()?;
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?;
+        let builder = match &self.client_ca {
+            Some(ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in CertificateDer::pem_file_iter(ca)
+                    .map_err(|e| tls_error(format!("load {} failed: {e}", ca.display())))?
+                {
+                    let cert =
+                        cert.map_err(|e| tls_error(format!("load {} failed: {e}", ca.display())))?;
+                    roots.add(cert).map_err(|e| {
+                        tls_error(format!("invalid ca certificate {}: {e}", ca.display()))
+                    })?;
+                }
+                let verifier =
+                    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone());
+                let verifier = if self.client_auth_optional {
+                    verifier.allow_unauthenticated()
+                } else {
+                    verifier
+                };
+                builder.with_client_cert_verifier(verifier.build().map_err(tls_error)?)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut resolver = SniResolver {
+            default: None,
+            certs: HashMap::new(),
+        };
+        if let Some(files) = &self.default_cert {
+            resolver.default = Some(files.load(&provider)?);
+        }
+        for (server_name, files) in &self.sni_certs {
+            resolver
+                .certs
+                .insert(server_name.clone(), files.load(&provider)?);
+        }
+        let mut config = builder.with_cert_resolver(Arc::new(resolver));
+        config.alpn_protocols = self.alpn_protocols.clone();
+        Ok(config)
+    }
+}
+
+/// 可热重载的 TLS 接收器
+///
+/// 重载时重新读取证书文件并替换配置，只影响之后建立的连接；重载失败时继续使用原配置。
+/// ```no_run
+/// use silent::prelude::*;
+/// use std::time::Duration;
+/// # async fn run() -> std::io::Result<()> {
+/// let tls = ReloadableTlsAcceptor::new(TlsConfig::new().cert("cert.pem", "key.pem"))?;
+/// // 每 30 秒检查证书文件是否变化
+/// tls.watch(Duration::from_secs(30));
+/// let listener: Listener = tokio::net::TcpListener::bind("127.0.0.1:8443").await?.into();
+/// let route = Route::new("").get(|_req| async { Ok("hello") });
+/// Server::new().listen(listener.tls_reloadable(tls.clone())).serve(route).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReloadableTlsAcceptor {
+    inner: Arc<ReloadableInner>,
+}
+
+struct ReloadableInner {
+    config: TlsConfig,
+    acceptor: RwLock<TlsAcceptor>,
+    modified: Mutex<Vec<Option<SystemTime>>>,
+}
+
+impl ReloadableInner {
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.config
+            .files()
+            .into_iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+impl ReloadableTlsAcceptor {
+    pub fn new(config: TlsConfig) -> Result<Self> {
+        let acceptor = TlsAcceptor::from(Arc::new(config.build()?));
+        let inner = ReloadableInner {
+            config,
+            acceptor: RwLock::new(acceptor),
+            modified: Mutex::new(vec![]),
+        };
+        *inner.modified.lock().unwrap() = inner.modified();
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// 当前使用的接收器
+    pub fn acceptor(&self) -> TlsAcceptor {
+        self.inner.acceptor.read().unwrap().clone()
+    }
+
+    /// 立即重新加载证书
+    pub fn reload(&self) -> Result<()> {
+        let modified = self.inner.modified();
+        let config = self.inner.config.build()?;
+        *self.inner.acceptor.write().unwrap() = TlsAcceptor::from(Arc::new(config));
+        *self.inner.modified.lock().unwrap() = modified;
+        tracing::info!("tls certificates reloaded");
+        Ok(())
+    }
+
+    /// 定期检查证书文件的修改时间，变化时自动重新加载
+    ///
+    /// 需要在 tokio 运行时中调用，所有接收器被释放后检查任务自动退出。
+    pub fn watch(&self, interval: Duration) -> JoinHandle<()> {
+        let inner = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(inner) = Weak::upgrade(&inner) else {
+                    break;
+                };
+                if inner.modified() == *inner.modified.lock().unwrap() {
+                    continue;
+                }
+                if let Err(e) = (Self { inner }).reload() {
+                    tracing::error!("reload tls certificates failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+impl Listener {
+    /// 使用可热重载的 TLS 配置
+    pub fn tls_reloadable(self, acceptor: ReloadableTlsAcceptor) -> ReloadableTlsListener {
+        ReloadableTlsListener {
+            listener: self,
+            acceptor,
+        }
+    }
+}
+
+/// 使用 [`ReloadableTlsAcceptor`] 的 TLS 监听器
+pub struct ReloadableTlsListener {
+    listener: Listener,
+    acceptor: ReloadableTlsAcceptor,
+}
+
+impl Listen for ReloadableTlsListener {
+    fn accept(&self) -> AcceptFuture<'_> {
+        let accept_future = async move {
+            let (stream, addr) = self.listener.accept().await?;
+            let tls_stream = self.acceptor.acceptor().accept(stream).await?;
+            Ok((
+                Box::new(tls_stream) as Box<dyn Connection + Send + Sync>,
+                addr,
+            ))
+        };
+        Box::pin(accept_future)
+    }
+
+    fn accept_stream(&self) -> AcceptFuture<'_> {
+        self.listener.accept()
+    }
+
+    fn handshake(&self, stream: Box<dyn Connection + Send + Sync>) -> HandshakeFuture {
+        let acceptor = self.acceptor.acceptor();
+        Box::pin(async move {
+            let tls_stream = acceptor.accept(stream).await?;
+            Ok(Box::new(tls_stream) as Box<dyn Connection + Send + Sync>)
+        })
+    }
+
+    fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr()?.tls()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn raw_fd(&self) -> Option<std::os::fd::RawFd> {
+        self.listener.raw_fd()
+    }
+}
+
+#[cfg(all(test, feature = "server", any(feature = "aws-lc-rs", feature = "ring")))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::ClientConfig;
+    use tokio_rustls::rustls::pki_types::ServerName;
+
+    fn cert_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/certs")
+            .join(name)
+    }
+
+    fn connector(client_cert: bool) -> TlsConnector {
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(cert_path("ca.pem")).unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let builder = ClientConfig::builder_with_provider(crypto_provider().unwrap())
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+        let mut config = if client_cert {
+            let certs = CertificateDer::pem_file_iter(cert_path("client.pem"))
+                .unwrap()
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .unwrap();
+            let key = PrivateKeyDer::from_pem_file(cert_path("client-key.pem")).unwrap();
+            builder.with_client_auth_cert(certs, key).unwrap()
+        } else {
+            builder.with_no_client_auth()
+        };
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        TlsConnector::from(Arc::new(config))
+    }
+
+    /// 发送请求并返回服务端证书与响应体
+    async fn request(
+        addr: std::net::SocketAddr,
+        server_name: &str,
+        client_cert: bool,
+    ) -> std::io::Result<(Vec<u8>, String)> {
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+        let server_name = ServerName::try_from(server_name.to_string()).unwrap();
+        let mut stream = connector(client_cert).connect(server_name, stream).await?;
+        let cert = stream.get_ref().1.peer_certificates().unwrap()[0].to_vec();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+        Ok((cert, body))
+    }
+
+    fn leaf_cert(name: &str) -> Vec<u8> {
+        CertificateDer::pem_file_iter(cert_path(name))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .to_vec()
+    }
+
+    async fn start(acceptor: ReloadableTlsAcceptor) -> (ServerHandle, std::net::SocketAddr) {
+        let listener: Listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .into();
+        let route = Route::new("").get(|req: Request| async move {
+            let info = req.extensions().get::<ConnectionInfo>().unwrap();
+            let tls = info.tls().unwrap();
+            Ok(format!(
+                "{}|{}|{}",
+                tls.server_name.clone().unwrap_or_default(),
+                String::from_utf8_lossy(tls.alpn_protocol.as_deref().unwrap_or_default()),
+                tls.peer_certificates.len()
+            ))
+        });
+        let handle = Server::new()
+            .listen(listener.tls_reloadable(acceptor))
+            .start(route)
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        (handle, addr)
+    }
+
+    #[tokio::test]
+    async fn test_sni_cert_selection() {
+        let config = TlsConfig::new()
+            .cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"))
+            .sni_cert(
+                "*.example.com",
+                cert_path("example.com.pem"),
+                cert_path("example.com-key.pem"),
+            )
+            .alpn_protocols(vec![b"http/1.1".to_vec()]);
+        let (handle, addr) = start(ReloadableTlsAcceptor::new(config).unwrap()).await;
+
+        let (cert, body) = request(addr, "localhost", false).await.unwrap();
+        assert_eq!(cert, leaf_cert("localhost.pem"));
+        assert_eq!(body, "localhost|http/1.1|0");
+        let (cert, body) = request(addr, "api.example.com", false).await.unwrap();
+        assert_eq!(cert, leaf_cert("example.com.pem"));
+        assert_eq!(body, "api.example.com|http/1.1|0");
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_cert_verification() {
+        let config = TlsConfig::new()
+            .cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"))
+            .client_ca(cert_path("ca.pem"));
+        let (handle, addr) = start(ReloadableTlsAcceptor::new(config).unwrap()).await;
+
+        let (_, body) = request(addr, "localhost", true).await.unwrap();
+        assert_eq!(body, "localhost|http/1.1|1");
+        assert!(request(addr, "localhost", false).await.is_err());
+        handle.shutdown();
+        handle.join().await.unwrap();
+
+        let config = TlsConfig::new()
+            .cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"))
+            .client_ca_optional(cert_path("ca.pem"));
+        let (handle, addr) = start(ReloadableTlsAcceptor::new(config).unwrap()).await;
+        let (_, body) = request(addr, "localhost", false).await.unwrap();
+        assert_eq!(body, "localhost|http/1.1|0");
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slow_handshake_not_blocking() {
+        let config = TlsConfig::new()
+            .cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"))
+            .alpn_protocols(vec![b"http/1.1".to_vec()]);
+        let (handle, addr) = start(ReloadableTlsAcceptor::new(config).unwrap()).await;
+
+        // 只建立 TCP 连接而不发送 ClientHello
+        let _idle = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (_, body) =
+            tokio::time::timeout(Duration::from_secs(2), request(addr, "localhost", false))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(body, "localhost|http/1.1|0");
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_handshakes() {
+        let config = TlsConfig::new()
+            .cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"))
+            .alpn_protocols(vec![b"http/1.1".to_vec()]);
+        let (handle, addr) = start(ReloadableTlsAcceptor::new(config).unwrap()).await;
+
+        // 握手进行中不断有其他连接结束
+        let closing = tokio::spawn(async move {
+            for _ in 0..50 {
+                drop(tokio::net::TcpStream::connect(addr).await.unwrap());
+                tokio::task::yield_now().await;
+            }
+        });
+        let requests = (0..20)
+            .map(|_| tokio::spawn(request(addr, "localhost", false)))
+            .collect::<Vec<_>>();
+        for request in requests {
+            let (_, body) = request.await.unwrap().unwrap();
+            assert_eq!(body, "localhost|http/1.1|0");
+        }
+        closing.await.unwrap();
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let dir = std::env::temp_dir().join(format!("silent-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        std::fs::copy(cert_path("localhost.pem"), &cert).unwrap();
+        std::fs::copy(cert_path("localhost-key.pem"), &key).unwrap();
+        let acceptor = ReloadableTlsAcceptor::new(TlsConfig::new().cert(&cert, &key)).unwrap();
+        let (handle, addr) = start(acceptor.clone()).await;
+        assert!(request(addr, "example.com", false).await.is_err());
+
+        // 证书文件无效时保留原配置
+        std::fs::write(&cert, "invalid").unwrap();
+        assert!(acceptor.reload().is_err());
+        assert!(request(addr, "localhost", false).await.is_ok());
+
+        std::fs::copy(cert_path("example.com.pem"), &cert).unwrap();
+        std::fs::copy(cert_path("example.com-key.pem"), &key).unwrap();
+        acceptor.reload().unwrap();
+        let (cert, _) = request(addr, "example.com", false).await.unwrap();
+        assert_eq!(cert, leaf_cert("example.com.pem"));
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_watch_reload() {
+        let dir = std::env::temp_dir().join(format!("silent-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        std::fs::copy(cert_path("localhost.pem"), &cert).unwrap();
+        std::fs::copy(cert_path("localhost-key.pem"), &key).unwrap();
+        let acceptor = ReloadableTlsAcceptor::new(TlsConfig::new().cert(&cert, &key)).unwrap();
+        let watcher = acceptor.watch(Duration::from_millis(50));
+        let before = acceptor.acceptor().config().clone();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        std::fs::copy(cert_path("example.com.pem"), &cert).unwrap();
+        std::fs::copy(cert_path("example.com-key.pem"), &key).unwrap();
+        let mut reloaded = false;
+        for _ in 0..40 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if !Arc::ptr_eq(&before, acceptor.acceptor().config()) {
+                reloaded = true;
+                break;
+            }
+        }
+        assert!(reloaded);
+
+        drop(acceptor);
+        tokio::time::timeout(Duration::from_secs(1), watcher)
+            .await
+            .unwrap()
+            .unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
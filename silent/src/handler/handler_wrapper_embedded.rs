@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{HeaderValue, header};
+
+use super::StaticOptions;
+use super::static_serve::{Content, StaticFile, accepted_encodings, not_found, safe_segments};
+use crate::{Handler, Request, Response, SilentError};
+
+/// 编译期嵌入的静态文件集合，由 [`embed_assets!`](crate::prelude::embed_assets) 生成
+///
+/// 不依赖运行时的目录，适合单文件部署。文件的 ETag 与 MIME 类型在编译期计算，
+/// 同名的 `.br` 与 `.gz` 文件作为预压缩版本返回。
+/// ```ignore
+/// use silent::prelude::*;
+///
+/// static ASSETS: EmbeddedAssets = embed_assets!("static");
+///
+/// let route = Route::new("").with_embedded(ASSETS);
+/// ```
+///
+/// 目录中新增或删除文件后需要重新编译才能生效。
+#[derive(Clone, Copy)]
+pub struct EmbeddedAssets {
+    files: &'static [EmbeddedFile],
+}
+
+/// 嵌入的单个文件
+pub struct EmbeddedFile {
+    path: &'static str,
+    content_type: &'static str,
+    etag: &'static str,
+    data: &'static [u8],
+    variants: &'static [EmbeddedVariant],
+}
+
+/// 嵌入文件的预压缩版本
+pub struct EmbeddedVariant {
+    encoding: &'static str,
+    etag: &'static str,
+    data: &'static [u8],
+}
+
+impl EmbeddedAssets {
+    #[doc(hidden)]
+    pub const fn new(files: &'static [EmbeddedFile]) -> Self {
+        Self { files }
+    }
+    /// 按相对路径查找文件，路径使用 `/` 分隔
+    pub fn get(&self, path: &str) -> Option<&'static EmbeddedFile> {
+        // 宏按路径排序生成文件列表
+        self.files
+            .binary_search_by(|file| file.path.cmp(path))
+            .ok()
+            .map(|index| &self.files[index])
+    }
+    /// 遍历所有文件
+    pub fn iter(&self) -> impl Iterator<Item = &'static EmbeddedFile> {
+        self.files.iter()
+    }
+    /// 文件数量
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+    /// 是否没有文件
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl EmbeddedFile {
+    #[doc(hidden)]
+    pub const fn new(
+        path: &'static str,
+        content_type: &'static str,
+        etag: &'static str,
+        data: &'static [u8],
+        variants: &'static [EmbeddedVariant],
+    ) -> Self {
+        Self {
+            path,
+            content_type,
+            etag,
+            data,
+            variants,
+        }
+    }
+    /// 相对路径
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+    /// MIME 类型
+    pub fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+    /// 按内容计算的 ETag
+    pub fn etag(&self) -> &'static str {
+        self.etag
+    }
+    /// 文件内容
+    pub fn data(&self) -> &'static [u8] {
+        self.data
+    }
+    /// 指定编码的预压缩内容
+    pub fn encoded(&self, encoding: &str) -> Option<&'static [u8]> {
+        self.variant(encoding).map(|variant| variant.data)
+    }
+
+    fn variant(&self, encoding: &str) -> Option<&'static EmbeddedVariant> {
+        self.variants
+            .iter()
+            .find(|variant| variant.encoding == encoding)
+    }
+}
+
+impl EmbeddedVariant {
+    #[doc(hidden)]
+    pub const fn new(encoding: &'static str, etag: &'static str, data: &'static [u8]) -> Self {
+        Self {
+            encoding,
+            etag,
+            data,
+        }
+    }
+}
+
+struct HandlerWrapperEmbedded {
+    assets: EmbeddedAssets,
+    options: StaticOptions,
+}
+
+impl HandlerWrapperEmbedded {
+    fn find(&self, path: &str) -> Option<&'static EmbeddedFile> {
+        if let Some(file) = self.assets.get(path) {
+            return Some(file);
+        }
+        self.options.index.iter().find_map(|index| match path {
+            "" => self.assets.get(index),
+            path => self.assets.get(&format!("{path}/{index}")),
+        })
+    }
+
+    fn serve(&self, req: &Request, file: &'static EmbeddedFile) -> Response {
+        let variant = match self.options.precompressed {
+            true => accepted_encodings(req.headers())
+                .into_iter()
+                .find_map(|encoding| file.variant(encoding)),
+            false => None,
+        };
+        let (data, etag, encoding) = match variant {
+            Some(variant) => (variant.data, variant.etag, Some(variant.encoding)),
+            None => (file.data, file.etag, None),
+        };
+        let file = StaticFile {
+            content: Content::Bytes(Bytes::from_static(data)),
+            len: data.len() as u64,
+            content_type: HeaderValue::from_static(file.content_type),
+            etag: Some(etag.parse().expect("invalid embedded etag")),
+            last_modified: None,
+            encoding,
+        };
+        let mut res = file.into_response(req, self.options.cache_control.as_ref());
+        if self.options.precompressed {
+            res.headers_mut()
+                .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        }
+        res
+    }
+}
+
+#[async_trait]
+impl Handler for HandlerWrapperEmbedded {
+    async fn call(&self, req: Request) -> Result<Response, SilentError> {
+        if let Ok(file_path) = req.get_path_params::<String>("path") {
+            let file_path = urlencoding::decode(&file_path).map_err(|_| not_found())?;
+            let file_path = safe_segments(&file_path).ok_or_else(not_found)?.join("/");
+            let fallback = self.options.fallback.as_deref().and_then(|fallback| {
+                let fallback = safe_segments(fallback)?.join("/");
+                self.assets.get(&fallback)
+            });
+            if let Some(file) = self.find(&file_path).or(fallback) {
+                return Ok(self.serve(&req, file));
+            }
+        }
+        Err(not_found())
+    }
+}
+
+/// 创建嵌入文件处理器，目录列表配置对嵌入文件无效
+pub fn embedded_handler(assets: EmbeddedAssets, options: StaticOptions) -> impl Handler {
+    HandlerWrapperEmbedded { assets, options }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+    use crate::prelude::*;
+
+    static ASSETS: EmbeddedAssets = embed_assets!("tests/assets");
+
+    #[tokio::test]
+    async fn test_embedded() {
+        assert_eq!(ASSETS.len(), 2);
+        let file = ASSETS.get("js/app.js").unwrap();
+        assert_eq!(file.content_type(), "text/javascript");
+        assert_eq!(file.data(), include_bytes!("../../tests/assets/js/app.js"));
+        assert!(file.encoded("gzip").is_some());
+        assert!(file.encoded("br").is_none());
+
+        let options = StaticOptions::default().fallback("index.html");
+        let client = TestClient::new(Route::new("").with_embedded_options(ASSETS, options));
+        let res = client.get("/").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.header("content-type"), Some("text/html"));
+        let index = res.text();
+        assert!(index.contains("<h1>silent</h1>"));
+
+        let res = client.get("/js/app.js").send().await;
+        let etag = res.header("etag").unwrap().to_string();
+        assert_eq!(res.header("content-encoding"), None);
+        assert_eq!(res.header("cache-control"), Some("no-cache"));
+        assert_eq!(res.bytes().as_ref(), file.data());
+        let res = client
+            .get("/js/app.js")
+            .header("if-none-match", etag.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        let res = client
+            .get("/js/app.js")
+            .header("range", "bytes=0-7")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.bytes().as_ref(), &file.data()[..8]);
+
+        let res = client
+            .get("/js/app.js")
+            .header("accept-encoding", "gzip")
+            .send()
+            .await;
+        assert_eq!(res.header("content-encoding"), Some("gzip"));
+        assert_ne!(res.header("etag"), Some(etag.as_str()));
+        assert_eq!(res.bytes().as_ref(), file.encoded("gzip").unwrap());
+
+        let res = client.get("/app/settings").send().await;
+        assert_eq!(res.text(), index);
+        let res = client.get("/../Cargo.toml").send().await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let client = TestClient::new(Route::new("assets").with_embedded(ASSETS));
+        let res = client.get("/assets/missing.css").send().await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,13 +1,83 @@
+use std::path::{Path, PathBuf};
+
 use async_trait::async_trait;
-use futures_util::StreamExt;
-use tokio::fs::File;
-use tokio_util::io::ReaderStream;
+use http::{HeaderValue, header};
+
+use super::static_serve::{
+    StaticFile, accepted_encodings, encoding_extension, not_found, safe_segments,
+};
+use crate::{Handler, Request, Response, SilentError};
 
-use crate::prelude::stream_body;
-use crate::{Handler, Request, Response, SilentError, StatusCode};
+/// 静态文件服务配置
+/// ```no_run
+/// use silent::prelude::*;
+/// let options = StaticOptions::default()
+///     .index(["index.html", "index.htm"])
+///     .fallback("index.html")
+///     .cache_control("public, max-age=3600");
+/// let route = Route::new("").with_static_options("static", options);
+/// ```
+#[derive(Clone)]
+pub struct StaticOptions {
+    pub(super) index: Vec<String>,
+    pub(super) directory_listing: bool,
+    pub(super) fallback: Option<String>,
+    pub(super) precompressed: bool,
+    pub(super) cache_control: Option<HeaderValue>,
+}
+
+impl Default for StaticOptions {
+    fn default() -> Self {
+        Self {
+            index: vec!["index.html".to_string()],
+            directory_listing: false,
+            fallback: None,
+            precompressed: true,
+            cache_control: Some(HeaderValue::from_static("no-cache")),
+        }
+    }
+}
+
+impl StaticOptions {
+    /// 目录的索引文件，按顺序查找，默认为 `index.html`
+    pub fn index<I, S>(mut self, files: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.index = files.into_iter().map(Into::into).collect();
+        self
+    }
+    /// 目录没有索引文件时列出目录内容，默认关闭
+    pub fn directory_listing(mut self, enable: bool) -> Self {
+        self.directory_listing = enable;
+        self
+    }
+    /// 文件不存在时返回的文件，用于单页应用，路径相对于根目录
+    pub fn fallback(mut self, file: &str) -> Self {
+        self.fallback = Some(file.to_string());
+        self
+    }
+    /// 客户端支持时返回同名的 `.br` 与 `.gz` 预压缩文件，默认开启
+    pub fn precompressed(mut self, enable: bool) -> Self {
+        self.precompressed = enable;
+        self
+    }
+    /// `cache-control` 响应头，默认为 `no-cache`，即每次使用前通过 ETag 验证
+    pub fn cache_control(mut self, value: &str) -> Self {
+        self.cache_control = Some(HeaderValue::from_str(value).expect("invalid cache-control"));
+        self
+    }
+    /// 不发送 `cache-control` 响应头
+    pub fn no_cache_control(mut self) -> Self {
+        self.cache_control = None;
+        self
+    }
+}
 
 struct HandlerWrapperStatic {
-    path: String,
+    path: PathBuf,
+    options: StaticOptions,
 }
 
 impl Default for HandlerWrapperStatic {
@@ -18,16 +88,72 @@ impl Default for HandlerWrapperStatic {
 
 impl HandlerWrapperStatic {
     fn new(path: &str) -> Self {
+        Self::with_options(path, StaticOptions::default())
+    }
+
+    fn with_options(path: &str, options: StaticOptions) -> Self {
         let mut path = path;
         if path.ends_with('/') {
             path = &path[..path.len() - 1];
         }
-        if !std::path::Path::new(path).is_dir() {
+        if !Path::new(path).is_dir() {
             panic!("Path not exists: {path}");
         }
         Self {
-            path: path.to_string(),
+            path: PathBuf::from(path),
+            options,
+        }
+    }
+
+    async fn serve(&self, req: &Request, path: &Path) -> Option<Response> {
+        let metadata = tokio::fs::metadata(path).await.ok()?;
+        if !metadata.is_dir() {
+            return self.serve_file(req, path).await;
+        }
+        for index in &self.options.index {
+            let index = path.join(index);
+            if tokio::fs::metadata(&index)
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+            {
+                return self.serve_file(req, &index).await;
+            }
+        }
+        if self.options.directory_listing {
+            return listing(req, path).await;
+        }
+        None
+    }
+
+    async fn serve_file(&self, req: &Request, path: &Path) -> Option<Response> {
+        let content_type = mime_guess::from_path(path).first_or_octet_stream();
+        let content_type = HeaderValue::from_str(content_type.as_ref()).ok()?;
+        let mut file = None;
+        if self.options.precompressed {
+            for encoding in accepted_encodings(req.headers()) {
+                let mut compressed = path.as_os_str().to_owned();
+                compressed.push(".");
+                compressed.push(encoding_extension(encoding));
+                if let Ok(compressed) =
+                    StaticFile::open(compressed.into(), content_type.clone(), Some(encoding)).await
+                {
+                    file = Some(compressed);
+                    break;
+                }
+            }
         }
+        let file = match file {
+            Some(file) => file,
+            None => StaticFile::open(path.to_path_buf(), content_type, None)
+                .await
+                .ok()?,
+        };
+        let mut res = file.into_response(req, self.options.cache_control.as_ref());
+        if self.options.precompressed {
+            res.headers_mut()
+                .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        }
+        Some(res)
     }
 }
 
@@ -35,40 +161,77 @@ impl HandlerWrapperStatic {
 impl Handler for HandlerWrapperStatic {
     async fn call(&self, req: Request) -> Result<Response, SilentError> {
         if let Ok(file_path) = req.get_path_params::<String>("path") {
-            // 文件路径使用url解码
-            let file_path =
-                urlencoding::decode(&file_path).map_err(|_| SilentError::BusinessError {
-                    code: StatusCode::NOT_FOUND,
-                    msg: "Not Found".to_string(),
-                })?;
-            let mut path = format!("{}/{}", self.path, file_path);
-            if path.ends_with('/') {
-                path.push_str("index.html");
+            // 文件路径使用url解码，并拒绝跳出根目录的路径
+            let file_path = urlencoding::decode(&file_path).map_err(|_| not_found())?;
+            let file_path = safe_segments(&file_path).ok_or_else(not_found)?;
+            if let Some(res) = self
+                .serve(&req, &self.path.join(file_path.iter().collect::<PathBuf>()))
+                .await
+            {
+                return Ok(res);
             }
-            if let Ok(file) = File::open(&path).await {
-                let mut res = Response::empty();
-                if let Some(content_type) = mime_guess::from_path(path).first() {
-                    res.set_typed_header(headers::ContentType::from(content_type));
-                } else {
-                    res.set_typed_header(headers::ContentType::octet_stream());
-                }
-                let reader_stream = ReaderStream::new(file);
-                let stream = reader_stream.boxed();
-                res.set_body(stream_body(stream));
+            if let Some(fallback) = self.options.fallback.as_deref().and_then(safe_segments)
+                && let Some(res) = self
+                    .serve_file(&req, &self.path.join(fallback.iter().collect::<PathBuf>()))
+                    .await
+            {
                 return Ok(res);
             }
         }
-        Err(SilentError::BusinessError {
-            code: StatusCode::NOT_FOUND,
-            msg: "Not Found".to_string(),
-        })
+        Err(not_found())
     }
 }
 
+/// 生成目录列表页面
+async fn listing(req: &Request, path: &Path) -> Option<Response> {
+    let mut entries = vec![];
+    let mut dir = tokio::fs::read_dir(path).await.ok()?;
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let is_dir = entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_dir());
+        entries.push((!is_dir, entry.file_name().to_string_lossy().to_string()));
+    }
+    entries.sort();
+    let base = req.uri().path().trim_end_matches('/');
+    let title = escape(&format!("{base}/"));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {title}</title></head>\n<body>\n<h1>Index of {title}</h1>\n<ul>\n"
+    );
+    for (is_file, name) in entries {
+        let slash = if is_file { "" } else { "/" };
+        html.push_str(&format!(
+            "<li><a href=\"{}/{}{slash}\">{}{slash}</a></li>\n",
+            escape(base),
+            urlencoding::encode(&name),
+            escape(&name)
+        ));
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+    let mut res = Response::html(&html);
+    res.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Some(res)
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub fn static_handler(path: &str) -> impl Handler {
     HandlerWrapperStatic::new(path)
 }
 
+/// 使用指定配置创建静态文件处理器
+pub fn static_handler_with_options(path: &str, options: StaticOptions) -> impl Handler {
+    HandlerWrapperStatic::with_options(path, options)
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
@@ -161,4 +324,147 @@ mod tests {
             panic!();
         }
     }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn test_static_options() {
+        use super::StaticOptions;
+
+        let path = "test_static_options";
+        std::fs::create_dir_all(format!("{path}/docs")).unwrap();
+        std::fs::write(format!("{path}/index.html"), CONTENT).unwrap();
+        std::fs::write(format!("{path}/data.txt"), "0123456789").unwrap();
+        std::fs::write(format!("{path}/app.js"), "plain").unwrap();
+        std::fs::write(format!("{path}/app.js.gz"), "gzipped").unwrap();
+        std::fs::write(format!("{path}/docs/a b.txt"), "a").unwrap();
+        let options = StaticOptions::default()
+            .directory_listing(true)
+            .fallback("index.html")
+            .cache_control("public, max-age=60");
+        let client = TestClient::new(Route::new("").with_static_options(path, options));
+
+        let res = client.get("/data.txt").send().await;
+        let etag = res.header("etag").unwrap().to_string();
+        let last_modified = res.header("last-modified").unwrap().to_string();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.header("content-length"), Some("10"));
+        assert_eq!(res.header("content-type"), Some("text/plain"));
+        assert_eq!(res.header("cache-control"), Some("public, max-age=60"));
+        assert_eq!(res.header("accept-ranges"), Some("bytes"));
+        assert_eq!(res.text(), "0123456789");
+
+        let res = client.head("/data.txt").send().await;
+        assert_eq!(res.header("content-length"), Some("10"));
+        assert!(res.bytes().is_empty());
+
+        // 条件请求
+        let res = client
+            .get("/data.txt")
+            .header("if-none-match", etag.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        let res = client
+            .get("/data.txt")
+            .header("if-modified-since", last_modified.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        let res = client
+            .get("/data.txt")
+            .header("if-none-match", "\"other\"")
+            .header("if-modified-since", last_modified.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // 区间请求
+        let res = client
+            .get("/data.txt")
+            .header("range", "bytes=2-4")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.header("content-range"), Some("bytes 2-4/10"));
+        assert_eq!(res.text(), "234");
+        let res = client
+            .get("/data.txt")
+            .header("range", "bytes=0-1,-2")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        let content_type = res.header("content-type").unwrap().to_string();
+        let boundary = content_type.split("boundary=").nth(1).unwrap();
+        let text = res.text();
+        assert_eq!(
+            res.header("content-length").unwrap(),
+            text.len().to_string()
+        );
+        assert!(text.starts_with(&format!("--{boundary}\r\n")));
+        assert!(text.contains("content-range: bytes 0-1/10\r\n\r\n01\r\n"));
+        assert!(text.contains("content-range: bytes 8-9/10\r\n\r\n89\r\n"));
+        assert!(text.ends_with(&format!("--{boundary}--\r\n")));
+        let res = client
+            .get("/data.txt")
+            .header("range", "bytes=20-")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(res.header("content-range"), Some("bytes */10"));
+        let res = client
+            .get("/data.txt")
+            .header("range", "bytes=2-4")
+            .header("if-range", etag.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        let res = client
+            .get("/data.txt")
+            .header("range", "bytes=2-4")
+            .header("if-range", "\"stale\"")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text(), "0123456789");
+
+        // 预压缩文件
+        let res = client
+            .get("/app.js")
+            .header("accept-encoding", "gzip, br")
+            .send()
+            .await;
+        assert_eq!(res.header("content-encoding"), Some("gzip"));
+        assert_eq!(res.header("vary"), Some("accept-encoding"));
+        assert_eq!(res.text(), "gzipped");
+        let res = client.get("/app.js").send().await;
+        assert_eq!(res.header("content-encoding"), None);
+        assert_eq!(res.text(), "plain");
+
+        // 目录列表与单页应用回退
+        let res = client.get("/docs/").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(
+            res.text()
+                .contains("<a href=\"/docs/a%20b.txt\">a b.txt</a>")
+        );
+        let res = client.get("/docs/a%20b.txt").send().await;
+        assert_eq!(res.text(), "a");
+        let res = client.get("/app/settings").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text(), CONTENT);
+
+        // 拒绝跳出根目录
+        let handler = HandlerWrapperStatic::new(path);
+        for file_path in [
+            "../Cargo.toml",
+            "docs/../../Cargo.toml",
+            "%2e%2e/Cargo.toml",
+        ] {
+            let mut req = Request::default();
+            req.set_path_params("path".to_owned(), PathParam::Path(file_path.to_string()));
+            let err = handler.call(req).await.unwrap_err();
+            assert_eq!(err.status(), StatusCode::NOT_FOUND);
+        }
+        std::fs::remove_dir_all(path).unwrap();
+    }
 }
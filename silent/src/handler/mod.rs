@@ -1,10 +1,18 @@
 /// Handler module
 mod handler_trait;
 mod handler_wrapper;
+#[cfg(feature = "embed")]
+mod handler_wrapper_embedded;
 #[cfg(feature = "static")]
 mod handler_wrapper_static;
+#[cfg(feature = "static")]
+pub(crate) mod static_serve;
 
 pub use handler_trait::Handler;
 pub use handler_wrapper::HandlerWrapper;
+#[cfg(feature = "embed")]
+pub use handler_wrapper_embedded::{
+    EmbeddedAssets, EmbeddedFile, EmbeddedVariant, embedded_handler,
+};
 #[cfg(feature = "static")]
-pub use handler_wrapper_static::static_handler;
+pub use handler_wrapper_static::{StaticOptions, static_handler, static_handler_with_options};
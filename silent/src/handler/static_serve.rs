@@ -0,0 +1,541 @@
+use crate::core::res_body::{ResBody, full, stream_body};
+use crate::headers::{
+    AcceptRanges, ContentLength, ContentRange, ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch,
+    IfRange, LastModified,
+};
+use crate::{Method, Request, Response, SilentError, StatusCode};
+use bytes::{Bytes, BytesMut};
+use futures_util::stream;
+use http::{HeaderMap, HeaderValue, header};
+use std::collections::VecDeque;
+use std::io::{self, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt};
+
+/// 单个请求最多处理的区间数量，超出时返回完整内容
+const MAX_RANGES: usize = 16;
+/// 读取文件的块大小
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// 文件内容的来源
+pub(crate) enum Content {
+    /// 已打开的磁盘文件
+    File(File),
+    /// 内存中的内容
+    #[cfg_attr(not(feature = "embed"), allow(dead_code))]
+    Bytes(Bytes),
+    /// 只能顺序读取的内容，多个区间时返回完整内容
+    Reader(Pin<Box<dyn AsyncRead + Send>>),
+}
+
+/// 待响应的静态文件
+pub(crate) struct StaticFile {
+    pub(crate) content: Content,
+    pub(crate) len: u64,
+    pub(crate) content_type: HeaderValue,
+    pub(crate) etag: Option<ETag>,
+    pub(crate) last_modified: Option<SystemTime>,
+    /// 预压缩文件的编码
+    pub(crate) encoding: Option<&'static str>,
+}
+
+impl StaticFile {
+    /// 打开磁盘文件，以长度与修改时间生成 ETag
+    pub(crate) async fn open(
+        path: PathBuf,
+        content_type: HeaderValue,
+        encoding: Option<&'static str>,
+    ) -> io::Result<Self> {
+        let file = File::open(&path).await?;
+        let metadata = file.metadata().await?;
+        if !metadata.is_file() {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        let last_modified = metadata.modified().ok();
+        let modified = last_modified
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_nanos());
+        let etag = format!("\"{:x}-{modified:x}\"", metadata.len())
+            .parse()
+            .map_err(|_| io::Error::other("invalid etag"))?;
+        Ok(Self {
+            content: Content::File(file),
+            len: metadata.len(),
+            content_type,
+            etag: Some(etag),
+            last_modified,
+            encoding,
+        })
+    }
+
+    /// 按请求的条件与区间生成响应
+    pub(crate) fn into_response(
+        self,
+        req: &Request,
+        cache_control: Option<&HeaderValue>,
+    ) -> Response {
+        let mut res = Response::empty();
+        if let Some(etag) = self.etag.clone() {
+            res.set_typed_header(etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            res.set_typed_header(LastModified::from(last_modified));
+        }
+        if let Some(cache_control) = cache_control {
+            res.headers_mut()
+                .insert(header::CACHE_CONTROL, cache_control.clone());
+        }
+        res.set_typed_header(AcceptRanges::bytes());
+        if let Some(encoding) = self.encoding {
+            res.headers_mut()
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+        }
+        if !self.modified(req.headers()) {
+            res.set_status(StatusCode::NOT_MODIFIED);
+            return res;
+        }
+
+        let ranges = match req.headers().get(header::RANGE) {
+            Some(range) if req.method() == Method::GET && self.if_range(req.headers()) => range
+                .to_str()
+                .ok()
+                .and_then(|range| parse_ranges(range, self.len))
+                .filter(|ranges| ranges.len() <= 1 || !matches!(self.content, Content::Reader(_))),
+            _ => None,
+        };
+        let head = req.method() == Method::HEAD;
+        let (segments, len) = match ranges {
+            None => {
+                res.headers_mut()
+                    .insert(header::CONTENT_TYPE, self.content_type.clone());
+                (vec![Segment::Range(0, self.len)], self.len)
+            }
+            Some(ranges) if ranges.is_empty() => {
+                res.set_status(StatusCode::RANGE_NOT_SATISFIABLE);
+                res.set_typed_header(ContentRange::unsatisfied_bytes(self.len));
+                res.set_typed_header(ContentLength(0));
+                return res;
+            }
+            Some(ranges) if ranges.len() == 1 => {
+                let (start, end) = ranges[0];
+                res.set_status(StatusCode::PARTIAL_CONTENT);
+                res.headers_mut()
+                    .insert(header::CONTENT_TYPE, self.content_type.clone());
+                if let Ok(range) = ContentRange::bytes(start..end, self.len) {
+                    res.set_typed_header(range);
+                }
+                (vec![Segment::Range(start, end)], end - start)
+            }
+            Some(ranges) => {
+                let boundary = uuid::Uuid::new_v4().simple().to_string();
+                res.set_status(StatusCode::PARTIAL_CONTENT);
+                if let Ok(content_type) =
+                    HeaderValue::from_str(&format!("multipart/byteranges; boundary={boundary}"))
+                {
+                    res.headers_mut().insert(header::CONTENT_TYPE, content_type);
+                }
+                let content_type = self.content_type.to_str().unwrap_or_default();
+                let mut segments = vec![];
+                for (index, (start, end)) in ranges.into_iter().enumerate() {
+                    let separator = if index == 0 { "" } else { "\r\n" };
+                    segments.push(Segment::Bytes(Bytes::from(format!(
+                        "{separator}--{boundary}\r\ncontent-type: {content_type}\r\ncontent-range: bytes {start}-{}/{}\r\n\r\n",
+                        end - 1,
+                        self.len
+                    ))));
+                    segments.push(Segment::Range(start, end));
+                }
+                segments.push(Segment::Bytes(Bytes::from(format!(
+                    "\r\n--{boundary}--\r\n"
+                ))));
+                let len = segments.iter().map(Segment::len).sum();
+                (segments, len)
+            }
+        };
+        res.set_typed_header(ContentLength(len));
+        if !head {
+            res.set_body(self.content.body(segments));
+        }
+        res
+    }
+
+    /// 按请求的条件与区间生成下载响应
+    pub(crate) fn into_download(self, req: &Request, disposition: HeaderValue) -> Response {
+        let mut res = self.into_response(req, None);
+        res.headers_mut()
+            .insert(header::CONTENT_DISPOSITION, disposition);
+        res
+    }
+
+    /// `If-None-Match` 优先于 `If-Modified-Since`
+    fn modified(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = headers.typed_get::<IfNoneMatch>() {
+            return self
+                .etag
+                .as_ref()
+                .is_none_or(|etag| if_none_match.precondition_passes(etag));
+        }
+        match (headers.typed_get::<IfModifiedSince>(), self.last_modified) {
+            (Some(since), Some(last_modified)) => since.is_modified(last_modified),
+            _ => true,
+        }
+    }
+
+    /// `If-Range` 不匹配时忽略 `Range` 并返回完整内容
+    fn if_range(&self, headers: &HeaderMap) -> bool {
+        headers.typed_get::<IfRange>().is_none_or(|if_range| {
+            let last_modified = self.last_modified.map(LastModified::from);
+            !if_range.is_modified(self.etag.as_ref(), last_modified.as_ref())
+        })
+    }
+}
+
+/// 生成 RFC 6266 的 `Content-Disposition`，非 ASCII 文件名使用 `filename*` 编码
+pub(crate) fn content_disposition(disposition: &'static str, filename: &str) -> HeaderValue {
+    let fallback = filename
+        .chars()
+        .map(|c| match c {
+            ' ' => c,
+            '"' | '\\' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    let value = if fallback == filename {
+        format!("{disposition}; filename=\"{filename}\"")
+    } else {
+        format!(
+            "{disposition}; filename=\"{fallback}\"; filename*=UTF-8''{}",
+            urlencoding::encode(filename)
+        )
+    };
+    HeaderValue::from_str(&value).unwrap_or_else(|_| HeaderValue::from_static(disposition))
+}
+
+/// 响应体片段，区间为左闭右开
+enum Segment {
+    Bytes(Bytes),
+    Range(u64, u64),
+}
+
+impl Segment {
+    fn len(&self) -> u64 {
+        match self {
+            Segment::Bytes(bytes) => bytes.len() as u64,
+            Segment::Range(start, end) => end - start,
+        }
+    }
+}
+
+impl Content {
+    fn body(self, segments: Vec<Segment>) -> ResBody {
+        match self {
+            Content::Bytes(content) => {
+                let mut body = BytesMut::new();
+                for segment in segments {
+                    match segment {
+                        Segment::Bytes(bytes) => body.extend_from_slice(&bytes),
+                        Segment::Range(start, end) => {
+                            body.extend_from_slice(&content[start as usize..end as usize])
+                        }
+                    }
+                }
+                full(body.freeze())
+            }
+            content => {
+                let state = (content, VecDeque::from(segments), 0u64, 0u64);
+                let stream = stream::try_unfold(
+                    state,
+                    |(mut content, mut segments, mut skip, mut remaining)| async move {
+                        loop {
+                            if skip > 0 || remaining > 0 {
+                                let want = if skip > 0 { skip } else { remaining };
+                                let mut buf = vec![0; want.min(CHUNK_SIZE) as usize];
+                                let read = content.read(&mut buf).await?;
+                                if read == 0 {
+                                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+                                }
+                                if skip > 0 {
+                                    skip -= read as u64;
+                                    continue;
+                                }
+                                buf.truncate(read);
+                                remaining -= read as u64;
+                                let state = (content, segments, skip, remaining);
+                                return Ok(Some((Bytes::from(buf), state)));
+                            }
+                            match segments.pop_front() {
+                                Some(Segment::Bytes(bytes)) => {
+                                    return Ok(Some((bytes, (content, segments, 0, 0))));
+                                }
+                                Some(Segment::Range(start, end)) => {
+                                    match &mut content {
+                                        Content::File(file) => {
+                                            file.seek(SeekFrom::Start(start)).await?;
+                                        }
+                                        // 顺序读取的内容只有一个区间，跳过区间之前的部分
+                                        _ => skip = start,
+                                    }
+                                    remaining = end - start;
+                                }
+                                None => return Ok(None),
+                            }
+                        }
+                    },
+                );
+                stream_body(stream)
+            }
+        }
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Content::File(file) => file.read(buf).await,
+            Content::Reader(reader) => reader.read(buf).await,
+            Content::Bytes(_) => Ok(0),
+        }
+    }
+}
+
+/// 将请求路径拆分为安全的路径段，包含 `..`、根目录或盘符时返回 `None`
+pub(crate) fn safe_segments(path: &str) -> Option<Vec<&str>> {
+    let mut segments = vec![];
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            segment => {
+                if segment.contains('\0')
+                    || Path::new(segment)
+                        .components()
+                        .any(|component| !matches!(component, Component::Normal(_)))
+                {
+                    return None;
+                }
+                segments.push(segment);
+            }
+        }
+    }
+    Some(segments)
+}
+
+pub(crate) fn not_found() -> SilentError {
+    SilentError::BusinessError {
+        code: StatusCode::NOT_FOUND,
+        msg: "Not Found".to_string(),
+    }
+}
+
+/// 解析 `Range` 请求头，返回左闭右开的区间
+///
+/// 格式无效时返回 `None` 以忽略该请求头，没有可满足的区间时返回空列表。
+pub(crate) fn parse_ranges(value: &str, len: u64) -> Option<Vec<(u64, u64)>> {
+    let specs = value.trim().strip_prefix("bytes=")?;
+    let mut ranges = vec![];
+    for spec in specs
+        .split(',')
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+    {
+        let (start, end) = spec.split_once('-')?;
+        let range = match (start.trim(), end.trim()) {
+            ("", suffix) => {
+                let suffix: u64 = suffix.parse().ok()?;
+                (suffix > 0 && len > 0).then(|| (len.saturating_sub(suffix), len))
+            }
+            (start, "") => {
+                let start: u64 = start.parse().ok()?;
+                (start < len).then_some((start, len))
+            }
+            (start, end) => {
+                let start: u64 = start.parse().ok()?;
+                let end: u64 = end.parse().ok()?;
+                if start > end {
+                    return None;
+                }
+                (start < len).then(|| (start, end.saturating_add(1).min(len)))
+            }
+        };
+        ranges.extend(range);
+    }
+    (ranges.len() <= MAX_RANGES).then_some(ranges)
+}
+
+/// 客户端接受的预压缩编码，按权重排序，权重相同时优先 `br`
+pub(crate) fn accepted_encodings(headers: &HeaderMap) -> Vec<&'static str> {
+    let mut encodings = headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let encoding = match params.next()?.trim().to_ascii_lowercase().as_str() {
+                "br" => "br",
+                "gzip" | "x-gzip" => "gzip",
+                _ => return None,
+            };
+            let quality = params
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+            (quality > 0.0).then_some((encoding, quality))
+        })
+        .collect::<Vec<_>>();
+    encodings.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| (a.0 != "br").cmp(&(b.0 != "br")))
+    });
+    encodings.dedup_by_key(|(encoding, _)| *encoding);
+    encodings
+        .into_iter()
+        .map(|(encoding, _)| encoding)
+        .collect()
+}
+
+/// 预压缩文件的扩展名
+pub(crate) fn encoding_extension(encoding: &str) -> &'static str {
+    match encoding {
+        "br" => "br",
+        _ => "gz",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(parse_ranges("bytes=0-4", 10), Some(vec![(0, 5)]));
+        assert_eq!(parse_ranges("bytes=5-", 10), Some(vec![(5, 10)]));
+        assert_eq!(parse_ranges("bytes=-3", 10), Some(vec![(7, 10)]));
+        assert_eq!(parse_ranges("bytes=-30", 10), Some(vec![(0, 10)]));
+        assert_eq!(parse_ranges("bytes=8-20", 10), Some(vec![(8, 10)]));
+        assert_eq!(
+            parse_ranges("bytes=0-1, 4-5", 10),
+            Some(vec![(0, 2), (4, 6)])
+        );
+        assert_eq!(parse_ranges("bytes=10-", 10), Some(vec![]));
+        assert_eq!(parse_ranges("bytes=5-1", 10), None);
+        assert_eq!(parse_ranges("items=0-1", 10), None);
+        assert_eq!(parse_ranges("bytes=a-1", 10), None);
+    }
+
+    #[test]
+    fn test_accepted_encodings() {
+        let mut headers = HeaderMap::new();
+        assert!(accepted_encodings(&headers).is_empty());
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip, deflate, br"),
+        );
+        assert_eq!(accepted_encodings(&headers), vec!["br", "gzip"]);
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("br;q=0.5, gzip;q=0.8, identity"),
+        );
+        assert_eq!(accepted_encodings(&headers), vec!["gzip", "br"]);
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("br;q=0, gzip"),
+        );
+        assert_eq!(accepted_encodings(&headers), vec!["gzip"]);
+    }
+
+    #[cfg(feature = "test")]
+    #[tokio::test]
+    async fn test_download() {
+        use crate::prelude::*;
+        use std::sync::Arc;
+
+        let export = |req: Request| async move {
+            let data = b"0123456789";
+            Ok(Response::attachment(&req, &data[..], "报告 \"1\".csv", 10))
+        };
+        let route = Route::new("")
+            .append(Route::new("file").get(|req: Request| async move {
+                Response::file(
+                    &req,
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/index.html"),
+                )
+                .await
+            }))
+            .append(Route::new("missing").get(|req: Request| async move {
+                Response::file(&req, "tests/assets/missing.txt").await
+            }))
+            .append(Route::new("export").get(export).handler(
+                Method::HEAD,
+                Arc::new(HandlerWrapper::new(|req: Request| async move {
+                    Ok(Response::attachment(
+                        &req,
+                        &b"0123456789"[..],
+                        "data.csv",
+                        10,
+                    ))
+                })),
+            ));
+        let client = TestClient::new(route);
+
+        let res = client.get("/export").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.header("content-type"), Some("text/csv"));
+        assert_eq!(res.header("content-length"), Some("10"));
+        assert_eq!(
+            res.header("content-disposition"),
+            Some(
+                "attachment; filename=\"__ _1_.csv\"; filename*=UTF-8''%E6%8A%A5%E5%91%8A%20%221%22.csv"
+            )
+        );
+        assert_eq!(res.text(), "0123456789");
+        let res = client
+            .get("/export")
+            .header("range", "bytes=3-5")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.header("content-range"), Some("bytes 3-5/10"));
+        assert_eq!(res.text(), "345");
+        let res = client
+            .get("/export")
+            .header("range", "bytes=0-1,4-5")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text(), "0123456789");
+        let res = client.head("/export").send().await;
+        assert_eq!(
+            res.header("content-disposition"),
+            Some("attachment; filename=\"data.csv\"")
+        );
+        assert_eq!(res.header("content-length"), Some("10"));
+        assert!(res.bytes().is_empty());
+
+        let res = client.get("/file").send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.header("content-type"), Some("text/html"));
+        assert_eq!(
+            res.header("content-disposition"),
+            Some("inline; filename=\"index.html\"")
+        );
+        assert!(res.text().contains("<h1>silent</h1>"));
+        let etag = res.header("etag").unwrap().to_string();
+        let res = client
+            .get("/file")
+            .header("if-none-match", etag.as_str())
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        let res = client
+            .get("/file")
+            .header("range", "bytes=0-5")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.text(), "<!DOCT");
+        let res = client.get("/missing").send().await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,3 +1,8 @@
+// 过程宏生成的代码通过 `::silent` 引用本 crate
+extern crate self as silent;
+
+#[cfg(feature = "client")]
+mod client;
 mod configs;
 #[cfg(feature = "cookie")]
 mod cookie;
@@ -10,6 +15,8 @@ mod handler;
 mod log;
 pub mod middleware;
 pub mod prelude;
+#[cfg(feature = "proxy")]
+mod proxy;
 mod route;
 #[cfg(feature = "scheduler")]
 mod scheduler;
@@ -19,10 +26,14 @@ mod security;
 mod service;
 #[cfg(feature = "session")]
 mod session;
+#[cfg(feature = "settings")]
+mod settings;
 #[cfg(feature = "sse")]
 mod sse;
 #[cfg(feature = "template")]
 mod templates;
+#[cfg(feature = "test")]
+mod test;
 #[cfg(feature = "upgrade")]
 mod ws;
 
@@ -32,13 +43,15 @@ mod ws;
 #[allow(clippy::single_component_path_imports)]
 use multer;
 
-pub use crate::configs::Configs;
+pub use crate::configs::{Configs, LiveConfig};
 #[cfg(feature = "cookie")]
-pub use crate::cookie::cookie_ext::CookieExt;
-pub use crate::core::{next::Next, request::Request, response::Response};
+pub use crate::cookie::cookie_ext::{CookieExt, CookieKeys};
+pub use crate::core::{next::Next, request::Request, response::Response, socket_addr::SocketAddr};
 #[cfg(feature = "grpc")]
 pub use crate::grpc::{GrpcHandler, GrpcRegister};
 pub use crate::middleware::{MiddleWareHandler, middlewares};
+#[cfg(feature = "session")]
+pub use crate::session::{SessionConfig, SessionMiddleware};
 pub use error::SilentError;
 pub use error::SilentResult as Result;
 pub use handler::Handler;
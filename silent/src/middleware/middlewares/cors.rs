@@ -1,8 +1,12 @@
-use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result, SilentError};
+use crate::{Handler, LiveConfig, MiddleWareHandler, Next, Request, Response, Result, SilentError};
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use http::{Method, header};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use regex::Regex;
+use std::fmt;
+use std::sync::Arc;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CorsType {
     Any,
     AllowSome(Vec<String>),
@@ -35,22 +39,81 @@ impl From<Vec<header::HeaderName>> for CorsType {
     }
 }
 
-#[derive(Debug)]
+type OriginPredicate = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// 单个来源匹配规则
+#[derive(Clone)]
+enum OriginMatcher {
+    Exact(String),
+    /// 形如 `https://*.example.com` 的通配符，`*` 不匹配 `/`
+    Wildcard(String, String),
+    Regex(Regex),
+    Predicate(OriginPredicate),
+}
+
+impl OriginMatcher {
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            OriginMatcher::Exact(value) => value == origin,
+            OriginMatcher::Wildcard(prefix, suffix) => {
+                origin.len() > prefix.len() + suffix.len()
+                    && origin.starts_with(prefix.as_str())
+                    && origin.ends_with(suffix.as_str())
+                    && !origin[prefix.len()..origin.len() - suffix.len()].contains('/')
+            }
+            OriginMatcher::Regex(regex) => regex.is_match(origin),
+            OriginMatcher::Predicate(predicate) => predicate(origin),
+        }
+    }
+}
+
+impl From<String> for OriginMatcher {
+    fn from(value: String) -> Self {
+        let value = value.trim().to_string();
+        match value.split_once('*') {
+            Some((prefix, suffix)) => {
+                OriginMatcher::Wildcard(prefix.to_string(), suffix.to_string())
+            }
+            None => OriginMatcher::Exact(value),
+        }
+    }
+}
+
+impl fmt::Debug for OriginMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OriginMatcher::Exact(value) => f.debug_tuple("Exact").field(value).finish(),
+            OriginMatcher::Wildcard(prefix, suffix) => {
+                write!(f, "Wildcard({prefix}*{suffix})")
+            }
+            OriginMatcher::Regex(regex) => f.debug_tuple("Regex").field(&regex.as_str()).finish(),
+            OriginMatcher::Predicate(_) => f.write_str("Predicate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 enum CorsOriginType {
     Any,
-    AllowSome(Vec<String>),
+    AllowSome(Vec<OriginMatcher>),
 }
 
 impl CorsOriginType {
-    fn get_value(&self, origin: &str) -> String {
+    fn is_allowed(&self, origin: &str) -> bool {
+        match self {
+            CorsOriginType::Any => true,
+            CorsOriginType::AllowSome(matchers) => {
+                matchers.iter().any(|matcher| matcher.matches(origin))
+            }
+        }
+    }
+
+    fn push(self, matcher: OriginMatcher) -> Self {
         match self {
-            CorsOriginType::Any => "*".to_string(),
-            CorsOriginType::AllowSome(value) => {
-                if let Some(v) = value.iter().find(|&v| v == origin) {
-                    v.to_string()
-                } else {
-                    "".to_string()
-                }
+            CorsOriginType::Any => CorsOriginType::AllowSome(vec![matcher]),
+            CorsOriginType::AllowSome(mut matchers) => {
+                matchers.push(matcher);
+                CorsOriginType::AllowSome(matchers)
             }
         }
     }
@@ -60,7 +123,9 @@ impl From<CorsType> for CorsOriginType {
     fn from(value: CorsType) -> Self {
         match value {
             CorsType::Any => CorsOriginType::Any,
-            CorsType::AllowSome(value) => CorsOriginType::AllowSome(value),
+            CorsType::AllowSome(value) => {
+                CorsOriginType::AllowSome(value.into_iter().map(OriginMatcher::from).collect())
+            }
         }
     }
 }
@@ -75,7 +140,48 @@ impl From<&str> for CorsType {
     }
 }
 
+/// 运行时可重新加载的允许来源
+///
+/// 注册到 `Configs` 后，[`Cors`] 会在每个请求中读取它并替代构建时设置的 `origin`，
+/// 持有其克隆的一方调用 [`CorsOrigins::set`] 即可即时生效。
+/// ```rust
+/// use silent::prelude::*;
+/// use silent::middlewares::CorsOrigins;
+/// let origins = CorsOrigins::new("https://a.example.com");
+/// let mut configs = Configs::default();
+/// configs.insert(origins.clone());
+/// origins.set("https://a.example.com,https://*.example.org");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CorsOrigins {
+    origin: Arc<ArcSwap<CorsOriginType>>,
+}
+
+impl CorsOrigins {
+    pub fn new<T: Into<CorsType>>(origin: T) -> Self {
+        Self {
+            origin: Arc::new(ArcSwap::from_pointee(origin.into().into())),
+        }
+    }
+    /// 替换允许的来源
+    pub fn set<T: Into<CorsType>>(&self, origin: T) {
+        self.origin.store(Arc::new(origin.into().into()));
+    }
+
+    fn is_allowed(&self, origin: &str) -> bool {
+        self.origin.load().is_allowed(origin)
+    }
+
+    fn is_any(&self) -> bool {
+        matches!(**self.origin.load(), CorsOriginType::Any)
+    }
+}
+
 /// cors 中间件
+///
+/// 带有 `Access-Control-Request-Method` 的 `OPTIONS` 预检请求直接响应 204，不会进入处理器；
+/// 来源不被允许（或未设置来源）时预检返回 403，普通请求则不附加 CORS 响应头。
+/// `credentials(true)` 与 `Any` 同时使用时会回显请求中的来源、方法与请求头，而不是返回 `*`。
 /// ```rust
 /// use silent::prelude::*;
 /// use silent::middlewares::{Cors, CorsType};
@@ -97,6 +203,12 @@ impl From<&str> for CorsType {
 ///                .methods("POST")
 ///                .headers("authorization,accept")
 ///                .credentials(true);
+/// // set with wildcard, regex or closure
+/// let _ = Cors::new()
+///                .origin("https://*.example.com")
+///                .origin_regex(r"https://.+\.example\.org")
+///                .origin_fn(|origin| origin.ends_with(".internal"));
+/// ```
 #[derive(Default, Debug)]
 pub struct Cors {
     origin: Option<CorsOriginType>,
@@ -105,12 +217,28 @@ pub struct Cors {
     credentials: Option<bool>,
     max_age: Option<u32>,
     expose: Option<CorsType>,
+    live: Option<LiveConfig<Cors>>,
 }
 
 impl Cors {
     pub fn new() -> Self {
         Self::default()
     }
+    /// 每个请求从 [`LiveConfig`] 读取完整的 cors 配置，替换后对新的请求立即生效
+    /// ```rust
+    /// use silent::prelude::*;
+    /// use silent::middlewares::Cors;
+    /// let cors = LiveConfig::new(Cors::new().origin("https://a.example.com"));
+    /// let route = Route::new("").hook(Cors::live(cors.clone()));
+    /// cors.store(Cors::new().origin("https://b.example.com").credentials(true));
+    /// ```
+    pub fn live(cors: LiveConfig<Cors>) -> Self {
+        Self {
+            live: Some(cors),
+            ..Self::default()
+        }
+    }
+    /// 允许的来源，包含 `*` 的来源按通配符匹配，如 `https://*.example.com`
     pub fn origin<T>(mut self, origin: T) -> Self
     where
         T: Into<CorsType>,
@@ -118,6 +246,27 @@ impl Cors {
         self.origin = Some(origin.into().into());
         self
     }
+    /// 追加按正则匹配的来源，正则需匹配完整来源
+    ///
+    /// # Panics
+    ///
+    /// 正则表达式无效时 panic
+    pub fn origin_regex(mut self, pattern: &str) -> Self {
+        let regex = Regex::new(&format!("^(?:{pattern})$")).expect("Cors: invalid origin regex");
+        self.origin = Some(self.origin_or_empty().push(OriginMatcher::Regex(regex)));
+        self
+    }
+    /// 追加由闭包判断的来源
+    pub fn origin_fn<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.origin = Some(
+            self.origin_or_empty()
+                .push(OriginMatcher::Predicate(Arc::new(predicate))),
+        );
+        self
+    }
     pub fn methods<T>(mut self, methods: T) -> Self
     where
         T: Into<CorsType>,
@@ -147,102 +296,389 @@ impl Cors {
         self.expose = Some(expose.into());
         self
     }
+
+    fn origin_or_empty(&mut self) -> CorsOriginType {
+        match self.origin.take() {
+            Some(CorsOriginType::AllowSome(matchers)) => CorsOriginType::AllowSome(matchers),
+            _ => CorsOriginType::AllowSome(vec![]),
+        }
+    }
+
+    fn allow_credentials(&self) -> bool {
+        self.credentials == Some(true)
+    }
+
+    /// 计算 `Access-Control-Allow-Origin` 的值，`None` 表示来源不被允许
+    fn allow_origin(&self, req: &Request, origin: Option<&str>) -> Option<String> {
+        let (is_any, is_allowed) = match req.configs().get::<CorsOrigins>() {
+            Some(origins) => (
+                origins.is_any(),
+                origin.is_some_and(|origin| origins.is_allowed(origin)),
+            ),
+            None => match self.origin {
+                Some(CorsOriginType::Any) => (true, origin.is_some()),
+                Some(ref origin_type) => (
+                    false,
+                    origin.is_some_and(|origin| origin_type.is_allowed(origin)),
+                ),
+                None => (false, false),
+            },
+        };
+        if !is_allowed {
+            return None;
+        }
+        if is_any && !self.allow_credentials() {
+            Some("*".to_string())
+        } else {
+            origin.map(str::to_string)
+        }
+    }
+
+    /// 是否需要 `Vary: Origin`，响应随请求来源变化时都需要
+    fn vary_origin(&self, req: &Request) -> bool {
+        let is_any = match req.configs().get::<CorsOrigins>() {
+            Some(origins) => origins.is_any(),
+            None if self.origin.is_none() => return false,
+            None => matches!(self.origin, Some(CorsOriginType::Any)),
+        };
+        !is_any || self.allow_credentials()
+    }
+
+    /// `Any` 与 credentials 同时使用时回显请求值
+    fn allow_value(&self, value: &CorsType, requested: Option<&HeaderValue>) -> Option<String> {
+        match value {
+            CorsType::Any if self.allow_credentials() => requested
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            value => Some(value.get_value()),
+        }
+    }
+
+    fn preflight(&self, req: &Request, allow_origin: String) -> Result<Response> {
+        let mut res = Response::empty();
+        res.set_status(StatusCode::NO_CONTENT);
+        let headers = res.headers_mut();
+        insert_header(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)?;
+        if let Some(ref methods) = self.methods
+            && let Some(value) = self.allow_value(
+                methods,
+                req.headers().get(header::ACCESS_CONTROL_REQUEST_METHOD),
+            )
+        {
+            insert_header(headers, header::ACCESS_CONTROL_ALLOW_METHODS, value)?;
+        }
+        if let Some(ref allow_headers) = self.headers
+            && let Some(value) = self.allow_value(
+                allow_headers,
+                req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS),
+            )
+        {
+            insert_header(headers, header::ACCESS_CONTROL_ALLOW_HEADERS, value)?;
+        }
+        if self.allow_credentials() {
+            insert_header(headers, header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true")?;
+        }
+        if let Some(max_age) = self.max_age {
+            insert_header(headers, header::ACCESS_CONTROL_MAX_AGE, max_age.to_string())?;
+        }
+        for vary in [
+            header::ORIGIN,
+            header::ACCESS_CONTROL_REQUEST_METHOD,
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+        ] {
+            headers.append(header::VARY, HeaderValue::from_name(vary));
+        }
+        Ok(res)
+    }
+}
+
+fn insert_header<T: AsRef<str>>(
+    headers: &mut HeaderMap,
+    name: header::HeaderName,
+    value: T,
+) -> Result<()> {
+    let value = HeaderValue::from_str(value.as_ref()).map_err(|e| {
+        SilentError::business_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Cors: Failed to parse {name}: {e}"),
+        )
+    })?;
+    headers.insert(name, value);
+    Ok(())
 }
 
 #[async_trait]
 impl MiddleWareHandler for Cors {
     async fn handle(&self, req: Request, next: &Next) -> Result<Response> {
+        if let Some(live) = &self.live {
+            return live.load().handle(req, next).await;
+        }
         let req_origin = req
             .headers()
-            .get("origin")
-            .map_or("", |v| v.to_str().unwrap_or(""))
-            .to_string();
-        let mut res = Response::empty();
-        if let Some(ref origin) = self.origin {
-            let origin = origin.get_value(&req_origin);
-            if origin.is_empty() {
-                return Err(SilentError::business_error(
-                    http::StatusCode::FORBIDDEN,
-                    format!("Cors: Origin \"{req_origin}\" is not allowed"),
-                ));
-            }
-            res.headers_mut().insert(
-                "Access-Control-Allow-Origin",
-                origin.parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors allow origin: {e}"),
-                    )
-                })?,
-            );
-        }
-        if let Some(ref methods) = self.methods {
-            res.headers_mut().insert(
-                "Access-Control-Allow-Methods",
-                methods.get_value().parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors allow methods: {e}"),
-                    )
-                })?,
-            );
+            .get(header::ORIGIN)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let allow_origin = self.allow_origin(&req, req_origin.as_deref());
+        let vary_origin = self.vary_origin(&req);
+
+        let is_preflight = req.method() == Method::OPTIONS
+            && req_origin.is_some()
+            && req
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+        if is_preflight {
+            return match allow_origin {
+                Some(allow_origin) => self.preflight(&req, allow_origin),
+                None => Err(SilentError::business_error(
+                    StatusCode::FORBIDDEN,
+                    format!(
+                        "Cors: Origin \"{}\" is not allowed",
+                        req_origin.unwrap_or_default()
+                    ),
+                )),
+            };
         }
-        if let Some(ref headers) = self.headers {
-            res.headers_mut().insert(
-                "Access-Control-Allow-Headers",
-                headers.get_value().parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors allow headers: {e}"),
-                    )
-                })?,
-            );
+
+        let mut res = match next.call(req).await {
+            Ok(res) => res,
+            Err(e) => e.into(),
+        };
+        let headers = res.headers_mut();
+        if vary_origin {
+            headers.append(header::VARY, HeaderValue::from_name(header::ORIGIN));
         }
-        if let Some(ref credentials) = self.credentials {
-            res.headers_mut().insert(
-                "Access-Control-Allow-Credentials",
-                credentials.to_string().parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors allow credentials: {e}"),
-                    )
-                })?,
-            );
+        if let Some(allow_origin) = allow_origin {
+            insert_header(headers, header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)?;
+            if self.allow_credentials() {
+                insert_header(headers, header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true")?;
+            }
+            if let Some(ref expose) = self.expose {
+                insert_header(
+                    headers,
+                    header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                    expose.get_value(),
+                )?;
+            }
         }
-        if let Some(ref max_age) = self.max_age {
-            res.headers_mut().insert(
-                "Access-Control-Max-Age",
-                max_age.to_string().parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors max age: {e}"),
-                    )
-                })?,
-            );
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn route(cors: Cors) -> Route {
+        let mut root = Route::new_root();
+        root.push(
+            Route::new("")
+                .hook(cors)
+                .get(|_req| async { Ok("hello") })
+                .post(|_req| async { Ok("hello") }),
+        );
+        root
+    }
+
+    async fn call(
+        route: &Route,
+        method: Method,
+        headers: &[(header::HeaderName, &str)],
+    ) -> Response {
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.method_mut() = method;
+        for (name, value) in headers {
+            req.headers_mut()
+                .insert(name.clone(), HeaderValue::from_str(value).unwrap());
         }
-        if let Some(ref expose) = self.expose {
-            res.headers_mut().insert(
-                "Access-Control-Expose-Headers",
-                expose.get_value().parse().map_err(|e| {
-                    SilentError::business_error(
-                        http::StatusCode::INTERNAL_SERVER_ERROR,
-                        format!("Cors: Failed to parse cors expose headers: {e}"),
-                    )
-                })?,
+        route.call(req).await.unwrap()
+    }
+
+    fn header_value(res: &Response, name: header::HeaderName) -> Option<&str> {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn test_preflight_short_circuit() {
+        let route = route(
+            Cors::new()
+                .origin("https://a.example.com")
+                .methods(vec![Method::GET, Method::POST])
+                .headers("authorization")
+                .max_age(600),
+        );
+        let res = call(
+            &route,
+            Method::OPTIONS,
+            &[
+                (header::ORIGIN, "https://a.example.com"),
+                (header::ACCESS_CONTROL_REQUEST_METHOD, "POST"),
+            ],
+        )
+        .await;
+        assert_eq!(res.status, StatusCode::NO_CONTENT);
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://a.example.com")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some("GET,POST")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_MAX_AGE),
+            Some("600")
+        );
+        assert_eq!(res.headers().get_all(header::VARY).iter().count(), 3);
+
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.method_mut() = Method::OPTIONS;
+        req.headers_mut().insert(
+            header::ORIGIN,
+            HeaderValue::from_static("https://b.example.com"),
+        );
+        req.headers_mut().insert(
+            header::ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("POST"),
+        );
+        let err = route.call(req).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_origin_matchers() {
+        let route = route(
+            Cors::new()
+                .origin("https://*.example.com")
+                .origin_regex(r"https://app\d+\.example\.org")
+                .origin_fn(|origin| origin.ends_with(".internal")),
+        );
+        for (origin, allowed) in [
+            ("https://a.example.com", true),
+            ("https://example.com", false),
+            ("https://evil.com/.example.com", false),
+            ("https://app1.example.org", true),
+            ("https://app1.example.org.evil.com", false),
+            ("http://service.internal", true),
+            ("https://other.com", false),
+        ] {
+            let res = call(&route, Method::GET, &[(header::ORIGIN, origin)]).await;
+            assert_eq!(res.status, StatusCode::OK);
+            assert_eq!(
+                header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+                allowed.then_some(origin),
+                "{origin}"
             );
+            assert_eq!(header_value(&res, header::VARY), Some("origin"));
         }
-        if req.method() == Method::OPTIONS {
-            return Ok(res);
-        }
-        match next.call(req).await {
-            Ok(result) => {
-                res.copy_from_response(result);
-                Ok(res)
-            }
-            Err(e) => {
-                res.copy_from_response(e.into());
-                Ok(res)
-            }
-        }
+    }
+
+    #[tokio::test]
+    async fn test_credentials_with_any() {
+        let route = route(
+            Cors::new()
+                .origin(CorsType::Any)
+                .methods(CorsType::Any)
+                .headers(CorsType::Any)
+                .credentials(true),
+        );
+        let res = call(
+            &route,
+            Method::OPTIONS,
+            &[
+                (header::ORIGIN, "https://a.example.com"),
+                (header::ACCESS_CONTROL_REQUEST_METHOD, "PUT"),
+                (header::ACCESS_CONTROL_REQUEST_HEADERS, "x-token"),
+            ],
+        )
+        .await;
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://a.example.com")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some("PUT")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("x-token")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+
+        // 不带 credentials 时返回 *，且不需要 Vary
+        let route = super::tests::route(Cors::new().origin(CorsType::Any));
+        let res = call(
+            &route,
+            Method::GET,
+            &[(header::ORIGIN, "https://a.example.com")],
+        )
+        .await;
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("*")
+        );
+        assert!(res.headers().get(header::VARY).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_origins_reload_from_configs() {
+        let origins = CorsOrigins::new("https://a.example.com");
+        let mut route = route(Cors::new().origin(CorsType::Any));
+        let mut configs = Configs::default();
+        configs.insert(origins.clone());
+        route.set_configs(Some(configs));
+
+        let res = call(
+            &route,
+            Method::GET,
+            &[(header::ORIGIN, "https://b.example.com")],
+        )
+        .await;
+        assert!(header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+
+        origins.set("https://*.example.com");
+        let res = call(
+            &route,
+            Method::GET,
+            &[(header::ORIGIN, "https://b.example.com")],
+        )
+        .await;
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://b.example.com")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_cors() {
+        let cors = LiveConfig::new(Cors::new().origin("https://a.example.com"));
+        let route = route(Cors::live(cors.clone()));
+        let headers = [(header::ORIGIN, "https://b.example.com")];
+
+        let res = call(&route, Method::GET, &headers).await;
+        assert!(header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+
+        cors.store(
+            Cors::new()
+                .origin("https://b.example.com")
+                .credentials(true),
+        );
+        let res = call(&route, Method::GET, &headers).await;
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://b.example.com")
+        );
+        assert_eq!(
+            header_value(&res, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
     }
 }
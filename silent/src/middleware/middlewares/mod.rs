@@ -1,9 +1,11 @@
 mod cors;
 mod exception_handler;
 mod request_time_logger;
+mod security_headers;
 mod timeout;
 
-pub use cors::{Cors, CorsType};
+pub use cors::{Cors, CorsOrigins, CorsType};
 pub use exception_handler::ExceptionHandler;
 pub use request_time_logger::RequestTimeLogger;
+pub use security_headers::{CSP_NONCE_PLACEHOLDER, CspNonce, FrameOptions, SecurityHeaders};
 pub use timeout::Timeout;
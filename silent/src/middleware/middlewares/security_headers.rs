@@ -0,0 +1,388 @@
+use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result};
+use async_trait::async_trait;
+use http::{HeaderName, HeaderValue, header};
+use std::fmt;
+use std::time::Duration;
+
+/// CSP 中 nonce 的占位符
+pub const CSP_NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// 每个请求生成的 CSP nonce
+///
+/// 由 [`SecurityHeaders`] 写入请求与响应的 extensions，
+/// 模板中间件会以 `csp_nonce` 变量注入到模板上下文。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CspNonce(String);
+
+impl CspNonce {
+    /// 生成新的 nonce
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+    /// 获取 nonce 字符串
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CspNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// X-Frame-Options 取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameOptions {
+    Deny,
+    SameOrigin,
+}
+
+impl FrameOptions {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FrameOptions::Deny => "DENY",
+            FrameOptions::SameOrigin => "SAMEORIGIN",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Hsts {
+    max_age: Duration,
+    include_subdomains: bool,
+    preload: bool,
+}
+
+impl Hsts {
+    fn value(&self) -> String {
+        let mut value = format!("max-age={}", self.max_age.as_secs());
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+        if self.preload {
+            value.push_str("; preload");
+        }
+        value
+    }
+}
+
+/// 安全响应头中间件
+///
+/// 默认设置 HSTS、`X-Frame-Options: DENY`、`X-Content-Type-Options: nosniff`、
+/// `Referrer-Policy: strict-origin-when-cross-origin`、
+/// `Cross-Origin-Opener-Policy: same-origin` 与 `Cross-Origin-Resource-Policy: same-origin`，
+/// 处理器已经设置的同名响应头不会被覆盖。
+///
+/// CSP 中的 `{nonce}` 会被替换为每个请求独立生成的 [`CspNonce`]。
+/// ```rust
+/// use silent::prelude::*;
+/// use silent::middlewares::{FrameOptions, SecurityHeaders};
+/// use std::time::Duration;
+/// let _ = SecurityHeaders::new()
+///                .hsts(Duration::from_secs(63072000), true, true)
+///                .content_security_policy("default-src 'self'; script-src 'self' 'nonce-{nonce}'")
+///                .frame_options(FrameOptions::SameOrigin)
+///                .permissions_policy("geolocation=()")
+///                .cross_origin_embedder_policy("require-corp");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    hsts: Option<Hsts>,
+    content_security_policy: Option<String>,
+    csp_report_only: bool,
+    frame_options: Option<FrameOptions>,
+    content_type_options: bool,
+    referrer_policy: Option<String>,
+    permissions_policy: Option<String>,
+    cross_origin_opener_policy: Option<String>,
+    cross_origin_embedder_policy: Option<String>,
+    cross_origin_resource_policy: Option<String>,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            hsts: Some(Hsts {
+                max_age: Duration::from_secs(31536000),
+                include_subdomains: true,
+                preload: false,
+            }),
+            content_security_policy: None,
+            csp_report_only: false,
+            frame_options: Some(FrameOptions::Deny),
+            content_type_options: true,
+            referrer_policy: Some("strict-origin-when-cross-origin".to_string()),
+            permissions_policy: None,
+            cross_origin_opener_policy: Some("same-origin".to_string()),
+            cross_origin_embedder_policy: None,
+            cross_origin_resource_policy: Some("same-origin".to_string()),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 清空所有默认值
+    pub fn none() -> Self {
+        Self {
+            hsts: None,
+            content_security_policy: None,
+            csp_report_only: false,
+            frame_options: None,
+            content_type_options: false,
+            referrer_policy: None,
+            permissions_policy: None,
+            cross_origin_opener_policy: None,
+            cross_origin_embedder_policy: None,
+            cross_origin_resource_policy: None,
+        }
+    }
+    pub fn hsts(mut self, max_age: Duration, include_subdomains: bool, preload: bool) -> Self {
+        self.hsts = Some(Hsts {
+            max_age,
+            include_subdomains,
+            preload,
+        });
+        self
+    }
+    pub fn disable_hsts(mut self) -> Self {
+        self.hsts = None;
+        self
+    }
+    pub fn content_security_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self.csp_report_only = false;
+        self
+    }
+    /// 以 `Content-Security-Policy-Report-Only` 发送 CSP
+    pub fn content_security_policy_report_only<T: Into<String>>(mut self, policy: T) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self.csp_report_only = true;
+        self
+    }
+    pub fn frame_options(mut self, frame_options: FrameOptions) -> Self {
+        self.frame_options = Some(frame_options);
+        self
+    }
+    pub fn disable_frame_options(mut self) -> Self {
+        self.frame_options = None;
+        self
+    }
+    pub fn content_type_options(mut self, nosniff: bool) -> Self {
+        self.content_type_options = nosniff;
+        self
+    }
+    pub fn referrer_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.referrer_policy = Some(policy.into());
+        self
+    }
+    pub fn permissions_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.permissions_policy = Some(policy.into());
+        self
+    }
+    pub fn cross_origin_opener_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.cross_origin_opener_policy = Some(policy.into());
+        self
+    }
+    pub fn cross_origin_embedder_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.cross_origin_embedder_policy = Some(policy.into());
+        self
+    }
+    pub fn cross_origin_resource_policy<T: Into<String>>(mut self, policy: T) -> Self {
+        self.cross_origin_resource_policy = Some(policy.into());
+        self
+    }
+
+    fn uses_nonce(&self) -> bool {
+        self.content_security_policy
+            .as_ref()
+            .is_some_and(|policy| policy.contains(CSP_NONCE_PLACEHOLDER))
+    }
+
+    fn header_values(&self, nonce: Option<&CspNonce>) -> Vec<(HeaderName, String)> {
+        let mut values = vec![];
+        if let Some(ref hsts) = self.hsts {
+            values.push((header::STRICT_TRANSPORT_SECURITY, hsts.value()));
+        }
+        if let Some(ref policy) = self.content_security_policy {
+            let policy = match nonce {
+                Some(nonce) => policy.replace(CSP_NONCE_PLACEHOLDER, nonce.as_str()),
+                None => policy.clone(),
+            };
+            let name = if self.csp_report_only {
+                header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+            } else {
+                header::CONTENT_SECURITY_POLICY
+            };
+            values.push((name, policy));
+        }
+        if let Some(frame_options) = self.frame_options {
+            values.push((header::X_FRAME_OPTIONS, frame_options.as_str().to_string()));
+        }
+        if self.content_type_options {
+            values.push((header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()));
+        }
+        if let Some(ref policy) = self.referrer_policy {
+            values.push((header::REFERRER_POLICY, policy.clone()));
+        }
+        if let Some(ref policy) = self.permissions_policy {
+            values.push((
+                HeaderName::from_static("permissions-policy"),
+                policy.clone(),
+            ));
+        }
+        if let Some(ref policy) = self.cross_origin_opener_policy {
+            values.push((
+                HeaderName::from_static("cross-origin-opener-policy"),
+                policy.clone(),
+            ));
+        }
+        if let Some(ref policy) = self.cross_origin_embedder_policy {
+            values.push((
+                HeaderName::from_static("cross-origin-embedder-policy"),
+                policy.clone(),
+            ));
+        }
+        if let Some(ref policy) = self.cross_origin_resource_policy {
+            values.push((
+                HeaderName::from_static("cross-origin-resource-policy"),
+                policy.clone(),
+            ));
+        }
+        values
+    }
+}
+
+#[async_trait]
+impl MiddleWareHandler for SecurityHeaders {
+    async fn handle(&self, mut req: Request, next: &Next) -> Result<Response> {
+        let nonce = if self.uses_nonce() {
+            // 多个 SecurityHeaders 作用于同一请求时共用外层生成的 nonce
+            let nonce = match req.extensions().get::<CspNonce>() {
+                Some(nonce) => nonce.clone(),
+                None => CspNonce::generate(),
+            };
+            req.extensions_mut().insert(nonce.clone());
+            Some(nonce)
+        } else {
+            None
+        };
+        let mut res = next.call(req).await?;
+        for (name, value) in self.header_values(nonce.as_ref()) {
+            if res.headers().contains_key(&name) {
+                continue;
+            }
+            match HeaderValue::from_str(&value) {
+                Ok(value) => {
+                    res.headers_mut().insert(name, value);
+                }
+                Err(e) => {
+                    tracing::error!("SecurityHeaders: invalid value for {}: {}", name, e);
+                }
+            }
+        }
+        if let Some(nonce) = nonce {
+            res.extensions_mut().insert(nonce);
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    async fn call(security_headers: SecurityHeaders, route: Route) -> Response {
+        let mut root = Route::new_root();
+        root.push(route.hook(security_headers));
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        root.call(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_default_headers() {
+        let route = Route::new("").get(|_req| async { Ok("hello") });
+        let res = call(SecurityHeaders::new(), route).await;
+        let headers = res.headers();
+        assert_eq!(
+            headers.get(header::STRICT_TRANSPORT_SECURITY).unwrap(),
+            "max-age=31536000; includeSubDomains"
+        );
+        assert_eq!(headers.get(header::X_FRAME_OPTIONS).unwrap(), "DENY");
+        assert_eq!(
+            headers.get(header::X_CONTENT_TYPE_OPTIONS).unwrap(),
+            "nosniff"
+        );
+        assert_eq!(
+            headers.get(header::REFERRER_POLICY).unwrap(),
+            "strict-origin-when-cross-origin"
+        );
+        assert_eq!(
+            headers.get("cross-origin-opener-policy").unwrap(),
+            "same-origin"
+        );
+        assert!(headers.get(header::CONTENT_SECURITY_POLICY).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handler_header_not_overridden() {
+        let route = Route::new("").get(|_req| async {
+            Ok(Response::text("hello").with_header(
+                header::X_FRAME_OPTIONS,
+                HeaderValue::from_static("SAMEORIGIN"),
+            ))
+        });
+        let res = call(SecurityHeaders::new(), route).await;
+        assert_eq!(
+            res.headers().get(header::X_FRAME_OPTIONS).unwrap(),
+            "SAMEORIGIN"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csp_nonce() {
+        let route = Route::new("").get(|req: Request| async move {
+            let nonce = req.extensions().get::<CspNonce>().cloned().unwrap();
+            Ok(nonce.to_string())
+        });
+        let security_headers =
+            SecurityHeaders::none().content_security_policy("script-src 'nonce-{nonce}'");
+        let res = call(security_headers, route).await;
+        let nonce = res.extensions().get::<CspNonce>().unwrap();
+        assert_eq!(
+            res.headers().get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            format!("script-src 'nonce-{nonce}'").as_str()
+        );
+        assert!(res.headers().get(header::X_FRAME_OPTIONS).is_none());
+        assert_ne!(nonce, &CspNonce::generate());
+    }
+
+    #[tokio::test]
+    async fn test_nested_csp_nonce() {
+        let policy = "script-src 'nonce-{nonce}'";
+        let route = Route::new("")
+            .hook(SecurityHeaders::none().content_security_policy(policy))
+            .get(|req: Request| async move {
+                let nonce = req.extensions().get::<CspNonce>().cloned().unwrap();
+                Ok(nonce.to_string())
+            });
+        let mut res = call(
+            SecurityHeaders::none().content_security_policy(policy),
+            route,
+        )
+        .await;
+        let nonce = res.body_bytes().await.unwrap();
+        let nonce = String::from_utf8_lossy(&nonce);
+        assert_eq!(
+            res.headers().get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            format!("script-src 'nonce-{nonce}'").as_str()
+        );
+        assert_eq!(res.extensions().get::<CspNonce>().unwrap().as_str(), nonce);
+    }
+}
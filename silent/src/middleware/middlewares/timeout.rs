@@ -1,4 +1,4 @@
-use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result, SilentError};
+use crate::{Handler, LiveConfig, MiddleWareHandler, Next, Request, Response, Result, SilentError};
 use async_trait::async_trait;
 use http::StatusCode;
 use std::time::Duration;
@@ -12,18 +12,38 @@ use std::time::Duration;
 #[derive(Default, Clone)]
 pub struct Timeout {
     timeout: Duration,
+    live: Option<LiveConfig<Duration>>,
 }
 
 impl Timeout {
     pub fn new(timeout: Duration) -> Self {
-        Self { timeout }
+        Self {
+            timeout,
+            live: None,
+        }
+    }
+    /// 每个请求从 [`LiveConfig`] 读取超时时间，更新后对新的请求立即生效
+    /// ```rust
+    /// use silent::prelude::*;
+    /// use silent::middlewares::Timeout;
+    /// use std::time::Duration;
+    /// let timeout = LiveConfig::new(Duration::from_secs(30));
+    /// let route = Route::new("").hook(Timeout::live(timeout.clone()));
+    /// timeout.store(Duration::from_secs(5));
+    /// ```
+    pub fn live(timeout: LiveConfig<Duration>) -> Self {
+        Self {
+            timeout: Duration::ZERO,
+            live: Some(timeout),
+        }
     }
 }
 
 #[async_trait]
 impl MiddleWareHandler for Timeout {
     async fn handle(&self, req: Request, next: &Next) -> Result<Response> {
-        match tokio::time::timeout(self.timeout, next.call(req))
+        let timeout = self.live.as_ref().map_or(self.timeout, |live| *live.load());
+        match tokio::time::timeout(timeout, next.call(req))
             .await
             .map_err(|_| {
                 SilentError::business_error(
@@ -36,3 +56,27 @@ impl MiddleWareHandler for Timeout {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn test_live_timeout() {
+        let timeout = LiveConfig::new(Duration::from_millis(10));
+        let route =
+            Route::new_root().append(Route::new("").hook(Timeout::live(timeout.clone())).get(
+                |_req| async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok("done")
+                },
+            ));
+        let err = route.call(Request::empty()).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::REQUEST_TIMEOUT);
+
+        timeout.store(Duration::from_secs(5));
+        let res = route.call(Request::empty()).await.unwrap();
+        assert_eq!(res.status, StatusCode::OK);
+    }
+}
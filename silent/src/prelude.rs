@@ -1,8 +1,18 @@
-pub use crate::configs::Configs;
+#[cfg(feature = "client")]
+pub use crate::client::{Client, ClientBuilder, ClientRequest};
+pub use crate::configs::{Configs, LiveConfig};
 #[cfg(feature = "cookie")]
-pub use crate::cookie::cookie_ext::CookieExt;
+pub use crate::cookie::cookie_ext::{CookieExt, CookieKeys};
+#[cfg(feature = "server")]
+pub use crate::core::channel_body::BodySender;
 #[cfg(feature = "multipart")]
 pub use crate::core::form::{FilePart, FormData};
+#[cfg(feature = "tls")]
+pub use crate::core::listener::TlsListener;
+#[cfg(feature = "multipart")]
+pub use crate::core::multipart::{Multipart, MultipartField, MultipartLimits};
+#[cfg(feature = "tls")]
+pub use crate::core::tls::{ReloadableTlsAcceptor, ReloadableTlsListener, TlsConfig};
 pub use crate::core::{
     listener::{Listen, Listener},
     next::Next,
@@ -20,24 +30,45 @@ pub use crate::error::{SilentError, SilentResult as Result};
 pub use crate::grpc::{GrpcHandler, GrpcRegister};
 pub use crate::handler::Handler;
 pub use crate::handler::HandlerWrapper;
+#[cfg(feature = "embed")]
+pub use crate::handler::{EmbeddedAssets, EmbeddedFile, EmbeddedVariant, embedded_handler};
 #[cfg(feature = "static")]
-pub use crate::handler::static_handler;
+pub use crate::handler::{StaticOptions, static_handler, static_handler_with_options};
 pub use crate::log::*;
 pub use crate::middleware::MiddleWareHandler;
+#[cfg(feature = "proxy")]
+pub use crate::proxy::{LoadBalance, Proxy, ProxyBuilder};
 pub use crate::route::handler_append::{HandlerAppend, HandlerGetter};
 pub use crate::route::{Route, RouteService, RouterAdapt};
 #[cfg(feature = "scheduler")]
 pub use crate::scheduler::{SCHEDULER, SchedulerExt, Task};
 #[cfg(feature = "security")]
-pub use crate::security::{argon2, pbkdf2};
+pub use crate::security::{Envelope, KeyRing, argon2, password, pbkdf2};
 #[cfg(feature = "server")]
-pub use crate::service::Server;
+pub use crate::service::{ConnectionInfo, HttpConfig, HttpProtocol, Server, ServerHandle, TlsInfo};
+#[cfg(feature = "http3")]
+pub use crate::service::{Http3Body, Http3Listener};
 #[cfg(feature = "session")]
 pub use crate::session::session_ext::SessionExt;
+#[cfg(feature = "session")]
+pub use crate::session::{
+    FileStore, RespStore, SessionConfig, SessionMiddleware, SignedCookieStore,
+};
+#[cfg(feature = "settings")]
+pub use crate::settings::{
+    LogSettings, ReloadableSettings, ServerSettings, Settings, SettingsError, SettingsLoader,
+    TlsSettings,
+};
 #[cfg(feature = "sse")]
 pub use crate::sse::{KeepAlive, SSEEvent, sse_reply};
 #[cfg(feature = "template")]
 pub use crate::templates::*;
+#[cfg(all(feature = "test", feature = "sse"))]
+pub use crate::test::TestEventStream;
+#[cfg(all(feature = "test", feature = "upgrade"))]
+pub use crate::test::TestWebSocket;
+#[cfg(feature = "test")]
+pub use crate::test::{MultipartForm, TestClient, TestRequest, TestResponse};
 #[cfg(feature = "upgrade")]
 pub use crate::ws::{
     FnOnClose, FnOnConnect, FnOnNoneResultFut, FnOnReceive, FnOnSend, FnOnSendFut, WSHandlerAppend,
@@ -45,8 +76,10 @@ pub use crate::ws::{
 #[cfg(feature = "upgrade")]
 pub use crate::ws::{Message, WebSocket, WebSocketHandler, WebSocketParts};
 #[cfg(feature = "session")]
-pub use async_session::{Session, SessionStore};
+pub use async_session::{MemoryStore, Session, SessionStore};
 #[cfg(feature = "cookie")]
-pub use cookie::{Cookie, CookieJar, Key, time as CookieTime};
+pub use cookie::{Cookie, CookieJar, Key, SameSite, time as CookieTime};
 pub use headers;
 pub use hyper::{Method, StatusCode, header, upgrade};
+#[cfg(feature = "embed")]
+pub use silent_macros::embed_assets;
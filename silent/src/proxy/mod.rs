@@ -0,0 +1,675 @@
+mod upstream;
+
+use crate::client::{Client, remove_hop_headers};
+use crate::core::res_body::ResBody;
+use crate::{Handler, Request, Response, Result, SilentError, StatusCode};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use http::{HeaderMap, HeaderValue, Version, header};
+use hyper::upgrade::OnUpgrade;
+use hyper_util::rt::TokioIo;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use upstream::{Upstream, UpstreamBody, UpstreamGuard};
+
+/// 负载均衡策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadBalance {
+    /// 依次轮询可用的上游
+    #[default]
+    RoundRobin,
+    /// 选择进行中请求最少的上游
+    LeastConnections,
+}
+
+/// 反向代理处理器
+///
+/// 将请求转发到一组上游服务，请求体与响应体以流的方式转发，支持 WebSocket 等协议升级。
+/// 转发路径默认取路径参数 `path`，不存在时使用完整的请求路径。
+/// ```no_run
+/// use silent::prelude::*;
+/// # fn route() -> Result<Route> {
+/// let proxy = Proxy::builder()
+///     .upstream("http://127.0.0.1:9001")
+///     .upstream("http://127.0.0.1:9002/api")
+///     .load_balance(LoadBalance::LeastConnections)
+///     .health_check("/health", std::time::Duration::from_secs(5))
+///     .build()?;
+/// // `/legacy/users?id=1` 转发到 `http://127.0.0.1:9001/users?id=1`
+/// // 或 `http://127.0.0.1:9002/api/users?id=1`
+/// Ok(Route::new("legacy/<path:**>").proxy(proxy))
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Proxy {
+    inner: Arc<ProxyInner>,
+}
+
+struct ProxyInner {
+    client: Client,
+    upstreams: Vec<Arc<Upstream>>,
+    load_balance: LoadBalance,
+    next: AtomicUsize,
+    path_param: String,
+    preserve_host: bool,
+    max_fails: u32,
+    fail_timeout: Duration,
+    health_check: Option<HealthCheck>,
+    health_started: AtomicBool,
+}
+
+struct HealthCheck {
+    path: String,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl Proxy {
+    /// 创建反向代理构造器
+    pub fn builder() -> ProxyBuilder {
+        ProxyBuilder::default()
+    }
+}
+
+impl ProxyInner {
+    /// 从轮转的起点开始挑选可用的上游
+    fn select(&self) -> Option<&Arc<Upstream>> {
+        let now = Instant::now();
+        let len = self.upstreams.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut available = (0..len)
+            .map(|i| &self.upstreams[(start + i) % len])
+            .filter(|upstream| upstream.available(now));
+        match self.load_balance {
+            LoadBalance::RoundRobin => available.next(),
+            LoadBalance::LeastConnections => available.min_by_key(|upstream| upstream.active()),
+        }
+    }
+
+    /// 在运行时中启动主动健康检查，只启动一次
+    fn start_health_check(self: &Arc<Self>) {
+        let Some(check) = &self.health_check else {
+            return;
+        };
+        if self.health_started.load(Ordering::Acquire)
+            || self.health_started.swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+        let interval = check.interval;
+        let inner = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                // 代理被释放后停止检查
+                let Some(inner) = inner.upgrade() else {
+                    break;
+                };
+                inner.check_health().await;
+            }
+        });
+    }
+
+    async fn check_health(&self) {
+        let Some(check) = &self.health_check else {
+            return;
+        };
+        join_all(self.upstreams.iter().map(|upstream| async move {
+            let healthy = match upstream.join(&check.path) {
+                Ok(uri) => self
+                    .client
+                    .get(&uri.to_string())
+                    .timeout(check.timeout)
+                    .send()
+                    .await
+                    .is_ok_and(|res| res.status.is_success() || res.status.is_redirection()),
+                Err(_) => false,
+            };
+            if !healthy {
+                tracing::warn!("upstream {} failed health check", upstream.uri());
+            }
+            upstream.set_healthy(healthy);
+        }))
+        .await;
+    }
+
+    /// 构造转发到上游的请求
+    fn upstream_request(
+        &self,
+        mut req: Request,
+        upstream: &Upstream,
+        upgrade: Option<HeaderValue>,
+    ) -> Result<http::Request<crate::core::req_body::ReqBody>> {
+        let path = match req.get_path_params::<String>(&self.path_param) {
+            Ok(path) => format!("/{}", path.trim_start_matches('/')),
+            Err(_) => req.uri().path().to_string(),
+        };
+        let path_and_query = match req.uri().query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path,
+        };
+        let uri = upstream.join(&path_and_query)?;
+        let host = req.headers().get(header::HOST).cloned().or_else(|| {
+            req.uri()
+                .authority()
+                .and_then(|authority| HeaderValue::from_str(authority.as_str()).ok())
+        });
+        let proto = proto(&req);
+        let peer = peer_ip(&req);
+        set_forwarded(req.headers_mut(), peer, host.as_ref(), proto);
+
+        let mut req = req.into_http();
+        *req.uri_mut() = uri;
+        // 请求可能来自 HTTP/2 连接，由客户端按连接协商结果决定版本
+        *req.version_mut() = Version::HTTP_11;
+        let headers = req.headers_mut();
+        remove_hop_headers(headers);
+        headers.remove(header::HOST);
+        if self.preserve_host
+            && let Some(host) = host
+        {
+            headers.insert(header::HOST, host);
+        }
+        if let Some(upgrade) = upgrade {
+            headers.insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+            headers.insert(header::UPGRADE, upgrade);
+        }
+        Ok(req)
+    }
+}
+
+#[async_trait]
+impl Handler for Proxy {
+    async fn call(&self, mut req: Request) -> Result<Response> {
+        let inner = &self.inner;
+        inner.start_health_check();
+        let upstream = inner.select().cloned().ok_or_else(|| {
+            SilentError::business_error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "no available upstream".to_string(),
+            )
+        })?;
+        let upgrade = upgrade_protocol(req.headers());
+        let on_upgrade = match upgrade {
+            Some(_) => Some(req.extensions_mut().remove::<OnUpgrade>().ok_or_else(|| {
+                SilentError::business_error(
+                    StatusCode::BAD_REQUEST,
+                    "connection can not be upgraded".to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let req = inner.upstream_request(req, &upstream, upgrade)?;
+
+        let guard = upstream.acquire();
+        let mut res = match inner.client.execute(req, inner.client.timeout()).await {
+            Ok(res) => {
+                upstream.success();
+                res
+            }
+            Err(e) => {
+                tracing::warn!("proxy to {} failed: {e}", upstream.uri());
+                upstream.failure(inner.max_fails, inner.fail_timeout);
+                return Err(e);
+            }
+        };
+
+        if let Some(on_upgrade) = on_upgrade
+            && res.status == StatusCode::SWITCHING_PROTOCOLS
+        {
+            let upstream_upgrade = res.extensions_mut().remove::<OnUpgrade>().ok_or_else(|| {
+                SilentError::business_error(
+                    StatusCode::BAD_GATEWAY,
+                    "upstream connection can not be upgraded".to_string(),
+                )
+            })?;
+            tokio::spawn(tunnel(on_upgrade, upstream_upgrade, guard));
+            return Ok(res);
+        }
+        remove_hop_headers(res.headers_mut());
+        if let ResBody::Incoming(body) = res.take_body() {
+            res.set_body(ResBody::Boxed(Box::pin(UpstreamBody {
+                body,
+                _guard: guard,
+            })));
+        }
+        Ok(res)
+    }
+}
+
+/// 在升级后的两个连接间双向转发数据
+async fn tunnel(client: OnUpgrade, upstream: OnUpgrade, _guard: UpstreamGuard) {
+    match tokio::try_join!(client, upstream) {
+        Ok((client, upstream)) => {
+            let mut client = TokioIo::new(client);
+            let mut upstream = TokioIo::new(upstream);
+            if let Err(e) = tokio::io::copy_bidirectional(&mut client, &mut upstream).await {
+                tracing::debug!("proxy tunnel closed: {e}");
+            }
+        }
+        Err(e) => tracing::warn!("proxy upgrade failed: {e}"),
+    }
+}
+
+/// 请求声明的升级协议，`connection` 需包含 `upgrade`
+fn upgrade_protocol(headers: &HeaderMap) -> Option<HeaderValue> {
+    let upgrade = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|token| token.trim().eq_ignore_ascii_case("upgrade"));
+    upgrade
+        .then(|| headers.get(header::UPGRADE).cloned())
+        .flatten()
+}
+
+fn proto(req: &Request) -> &'static str {
+    #[cfg(feature = "server")]
+    if req
+        .extensions()
+        .get::<crate::prelude::ConnectionInfo>()
+        .is_some_and(|info| info.tls().is_some())
+    {
+        return "https";
+    }
+    match req.uri().scheme_str() {
+        Some("https") => "https",
+        _ => "http",
+    }
+}
+
+/// 连接的对端 IP，不使用客户端可以伪造的 `x-real-ip`
+#[cfg_attr(not(feature = "server"), allow(unused_variables))]
+fn peer_ip(req: &Request) -> Option<std::net::IpAddr> {
+    #[cfg(feature = "server")]
+    if let Some(info) = req.extensions().get::<crate::prelude::ConnectionInfo>() {
+        return info.peer_addr().tcp_addr().map(|addr| addr.ip());
+    }
+    None
+}
+
+/// 按连接重新设置 `forwarded` 与 `x-forwarded-*` 请求头，并移除 `x-real-ip`
+///
+/// 客户端传入的同名请求头可以伪造，不予保留。
+fn set_forwarded(
+    headers: &mut HeaderMap,
+    ip: Option<std::net::IpAddr>,
+    host: Option<&HeaderValue>,
+    proto: &'static str,
+) {
+    for name in [
+        "x-real-ip",
+        "x-forwarded-for",
+        "x-forwarded-host",
+        "x-forwarded-proto",
+    ] {
+        headers.remove(name);
+    }
+    headers.remove(header::FORWARDED);
+    let host = host.and_then(|host| host.to_str().ok());
+
+    let mut forwarded = vec![];
+    match ip {
+        Some(std::net::IpAddr::V6(ip)) => forwarded.push(format!("for=\"[{ip}]\"")),
+        Some(ip) => forwarded.push(format!("for={ip}")),
+        None => forwarded.push("for=unknown".to_string()),
+    }
+    if let Some(host) = host {
+        forwarded.push(format!("host=\"{}\"", host.replace('"', "")));
+    }
+    forwarded.push(format!("proto={proto}"));
+    if let Ok(value) = HeaderValue::from_str(&forwarded.join(";")) {
+        headers.insert(header::FORWARDED, value);
+    }
+
+    if let Some(ip) = ip.and_then(|ip| HeaderValue::from_str(&ip.to_string()).ok()) {
+        headers.insert("x-forwarded-for", ip);
+    }
+    if let Some(host) = host.and_then(|host| HeaderValue::from_str(host).ok()) {
+        headers.insert("x-forwarded-host", host);
+    }
+    headers.insert("x-forwarded-proto", HeaderValue::from_static(proto));
+}
+
+/// [`Proxy`] 构造器
+pub struct ProxyBuilder {
+    client: Option<Client>,
+    upstreams: Vec<String>,
+    load_balance: LoadBalance,
+    path_param: String,
+    preserve_host: bool,
+    max_fails: u32,
+    fail_timeout: Duration,
+    health_check: Option<HealthCheck>,
+}
+
+impl Default for ProxyBuilder {
+    fn default() -> Self {
+        Self {
+            client: None,
+            upstreams: vec![],
+            load_balance: LoadBalance::default(),
+            path_param: "path".to_string(),
+            preserve_host: false,
+            max_fails: 3,
+            fail_timeout: Duration::from_secs(10),
+            health_check: None,
+        }
+    }
+}
+
+impl ProxyBuilder {
+    /// 添加上游地址，地址的路径作为转发路径的前缀
+    pub fn upstream(mut self, uri: &str) -> Self {
+        self.upstreams.push(uri.to_string());
+        self
+    }
+    /// 负载均衡策略，默认轮询
+    pub fn load_balance(mut self, load_balance: LoadBalance) -> Self {
+        self.load_balance = load_balance;
+        self
+    }
+    /// 转发请求使用的客户端，超时与 TLS 等配置取自客户端
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+    /// 作为转发路径的路径参数，默认为 `path`
+    pub fn path_param(mut self, name: &str) -> Self {
+        self.path_param = name.to_string();
+        self
+    }
+    /// 保留请求原有的 `host`，默认使用上游地址
+    pub fn preserve_host(mut self, preserve: bool) -> Self {
+        self.preserve_host = preserve;
+        self
+    }
+    /// 被动摘除：连续 `max_fails` 次连接失败或超时后，在 `fail_timeout` 内不再使用该上游
+    ///
+    /// 默认 3 次与 10 秒，`max_fails` 为 0 时不摘除。
+    pub fn passive_ejection(mut self, max_fails: u32, fail_timeout: Duration) -> Self {
+        self.max_fails = max_fails;
+        self.fail_timeout = fail_timeout;
+        self
+    }
+    /// 主动健康检查：每隔 `interval` 请求各上游的 `path`，响应非 2xx 与 3xx 的上游不再使用
+    pub fn health_check(mut self, path: &str, interval: Duration) -> Self {
+        let path = format!("/{}", path.trim_start_matches('/'));
+        self.health_check = Some(HealthCheck {
+            path,
+            interval,
+            timeout: interval.min(Duration::from_secs(5)),
+        });
+        self
+    }
+    /// 健康检查的请求超时时间，默认取检查间隔与 5 秒中的较小值
+    pub fn health_check_timeout(mut self, timeout: Duration) -> Self {
+        if let Some(check) = &mut self.health_check {
+            check.timeout = timeout;
+        }
+        self
+    }
+    /// 创建反向代理，上游地址无效或为空、默认客户端创建失败时返回错误
+    ///
+    /// 在运行时中创建时立即开始健康检查，否则在处理首个请求时开始。
+    pub fn build(self) -> Result<Proxy> {
+        if self.upstreams.is_empty() {
+            return Err(SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "proxy requires at least one upstream".to_string(),
+            ));
+        }
+        let upstreams = self
+            .upstreams
+            .iter()
+            .map(|uri| Upstream::new(uri).map(Arc::new))
+            .collect::<Result<Vec<_>>>()?;
+        let inner = Arc::new(ProxyInner {
+            client: match self.client {
+                Some(client) => client,
+                None => Client::new()?,
+            },
+            upstreams,
+            load_balance: self.load_balance,
+            next: AtomicUsize::new(0),
+            path_param: self.path_param,
+            preserve_host: self.preserve_host,
+            max_fails: self.max_fails,
+            fail_timeout: self.fail_timeout,
+            health_check: self.health_check,
+            health_started: AtomicBool::new(false),
+        });
+        if tokio::runtime::Handle::try_current().is_ok() {
+            inner.start_health_check();
+        }
+        Ok(Proxy { inner })
+    }
+}
+
+// 启用 tls 时测试需要加密实现
+#[cfg(all(
+    test,
+    feature = "server",
+    feature = "test",
+    any(not(feature = "tls"), feature = "aws-lc-rs", feature = "ring")
+))]
+mod tests {
+    use crate::prelude::*;
+    use std::time::Duration;
+
+    fn start(route: Route) -> (ServerHandle, String) {
+        let handle = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(route)
+            .unwrap();
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        (handle, format!("http://{addr}"))
+    }
+
+    fn upstream(name: &'static str, healthy: bool) -> Route {
+        Route::new("")
+            .append(Route::new("name").get(move |_req| async move { Ok(name) }))
+            .append(Route::new("echo").post(move |mut req: Request| async move {
+                let query = req.params().get("q").cloned().unwrap_or_default();
+                let header = |name: &str| {
+                    req.headers()
+                        .get(name)
+                        .map(|value| value.to_str().unwrap().to_string())
+                        .unwrap_or_default()
+                };
+                let head = [
+                    req.uri().path().to_string(),
+                    query,
+                    header("host"),
+                    header("forwarded"),
+                    header("x-forwarded-for"),
+                    header("x-forwarded-host"),
+                    header("x-forwarded-proto"),
+                    header("x-real-ip"),
+                ]
+                .join("|");
+                let body = req.take_body();
+                let body = http_body_util::BodyExt::collect(body).await?.to_bytes();
+                Ok(format!("{head}|{}", String::from_utf8_lossy(&body)))
+            }))
+            .append(Route::new("slow").get(move |_req| async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                Ok(name)
+            }))
+            .append(Route::new("health").get(move |_req| async move {
+                match healthy {
+                    true => Ok("ok"),
+                    false => Err(SilentError::business_error(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "down".to_string(),
+                    )),
+                }
+            }))
+    }
+
+    async fn name(client: &TestClient) -> String {
+        client.get("/legacy/name").send().await.text()
+    }
+
+    #[tokio::test]
+    async fn test_proxy() {
+        let (_a, a) = start(upstream("a", true));
+        let (_b, b) = start(upstream("b", true));
+        let proxy = Proxy::builder()
+            .upstream(&a)
+            .upstream(&format!("{b}/"))
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+
+        // 轮询依次使用各上游
+        let first = name(&client).await;
+        let second = name(&client).await;
+        assert_ne!(first, second);
+        assert_eq!(name(&client).await, first);
+
+        // 转发信息取自连接与请求，客户端伪造的同名请求头不会被使用或转发
+        let proxy = Proxy::builder().upstream(&a).build().unwrap();
+        let (_front, front) = start(Route::new("legacy/<path:**>").proxy(proxy));
+        let mut res = Client::new()
+            .unwrap()
+            .post(&format!("{front}/legacy/echo?q=1"))
+            .header("host", "example.com")
+            .header("forwarded", "for=10.0.0.1;host=evil.com;proto=https")
+            .header("x-forwarded-for", "10.0.0.1")
+            .header("x-forwarded-host", "evil.com")
+            .header("x-forwarded-proto", "https")
+            .header("x-real-ip", "203.0.113.7:1234")
+            .body("payload")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status, StatusCode::OK);
+        let text = res.body_text().await.unwrap();
+        let parts = text.split('|').collect::<Vec<_>>();
+        assert_eq!(parts[0], "/echo");
+        assert_eq!(parts[1], "1");
+        assert!(a.ends_with(parts[2]));
+        assert_eq!(parts[3], "for=127.0.0.1;host=\"example.com\";proto=http");
+        assert_eq!(parts[4], "127.0.0.1");
+        assert_eq!(parts[5], "example.com");
+        assert_eq!(parts[6], "http");
+        assert!(parts[7].starts_with("127.0.0.1:"));
+        assert_eq!(parts[8], "payload");
+
+        let proxy = Proxy::builder()
+            .upstream(&a)
+            .preserve_host(true)
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+        let text = client
+            .post("/legacy/echo")
+            .header("host", "example.com")
+            .send()
+            .await
+            .text();
+        assert_eq!(text.split('|').nth(2), Some("example.com"));
+
+        assert!(Proxy::builder().build().is_err());
+        assert!(Proxy::builder().upstream("/relative").build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_least_connections() {
+        let (_a, a) = start(upstream("a", true));
+        let (_b, b) = start(upstream("b", true));
+        let proxy = Proxy::builder()
+            .upstream(&a)
+            .upstream(&b)
+            .load_balance(LoadBalance::LeastConnections)
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+        let slow = {
+            let client = client.clone();
+            tokio::spawn(async move { client.get("/legacy/slow").send().await.text() })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // 慢请求未完成时其余请求都转发到另一个上游
+        let other = name(&client).await;
+        assert_eq!(name(&client).await, other);
+        assert_ne!(slow.await.unwrap(), other);
+    }
+
+    #[tokio::test]
+    async fn test_health() {
+        let (_a, a) = start(upstream("a", true));
+        let (_b, b) = start(upstream("b", false));
+        let proxy = Proxy::builder()
+            .upstream(&a)
+            .upstream(&b)
+            .health_check("/health", Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        for _ in 0..4 {
+            assert_eq!(name(&client).await, "a");
+        }
+
+        // 连接失败的上游被动摘除
+        let proxy = Proxy::builder()
+            .upstream("http://127.0.0.1:1")
+            .upstream(&a)
+            .passive_ejection(1, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+        let res = client.get("/legacy/name").send().await;
+        assert_eq!(res.status(), StatusCode::BAD_GATEWAY);
+        for _ in 0..4 {
+            assert_eq!(name(&client).await, "a");
+        }
+
+        let proxy = Proxy::builder()
+            .upstream(&b)
+            .health_check("/health", Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let res = client.get("/legacy/name").send().await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[cfg(feature = "upgrade")]
+    #[tokio::test]
+    async fn test_websocket() {
+        use tokio::sync::mpsc::UnboundedSender;
+
+        let route = Route::new("ws").ws(
+            None,
+            WebSocketHandler::new()
+                .on_connect(|parts, sender: UnboundedSender<Message>| async move {
+                    parts.write().await.extensions_mut().insert(sender);
+                    Ok(())
+                })
+                .on_send(|msg, _| async { Ok(msg) })
+                .on_receive(|msg, parts| async move {
+                    let parts = parts.read().await;
+                    let sender = parts.extensions().get::<UnboundedSender<Message>>();
+                    sender.unwrap().send(msg).unwrap();
+                    Ok(())
+                })
+                .on_close(|_| async {}),
+        );
+        let (_upstream, upstream) = start(route);
+        let proxy = Proxy::builder().upstream(&upstream).build().unwrap();
+        let client = TestClient::new(Route::new("legacy/<path:**>").proxy(proxy));
+
+        let mut ws = client.get("/legacy/ws").websocket().await.unwrap();
+        ws.send(Message::text("hello")).await.unwrap();
+        let msg = ws.recv().await.unwrap().unwrap();
+        assert_eq!(msg.to_str().unwrap(), "hello");
+        ws.close().await.unwrap();
+    }
+}
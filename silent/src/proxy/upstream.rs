@@ -0,0 +1,131 @@
+use crate::client::join_uri;
+use crate::error::BoxedError;
+use crate::{Result, SilentError, StatusCode};
+use bytes::Bytes;
+use http::Uri;
+use http_body::{Body, Frame, SizeHint};
+use hyper::body::Incoming;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// 上游服务及其状态
+pub(super) struct Upstream {
+    uri: Uri,
+    /// 进行中的请求数
+    active: AtomicUsize,
+    /// 最近一次主动健康检查的结果
+    healthy: AtomicBool,
+    /// 连续失败次数
+    fails: AtomicU32,
+    /// 被动摘除的截止时间
+    ejected_until: Mutex<Option<Instant>>,
+}
+
+impl Upstream {
+    pub(super) fn new(uri: &str) -> Result<Self> {
+        let uri: Uri = uri.parse().map_err(|e| {
+            SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("invalid upstream `{uri}`: {e}"),
+            )
+        })?;
+        if uri.scheme().is_none() || uri.authority().is_none() {
+            return Err(SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("upstream must be an absolute uri: {uri}"),
+            ));
+        }
+        Ok(Self {
+            uri,
+            active: AtomicUsize::new(0),
+            healthy: AtomicBool::new(true),
+            fails: AtomicU32::new(0),
+            ejected_until: Mutex::new(None),
+        })
+    }
+
+    pub(super) fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// 拼接上游地址的路径前缀与请求的路径、查询参数
+    pub(super) fn join(&self, path_and_query: &str) -> Result<Uri> {
+        join_uri(&self.uri, path_and_query)
+    }
+
+    /// 通过健康检查且未被摘除
+    pub(super) fn available(&self, now: Instant) -> bool {
+        self.healthy.load(Ordering::Acquire)
+            && self
+                .ejected_until
+                .lock()
+                .unwrap()
+                .is_none_or(|until| until <= now)
+    }
+
+    pub(super) fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub(super) fn set_healthy(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Release);
+    }
+
+    pub(super) fn success(&self) {
+        self.fails.store(0, Ordering::Release);
+    }
+
+    /// 记录一次失败，连续失败达到 `max_fails` 次时摘除 `fail_timeout`
+    pub(super) fn failure(&self, max_fails: u32, fail_timeout: Duration) {
+        if max_fails == 0 {
+            return;
+        }
+        if self.fails.fetch_add(1, Ordering::AcqRel) + 1 >= max_fails {
+            self.fails.store(0, Ordering::Release);
+            *self.ejected_until.lock().unwrap() = Some(Instant::now() + fail_timeout);
+        }
+    }
+
+    /// 标记请求开始，返回的守卫释放时视为请求结束
+    pub(super) fn acquire(self: &Arc<Self>) -> UpstreamGuard {
+        self.active.fetch_add(1, Ordering::AcqRel);
+        UpstreamGuard(self.clone())
+    }
+}
+
+pub(super) struct UpstreamGuard(Arc<Upstream>);
+
+impl Drop for UpstreamGuard {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// 上游响应体，读取完成或被丢弃时才结束请求计数
+pub(super) struct UpstreamBody {
+    pub(super) body: Incoming,
+    pub(super) _guard: UpstreamGuard,
+}
+
+impl Body for UpstreamBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.body).poll_frame(cx).map_err(Into::into)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
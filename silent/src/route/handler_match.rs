@@ -1,7 +1,7 @@
 use super::Route;
 use crate::MiddleWareHandler;
-use crate::Request;
 use crate::core::path_param::PathParam;
+use crate::{Method, Request};
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -269,6 +269,40 @@ impl Match for Route {
     }
 }
 
+/// 放宽 `OPTIONS` 请求匹配的标记，只在第二次匹配期间存在
+#[derive(Clone)]
+struct OptionsFallback;
+
+impl Route {
+    /// 匹配路由并收集中间件
+    ///
+    /// `OPTIONS` 请求没有找到注册了 `OPTIONS` 处理器的路由时，再匹配存在任意处理器的路由，
+    /// 以便 CORS 等中间件处理预检请求，未被中间件处理时由路由返回 405。
+    pub(crate) fn match_request(
+        &self,
+        req: &mut Request,
+        path: &str,
+    ) -> (RouteMatched, Vec<Vec<Arc<dyn MiddleWareHandler>>>) {
+        let params = req.path_params().clone();
+        let matched = self.handler_match_collect_middlewares(req, path);
+        if !matches!(matched.0, RouteMatched::Unmatched) || req.method() != Method::OPTIONS {
+            return matched;
+        }
+        // 第一次匹配失败的路由设置的路径参数不能带入第二次匹配
+        *req.path_params_mut() = params;
+        req.extensions_mut().insert(OptionsFallback);
+        let matched = self.handler_match_collect_middlewares(req, path);
+        req.extensions_mut().remove::<OptionsFallback>();
+        matched
+    }
+
+    /// 路由是否处理该请求方法
+    fn accepts_method(&self, req: &Request) -> bool {
+        self.handler.contains_key(req.method())
+            || (req.extensions().get::<OptionsFallback>().is_some() && !self.handler.is_empty())
+    }
+}
+
 impl RouteMatch for Route {
     fn get_path(&self) -> &str {
         self.path.as_str()
@@ -277,7 +311,7 @@ impl RouteMatch for Route {
     fn last_matched(&self, req: &mut Request, last_url: &str) -> RouteMatched {
         if last_url.is_empty() {
             // 如果当前路由有对应方法的handler，返回匹配
-            if self.handler.contains_key(req.method()) {
+            if self.accepts_method(req) {
                 let mut cloned_route = self.clone();
                 // 确保克隆的路由包含正确的configs信息
                 if cloned_route.configs.is_none() && self.configs.is_some() {
@@ -332,7 +366,7 @@ impl RouteMatch for Route {
             }
 
             // 如果当前路由有对应方法的handler，返回匹配
-            if self.handler.contains_key(req.method()) {
+            if self.accepts_method(req) {
                 let mut cloned_route = self.clone();
                 // 确保克隆的路由包含正确的configs信息
                 if cloned_route.configs.is_none() && self.configs.is_some() {
@@ -378,7 +412,7 @@ impl RouteMatch for Route {
             }
 
             // 如果子路由都匹配失败，再检查当前路由是否有对应方法的handler
-            if self.handler.contains_key(req.method()) {
+            if self.accepts_method(req) {
                 let mut middleware_layers = vec![];
                 if !self.middlewares.is_empty() {
                     middleware_layers.push(self.middlewares.clone());
@@ -413,7 +447,7 @@ impl RouteMatch for Route {
 mod tests {
     use super::*;
     use crate::prelude::HandlerAppend;
-    use crate::{Handler, Method, SilentError};
+    use crate::{Handler, SilentError};
     use bytes::Bytes;
     use http_body_util::BodyExt;
 
@@ -563,6 +597,63 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn options_sibling_match_test() {
+        let mut routes = Route::new_root();
+        routes.push(Route::new("api/<id>").get(hello));
+        routes.push(Route::new("<path:**>").options(world));
+        let call = |method: Method, uri: &'static str| {
+            let mut req = Request::empty();
+            req.set_remote("127.0.0.1:8080".parse().unwrap());
+            *req.method_mut() = method;
+            *req.uri_mut() = uri.parse().unwrap();
+            routes.call(req)
+        };
+
+        // 后注册的兄弟路由处理 OPTIONS，不会被只有 GET 的路由遮蔽
+        let res = call(Method::OPTIONS, "/api/1").await.unwrap();
+        let body = res.body.collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from("world"));
+        let res = call(Method::GET, "/api/1").await.unwrap();
+        let body = res.body.collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from("hello"));
+
+        // 没有路由处理 OPTIONS 时回退到路径匹配的路由，返回 405
+        let mut routes = Route::new_root();
+        routes.push(Route::new("api/<id>").get(hello));
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.method_mut() = Method::OPTIONS;
+        *req.uri_mut() = "/api/1".parse().unwrap();
+        let err = routes.call(req).await.unwrap_err();
+        assert_eq!(err.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.method_mut() = Method::OPTIONS;
+        *req.uri_mut() = "/missing".parse().unwrap();
+        let err = routes.call(req).await.unwrap_err();
+        assert_eq!(err.status(), http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn options_fallback_params_test() {
+        let mut routes = Route::new_root();
+        routes.push(Route::new("api/5").get(hello));
+        routes.push(Route::new("api/<id:int>").get(hello));
+        let mut req = Request::empty();
+        *req.method_mut() = Method::OPTIONS;
+        *req.uri_mut() = "/api/5".parse().unwrap();
+        let (mut req, path) = req.split_url();
+
+        // 第一次匹配经过 `<id:int>` 设置了参数，第二次匹配到不带参数的 `api/5`
+        let (matched, _) = routes.match_request(&mut req, path.as_str());
+        match matched {
+            RouteMatched::Matched(route) => assert_eq!(route.path, "5"),
+            RouteMatched::Unmatched => panic!("route should match"),
+        }
+        assert!(req.path_params().is_empty());
+    }
+
     // 边界情况测试
     #[test]
     fn empty_path_edge_case_test() {
@@ -7,12 +7,14 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::handler::Handler;
+#[cfg(feature = "embed")]
+use crate::handler::{EmbeddedAssets, embedded_handler};
 #[cfg(feature = "static")]
-use crate::handler::static_handler;
+use crate::handler::{StaticOptions, static_handler, static_handler_with_options};
 use crate::middleware::MiddleWareHandler;
 #[cfg(feature = "static")]
 use crate::prelude::HandlerGetter;
-use crate::route::handler_match::{Match, RouteMatched};
+use crate::route::handler_match::RouteMatched;
 use crate::{HandlerWrapper, Method, Next, Request, Response, SilentError};
 
 pub(crate) mod handler_append;
@@ -41,8 +43,25 @@ impl Handler for LayeredHandler {
                     }
                 }
 
-                let next = Next::build(Arc::new(route), flattened_middlewares);
-                next.call(req).await
+                // 匹配路由的中间件已收集在层级中，直接调用对应方法的处理器，避免中间件重复执行
+                // 方法不存在时仍经过中间件，以便 CORS 预检等中间件可以直接响应
+                let handler = match route.handler.get(req.method()).cloned() {
+                    Some(handler) => handler,
+                    None => {
+                        let handler = |_req| async move {
+                            Err::<(), SilentError>(SilentError::business_error(
+                                StatusCode::METHOD_NOT_ALLOWED,
+                                "method not allowed".to_string(),
+                            ))
+                        };
+                        Arc::new(HandlerWrapper::new(handler))
+                    }
+                };
+                let configs = req.configs();
+                let next = Next::build(handler, flattened_middlewares);
+                let mut res = next.call(req).await?;
+                res.configs = configs;
+                Ok(res)
             }
             RouteMatched::Unmatched => {
                 let handler = |_req| async move { Err::<(), SilentError>(SilentError::NotFound) };
@@ -196,8 +215,58 @@ impl Route {
 
     #[cfg(feature = "static")]
     pub fn with_static(self, path: &str) -> Self {
+        let handler: Arc<dyn Handler> = Arc::new(static_handler(path));
+        self.append(
+            Route::new("<path:**>")
+                .insert_handler(Method::GET, handler.clone())
+                .insert_handler(Method::HEAD, handler),
+        )
+    }
+
+    /// 使用指定配置挂载静态文件目录
+    #[cfg(feature = "static")]
+    pub fn with_static_options(self, path: &str, options: StaticOptions) -> Self {
+        let handler: Arc<dyn Handler> = Arc::new(static_handler_with_options(path, options));
+        self.append(
+            Route::new("<path:**>")
+                .insert_handler(Method::GET, handler.clone())
+                .insert_handler(Method::HEAD, handler),
+        )
+    }
+
+    /// 将当前路由的所有请求方法交给反向代理处理
+    #[cfg(feature = "proxy")]
+    pub fn proxy(self, proxy: crate::proxy::Proxy) -> Self {
+        use crate::prelude::HandlerGetter;
+        let proxy: Arc<dyn Handler> = Arc::new(proxy);
+        [
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::PATCH,
+            Method::HEAD,
+            Method::OPTIONS,
+            Method::TRACE,
+        ]
+        .into_iter()
+        .fold(self, |route, method| route.handler(method, proxy.clone()))
+    }
+
+    /// 挂载编译期嵌入的静态文件
+    #[cfg(feature = "embed")]
+    pub fn with_embedded(self, assets: EmbeddedAssets) -> Self {
+        self.with_embedded_options(assets, StaticOptions::default())
+    }
+
+    /// 使用指定配置挂载编译期嵌入的静态文件
+    #[cfg(feature = "embed")]
+    pub fn with_embedded_options(self, assets: EmbeddedAssets, options: StaticOptions) -> Self {
+        let handler: Arc<dyn Handler> = Arc::new(embedded_handler(assets, options));
         self.append(
-            Route::new("<path:**>").insert_handler(Method::GET, Arc::new(static_handler(path))),
+            Route::new("<path:**>")
+                .insert_handler(Method::GET, handler.clone())
+                .insert_handler(Method::HEAD, handler),
         )
     }
 
@@ -229,7 +298,16 @@ impl Route {
 
     #[cfg(feature = "session")]
     pub fn set_session_store<S: async_session::SessionStore>(&mut self, session: S) -> &mut Self {
-        self.hook_first(crate::session::middleware::SessionMiddleware::new(session));
+        self.set_session(crate::session::SessionMiddleware::new(session))
+    }
+
+    /// 设置带配置的会话中间件
+    #[cfg(feature = "session")]
+    pub fn set_session<S: async_session::SessionStore>(
+        &mut self,
+        middleware: crate::session::SessionMiddleware<S>,
+    ) -> &mut Self {
+        self.hook_first(middleware);
         self.session_set = true;
         self
     }
@@ -265,8 +343,7 @@ impl Route {
         let (mut req, path) = req.split_url();
 
         // 使用新的中间件收集逻辑
-        let (matched_route, middleware_layers) =
-            self.handler_match_collect_middlewares(&mut req, &path);
+        let (matched_route, middleware_layers) = self.match_request(&mut req, &path);
 
         // 收集根级中间件
         let mut root_middlewares = vec![];
@@ -330,6 +407,7 @@ impl Handler for Route {
 
 #[cfg(test)]
 mod tests {
+    use crate::prelude::HandlerAppend;
     use crate::{Next, Request, Response};
 
     use super::*;
@@ -354,6 +432,38 @@ mod tests {
         assert_eq!(route.children[0].middlewares.len(), 0); // 子路由没有中间件
     }
 
+    #[derive(Clone)]
+    struct CountMiddleware(Arc<std::sync::atomic::AtomicUsize>);
+    #[async_trait::async_trait]
+    impl MiddleWareHandler for CountMiddleware {
+        async fn handle(&self, req: Request, next: &Next) -> crate::error::SilentResult<Response> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            next.call(req).await
+        }
+    }
+
+    #[tokio::test]
+    async fn route_middleware_once_test() {
+        // 匹配路由的中间件只执行一次
+        let parent = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let leaf = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let route = Route::new("api")
+            .hook(CountMiddleware(parent.clone()))
+            .append(
+                Route::new("test")
+                    .hook(CountMiddleware(leaf.clone()))
+                    .get(|_req| async { Ok("ok") }),
+            );
+        let mut routes = Route::new_root();
+        routes.push(route);
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.uri_mut() = "/api/test".parse().unwrap();
+        assert_eq!(routes.call(req).await.unwrap().status, StatusCode::OK);
+        assert_eq!(parent.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(leaf.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn long_path_append_test() {
         let route = Route::new("api/v1")
@@ -1,11 +1,74 @@
 use crate::{Result, SilentError, StatusCode};
 use argon2::password_hash::SaltString;
 use argon2::password_hash::rand_core::OsRng;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+
+/// argon2id 哈希参数
+///
+/// 默认值与 argon2 crate 的默认参数一致。
+/// ```
+/// use silent::prelude::argon2::{Argon2Config, make_password_with};
+/// let config = Argon2Config::new().memory_cost(8 * 1024).time_cost(3);
+/// let _ = make_password_with("password".to_string(), &config).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Config {
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+}
+
+impl Default for Argon2Config {
+    fn default() -> Self {
+        Self {
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl Argon2Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 内存开销，单位 KiB
+    pub fn memory_cost(mut self, memory_cost: u32) -> Self {
+        self.memory_cost = memory_cost;
+        self
+    }
+    /// 迭代次数
+    pub fn time_cost(mut self, time_cost: u32) -> Self {
+        self.time_cost = time_cost;
+        self
+    }
+    /// 并行度
+    pub fn parallelism(mut self, parallelism: u32) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    fn hasher(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_cost, self.time_cost, self.parallelism, None)
+            .map_err(|e| {
+                SilentError::business_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("invalid argon2 params: {e}"),
+                )
+            })?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
 
 pub fn make_password(password: String) -> Result<String> {
+    make_password_with(password, &Argon2Config::default())
+}
+
+/// 使用指定参数生成 argon2id 哈希
+pub fn make_password_with(password: String, config: &Argon2Config) -> Result<String> {
     let salt = SaltString::generate(&mut OsRng);
-    Ok(Argon2::default()
+    Ok(config
+        .hasher()?
         .hash_password(password.as_bytes(), &salt)
         .map_err(|e| {
             SilentError::business_error(
@@ -28,6 +91,23 @@ pub fn verify_password(password_hash: String, password: String) -> Result<bool>
         .is_ok())
 }
 
+/// 哈希不是 argon2id，或参数与配置不一致时需要重新哈希
+pub fn needs_rehash(password_hash: &str, config: &Argon2Config) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+    Params::try_from(&parsed_hash).map_or(true, |params| {
+        params.m_cost() != config.memory_cost
+            || params.t_cost() != config.time_cost
+            || params.p_cost() != config.parallelism
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -40,4 +120,14 @@ mod test {
         info!("{}", password_hash);
         assert!(verify_password(password_hash, password,).is_ok())
     }
+
+    #[test]
+    fn needs_rehash_test() {
+        let config = Argon2Config::new().memory_cost(8 * 1024).time_cost(1);
+        let password_hash = make_password_with("hello_password".to_string(), &config).unwrap();
+        assert!(verify_password(password_hash.clone(), "hello_password".to_string()).unwrap());
+        assert!(!needs_rehash(&password_hash, &config));
+        assert!(needs_rehash(&password_hash, &config.time_cost(2)));
+        assert!(needs_rehash("invalid", &config));
+    }
 }
@@ -0,0 +1,126 @@
+use crate::security::keyring::{KEY_LEN, open, seal};
+use crate::{Result, SilentError, StatusCode};
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use rsa::sha2::Sha256;
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use std::fmt;
+use std::str::FromStr;
+
+/// RSA-OAEP 信封加密结果
+///
+/// 随机生成的数据密钥用 AES-256-GCM 加密数据，再用 RSA-OAEP(SHA-256) 公钥加密数据密钥。
+/// 字符串形式为 `base64(加密的数据密钥).base64(nonce+密文)`。
+/// ```
+/// use silent::prelude::Envelope;
+/// use rsa::{RsaPrivateKey, RsaPublicKey};
+/// let private_key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap();
+/// let public_key = RsaPublicKey::from(&private_key);
+/// let envelope = Envelope::seal(&public_key, b"hello").unwrap().to_string();
+/// let envelope: Envelope = envelope.parse().unwrap();
+/// assert_eq!(envelope.open(&private_key).unwrap(), b"hello");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    encrypted_key: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+fn envelope_error<T: fmt::Display>(status: StatusCode, msg: T) -> SilentError {
+    SilentError::business_error(status, msg.to_string())
+}
+
+impl Envelope {
+    /// 使用公钥加密
+    pub fn seal<T: AsRef<[u8]>>(public_key: &RsaPublicKey, plaintext: T) -> Result<Self> {
+        let mut data_key = Key::<Aes256Gcm>::default();
+        OsRng.fill_bytes(&mut data_key);
+        let ciphertext = seal(&data_key, plaintext.as_ref())?;
+        let encrypted_key = public_key
+            .encrypt(&mut OsRng, Oaep::new::<Sha256>(), &data_key)
+            .map_err(|e| {
+                envelope_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("encrypt data key failed: {e}"),
+                )
+            })?;
+        Ok(Self {
+            encrypted_key,
+            ciphertext,
+        })
+    }
+
+    /// 使用私钥解密
+    pub fn open(&self, private_key: &RsaPrivateKey) -> Result<Vec<u8>> {
+        let data_key = private_key
+            .decrypt(Oaep::new::<Sha256>(), &self.encrypted_key)
+            .map_err(|e| {
+                envelope_error(
+                    StatusCode::BAD_REQUEST,
+                    format!("decrypt data key failed: {e}"),
+                )
+            })?;
+        if data_key.len() != KEY_LEN {
+            return Err(envelope_error(
+                StatusCode::BAD_REQUEST,
+                "invalid data key length",
+            ));
+        }
+        open(Key::<Aes256Gcm>::from_slice(&data_key), &self.ciphertext)
+    }
+}
+
+impl fmt::Display for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}",
+            STANDARD.encode(&self.encrypted_key),
+            STANDARD.encode(&self.ciphertext)
+        )
+    }
+}
+
+impl FromStr for Envelope {
+    type Err = SilentError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let decode = |value: &str| {
+            STANDARD.decode(value).map_err(|e| {
+                envelope_error(StatusCode::BAD_REQUEST, format!("invalid envelope: {e}"))
+            })
+        };
+        let (encrypted_key, ciphertext) = s.split_once('.').ok_or_else(|| {
+            envelope_error(
+                StatusCode::BAD_REQUEST,
+                "invalid envelope: missing separator",
+            )
+        })?;
+        Ok(Self {
+            encrypted_key: decode(encrypted_key)?,
+            ciphertext: decode(ciphertext)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn envelope_test() {
+        let private_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let envelope = Envelope::seal(&public_key, "hello").unwrap();
+        let parsed: Envelope = envelope.to_string().parse().unwrap();
+        assert_eq!(parsed, envelope);
+        assert_eq!(parsed.open(&private_key).unwrap(), b"hello");
+
+        let other = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        assert!(envelope.open(&other).is_err());
+        assert!("invalid".parse::<Envelope>().is_err());
+    }
+}
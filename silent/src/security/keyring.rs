@@ -0,0 +1,234 @@
+use crate::{Result, SilentError, StatusCode};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::path::Path;
+
+/// AES-256-GCM 密钥长度
+pub const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+fn key_error<T: std::fmt::Display>(msg: T) -> SilentError {
+    SilentError::business_error(StatusCode::INTERNAL_SERVER_ERROR, msg.to_string())
+}
+
+fn decrypt_error<T: std::fmt::Display>(msg: T) -> SilentError {
+    SilentError::business_error(StatusCode::BAD_REQUEST, msg.to_string())
+}
+
+/// 带版本的对称密钥集合
+///
+/// 第一个密钥为当前密钥，用于加密；密文以 `密钥id:` 作为前缀，
+/// 解密时按前缀查找对应版本的密钥，轮换密钥后旧密文仍可解密。
+///
+/// 文件与环境变量使用 `id:base64密钥` 格式，多个密钥以换行或逗号分隔，`#` 开头的行为注释。
+/// ```
+/// use silent::prelude::KeyRing;
+/// let keyring = KeyRing::new("v2", [2u8; 32]).unwrap()
+///     .with_key("v1", [1u8; 32]).unwrap();
+/// let encrypted = keyring.encrypt(b"hello").unwrap();
+/// assert!(encrypted.starts_with("v2:"));
+/// assert_eq!(keyring.decrypt(&encrypted).unwrap(), b"hello");
+/// ```
+#[derive(Clone)]
+pub struct KeyRing {
+    keys: Vec<(String, Key<Aes256Gcm>)>,
+}
+
+impl std::fmt::Debug for KeyRing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyRing")
+            .field("ids", &self.ids().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl KeyRing {
+    /// 使用当前密钥创建
+    pub fn new<I: Into<String>, K: AsRef<[u8]>>(id: I, key: K) -> Result<Self> {
+        let mut keyring = Self { keys: vec![] };
+        keyring.add_key(id, key)?;
+        Ok(keyring)
+    }
+    /// 追加旧版本密钥
+    pub fn with_key<I: Into<String>, K: AsRef<[u8]>>(mut self, id: I, key: K) -> Result<Self> {
+        self.add_key(id, key)?;
+        Ok(self)
+    }
+    /// 轮换密钥，新密钥成为当前密钥
+    pub fn rotate<I: Into<String>, K: AsRef<[u8]>>(&mut self, id: I, key: K) -> Result<()> {
+        let entry = Self::entry(id, key)?;
+        self.keys.retain(|(id, _)| id != &entry.0);
+        self.keys.insert(0, entry);
+        Ok(())
+    }
+    /// 从文件加载
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            key_error(format!(
+                "read keyring file {} failed: {e}",
+                path.as_ref().display()
+            ))
+        })?;
+        Self::parse(&content)
+    }
+    /// 从环境变量加载
+    pub fn from_env(name: &str) -> Result<Self> {
+        let content =
+            std::env::var(name).map_err(|e| key_error(format!("read env {name} failed: {e}")))?;
+        Self::parse(&content)
+    }
+    /// 解析 `id:base64密钥` 格式的密钥列表
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut keyring = Self { keys: vec![] };
+        for entry in content
+            .split(['\n', ','])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+        {
+            let (id, key) = entry
+                .split_once(':')
+                .ok_or_else(|| key_error(format!("invalid keyring entry: {entry}")))?;
+            let key = STANDARD
+                .decode(key.trim())
+                .map_err(|e| key_error(format!("invalid key {id}: {e}")))?;
+            keyring.add_key(id.trim(), key)?;
+        }
+        if keyring.keys.is_empty() {
+            return Err(key_error("keyring is empty"));
+        }
+        Ok(keyring)
+    }
+    /// 当前密钥 id
+    pub fn current_id(&self) -> &str {
+        &self.keys[0].0
+    }
+    /// 所有密钥 id，当前密钥在前
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.keys.iter().map(|(id, _)| id.as_str())
+    }
+
+    fn entry<I: Into<String>, K: AsRef<[u8]>>(id: I, key: K) -> Result<(String, Key<Aes256Gcm>)> {
+        let id = id.into();
+        if id.is_empty() || id.contains(':') {
+            return Err(key_error(format!("invalid key id: {id:?}")));
+        }
+        let key = key.as_ref();
+        if key.len() != KEY_LEN {
+            return Err(key_error(format!(
+                "key {id} must be {KEY_LEN} bytes, got {}",
+                key.len()
+            )));
+        }
+        Ok((id, *Key::<Aes256Gcm>::from_slice(key)))
+    }
+
+    fn add_key<I: Into<String>, K: AsRef<[u8]>>(&mut self, id: I, key: K) -> Result<()> {
+        let entry = Self::entry(id, key)?;
+        if self.keys.iter().any(|(id, _)| id == &entry.0) {
+            return Err(key_error(format!("duplicate key id: {}", entry.0)));
+        }
+        self.keys.push(entry);
+        Ok(())
+    }
+
+    fn key(&self, id: &str) -> Option<&Key<Aes256Gcm>> {
+        self.keys
+            .iter()
+            .find(|(key_id, _)| key_id == id)
+            .map(|(_, key)| key)
+    }
+
+    /// 使用当前密钥加密，输出 `密钥id:base64(nonce+密文)`
+    pub fn encrypt<T: AsRef<[u8]>>(&self, plaintext: T) -> Result<String> {
+        let (id, key) = &self.keys[0];
+        let data = seal(key, plaintext.as_ref())?;
+        Ok(format!("{id}:{}", STANDARD.encode(data)))
+    }
+
+    /// 按密文前缀的密钥 id 解密
+    pub fn decrypt(&self, encrypted: &str) -> Result<Vec<u8>> {
+        let (id, data) = encrypted
+            .split_once(':')
+            .ok_or_else(|| decrypt_error("invalid ciphertext: missing key id"))?;
+        let key = self
+            .key(id)
+            .ok_or_else(|| decrypt_error(format!("unknown key id: {id}")))?;
+        let data = STANDARD
+            .decode(data)
+            .map_err(|e| decrypt_error(format!("invalid ciphertext: {e}")))?;
+        open(key, &data)
+    }
+}
+
+/// 使用随机 nonce 加密，输出 nonce+密文
+pub(crate) fn seal(key: &Key<Aes256Gcm>, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(key)
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| key_error(format!("encrypt failed: {e}")))?;
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// 解密 nonce+密文
+pub(crate) fn open(key: &Key<Aes256Gcm>, data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        return Err(decrypt_error("invalid ciphertext: too short"));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|e| decrypt_error(format!("decrypt failed: {e}")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keyring_rotation_test() {
+        let mut keyring = KeyRing::new("v1", [1u8; KEY_LEN]).unwrap();
+        let old = keyring.encrypt("hello").unwrap();
+        keyring.rotate("v2", [2u8; KEY_LEN]).unwrap();
+        let new = keyring.encrypt("hello").unwrap();
+        assert!(new.starts_with("v2:"));
+        assert_ne!(keyring.encrypt("hello").unwrap(), new);
+        assert_eq!(keyring.decrypt(&old).unwrap(), b"hello");
+        assert_eq!(keyring.decrypt(&new).unwrap(), b"hello");
+
+        let mut tampered = STANDARD.decode(&new[3..]).unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(
+            keyring
+                .decrypt(&format!("v2:{}", STANDARD.encode(tampered)))
+                .is_err()
+        );
+        assert!(keyring.decrypt(&new.replace("v2:", "v3:")).is_err());
+        assert!(KeyRing::new("v1", [1u8; 16]).is_err());
+    }
+
+    #[test]
+    fn keyring_parse_test() {
+        let content = format!(
+            "# current\nv2:{}\nv1:{}\n",
+            STANDARD.encode([2u8; KEY_LEN]),
+            STANDARD.encode([1u8; KEY_LEN])
+        );
+        let keyring = KeyRing::parse(&content).unwrap();
+        assert_eq!(keyring.ids().collect::<Vec<_>>(), ["v2", "v1"]);
+        let path = std::env::temp_dir().join(format!("keyring-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &content).unwrap();
+        assert_eq!(KeyRing::from_file(&path).unwrap().current_id(), "v2");
+        std::fs::remove_file(path).unwrap();
+
+        let content = content.replace('\n', ",");
+        let keyring = KeyRing::parse(&content).unwrap();
+        assert_eq!(keyring.current_id(), "v2");
+        assert!(KeyRing::parse("# empty").is_err());
+        assert!(KeyRing::from_env("SILENT_KEYRING_NOT_EXISTS").is_err());
+    }
+}
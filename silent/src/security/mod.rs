@@ -1,4 +1,10 @@
 pub mod aes;
 pub mod argon2;
+mod envelope;
+mod keyring;
+pub mod password;
 pub mod pbkdf2;
 pub mod rsa;
+
+pub use envelope::Envelope;
+pub use keyring::KeyRing;
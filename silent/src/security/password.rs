@@ -0,0 +1,94 @@
+use crate::security::argon2::{self, Argon2Config};
+use crate::security::pbkdf2;
+use crate::{Result, SilentError, StatusCode};
+use ::argon2::PasswordHash;
+
+/// 密码校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordVerified {
+    /// 密码错误
+    Invalid,
+    /// 密码正确，哈希无需更新
+    Valid,
+    /// 密码正确，返回按当前 argon2 参数重新生成的哈希，调用方应保存替换旧哈希
+    Rehashed(String),
+}
+
+impl PasswordVerified {
+    /// 密码是否正确
+    pub fn is_valid(&self) -> bool {
+        !matches!(self, PasswordVerified::Invalid)
+    }
+}
+
+/// 校验密码，并在需要时迁移到当前 argon2 参数
+///
+/// 支持 argon2 与 pbkdf2 哈希，pbkdf2 哈希或参数过时的 argon2 哈希在校验通过后会重新哈希，
+/// 适合在登录时逐步迁移旧密码。
+/// ```
+/// use silent::prelude::{argon2::Argon2Config, password, pbkdf2};
+/// let config = Argon2Config::default();
+/// let old_hash = pbkdf2::make_password("password".to_string()).unwrap();
+/// match password::verify_and_upgrade(&old_hash, "password", &config).unwrap() {
+///     password::PasswordVerified::Rehashed(new_hash) => assert!(new_hash.starts_with("$argon2id$")),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn verify_and_upgrade(
+    password_hash: &str,
+    password: &str,
+    config: &Argon2Config,
+) -> Result<PasswordVerified> {
+    let parsed_hash = PasswordHash::new(password_hash).map_err(|e| {
+        SilentError::business_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("read password hash failed: {e}"),
+        )
+    })?;
+    let algorithm = parsed_hash.algorithm.as_str();
+    let valid = if algorithm.starts_with("argon2") {
+        argon2::verify_password(password_hash.to_string(), password.to_string())?
+    } else if algorithm.starts_with("pbkdf2") {
+        pbkdf2::verify_password(password_hash.to_string(), password.to_string())?
+    } else {
+        return Err(SilentError::business_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("unsupported password hash algorithm: {algorithm}"),
+        ));
+    };
+    if !valid {
+        return Ok(PasswordVerified::Invalid);
+    }
+    if argon2::needs_rehash(password_hash, config) {
+        return Ok(PasswordVerified::Rehashed(argon2::make_password_with(
+            password.to_string(),
+            config,
+        )?));
+    }
+    Ok(PasswordVerified::Valid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn upgrade_test() {
+        let config = Argon2Config::new().memory_cost(8 * 1024).time_cost(1);
+        let old_hash = pbkdf2::make_password("hello_password".to_string()).unwrap();
+        assert_eq!(
+            verify_and_upgrade(&old_hash, "wrong", &config).unwrap(),
+            PasswordVerified::Invalid
+        );
+        let PasswordVerified::Rehashed(new_hash) =
+            verify_and_upgrade(&old_hash, "hello_password", &config).unwrap()
+        else {
+            panic!("pbkdf2 hash should be rehashed");
+        };
+        assert_eq!(
+            verify_and_upgrade(&new_hash, "hello_password", &config).unwrap(),
+            PasswordVerified::Valid
+        );
+        assert!(verify_and_upgrade("invalid", "hello_password", &config).is_err());
+    }
+}
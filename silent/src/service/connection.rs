@@ -0,0 +1,332 @@
+use crate::core::connection::Connection;
+use crate::core::socket_addr::SocketAddr;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// TLS 连接信息
+#[derive(Debug, Clone, Default)]
+pub struct TlsInfo {
+    /// 客户端通过 SNI 请求的域名
+    pub server_name: Option<String>,
+    /// ALPN 协商的协议
+    pub alpn_protocol: Option<Vec<u8>>,
+    /// TLS 协议版本
+    pub protocol_version: Option<String>,
+    /// 协商的加密套件
+    pub cipher_suite: Option<String>,
+    /// 客户端证书链（DER 编码），仅双向认证时存在
+    pub peer_certificates: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "tls")]
+impl TlsInfo {
+    fn from_connection(conn: &tokio_rustls::rustls::ServerConnection) -> Self {
+        Self {
+            server_name: conn.server_name().map(str::to_string),
+            alpn_protocol: conn.alpn_protocol().map(<[u8]>::to_vec),
+            protocol_version: conn.protocol_version().map(|v| format!("{v:?}")),
+            cipher_suite: conn
+                .negotiated_cipher_suite()
+                .map(|suite| format!("{:?}", suite.suite())),
+            peer_certificates: conn
+                .peer_certificates()
+                .map(|certs| certs.iter().map(|cert| cert.to_vec()).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// 从连接中提取 TLS 信息，非 TLS 连接返回 `None`
+#[allow(unused_variables)]
+pub(crate) fn tls_info(stream: &(dyn Connection + Send + Sync)) -> Option<TlsInfo> {
+    #[cfg(feature = "tls")]
+    {
+        let stream: &dyn std::any::Any = stream;
+        if let Some(stream) = stream
+            .downcast_ref::<tokio_rustls::server::TlsStream<Box<dyn Connection + Send + Sync>>>()
+        {
+            return Some(TlsInfo::from_connection(stream.get_ref().1));
+        }
+    }
+    None
+}
+
+/// 连接信息
+///
+/// 服务器为每个连接创建，并放入该连接上所有请求的拓展中。
+/// ```
+/// use silent::prelude::*;
+/// let route = Route::new("").get(|req: Request| async move {
+///     let info = req.extensions().get::<ConnectionInfo>().cloned();
+///     Ok(info.map(|info| info.requests_served()).unwrap_or_default())
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    inner: Arc<ConnectionState>,
+}
+
+#[derive(Debug)]
+struct ConnectionState {
+    id: u64,
+    accepted_at: SystemTime,
+    started: Instant,
+    peer_addr: SocketAddr,
+    tls: Option<TlsInfo>,
+    requests_served: AtomicU64,
+    in_flight: AtomicUsize,
+    /// 最近一次读写距 `started` 的毫秒数
+    last_active: AtomicU64,
+}
+
+impl ConnectionInfo {
+    pub(crate) fn new(id: u64, peer_addr: SocketAddr, tls: Option<TlsInfo>) -> Self {
+        Self {
+            inner: Arc::new(ConnectionState {
+                id,
+                accepted_at: SystemTime::now(),
+                started: Instant::now(),
+                peer_addr,
+                tls,
+                requests_served: AtomicU64::new(0),
+                in_flight: AtomicUsize::new(0),
+                last_active: AtomicU64::new(0),
+            }),
+        }
+    }
+    /// 连接 id，同一服务器内唯一
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+    /// 接受连接的时间
+    pub fn accepted_at(&self) -> SystemTime {
+        self.inner.accepted_at
+    }
+    /// 对端地址
+    pub fn peer_addr(&self) -> &SocketAddr {
+        &self.inner.peer_addr
+    }
+    /// 已处理完成的请求数量
+    pub fn requests_served(&self) -> u64 {
+        self.inner.requests_served.load(Ordering::Relaxed)
+    }
+    /// TLS 信息，非 TLS 连接返回 `None`
+    pub fn tls(&self) -> Option<&TlsInfo> {
+        self.inner.tls.as_ref()
+    }
+
+    /// 标记请求开始处理，返回的守卫释放时视为请求结束
+    pub(crate) fn request_started(&self) -> RequestGuard {
+        self.inner.in_flight.fetch_add(1, Ordering::AcqRel);
+        self.touch();
+        RequestGuard(self.clone())
+    }
+
+    fn touch(&self) {
+        let elapsed = self.inner.started.elapsed().as_millis() as u64;
+        self.inner.last_active.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    /// 空闲时长，有处理中的请求时返回 `None`
+    fn idle_for(&self) -> Option<Duration> {
+        if self.inner.in_flight.load(Ordering::Acquire) > 0 {
+            return None;
+        }
+        let last_active = Duration::from_millis(self.inner.last_active.load(Ordering::Relaxed));
+        Some(self.inner.started.elapsed().saturating_sub(last_active))
+    }
+
+    /// 连接空闲超过 `timeout` 时完成，未设置超时时永不完成
+    pub(crate) async fn idle_expired(&self, timeout: Option<Duration>) {
+        let Some(timeout) = timeout else {
+            return std::future::pending().await;
+        };
+        loop {
+            let wait = match self.idle_for() {
+                Some(idle) if idle >= timeout => return,
+                Some(idle) => timeout - idle,
+                None => timeout,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 处理中请求的守卫
+pub(crate) struct RequestGuard(ConnectionInfo);
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        let state = &self.0.inner;
+        state.requests_served.fetch_add(1, Ordering::Relaxed);
+        state.in_flight.fetch_sub(1, Ordering::AcqRel);
+        self.0.touch();
+    }
+}
+
+/// 记录读写活动的连接包装，用于空闲连接回收
+pub(crate) struct IdleStream<S> {
+    stream: S,
+    info: ConnectionInfo,
+}
+
+impl<S> IdleStream<S> {
+    pub(crate) fn new(stream: S, info: ConnectionInfo) -> Self {
+        Self { stream, info }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut self.stream).poll_read(cx, buf);
+        if buf.filled().len() > filled {
+            self.info.touch();
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_write(cx, buf);
+        if matches!(poll, Poll::Ready(Ok(n)) if n > 0) {
+            self.info.touch();
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_write_vectored(cx, bufs);
+        if matches!(poll, Poll::Ready(Ok(n)) if n > 0) {
+            self.info.touch();
+        }
+        poll
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
+
+/// 连接数量限制
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionLimits {
+    pub(crate) max_connections: Option<usize>,
+    pub(crate) max_connections_per_ip: Option<usize>,
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
+/// 每个 IP 的连接计数
+type IpCounts = Arc<Mutex<HashMap<IpAddr, usize>>>;
+
+/// 连接数量限制的运行时状态，克隆后共享同一份限额
+#[derive(Clone)]
+pub(crate) struct ConnectionTracker {
+    semaphore: Option<Arc<Semaphore>>,
+    per_ip: Option<(usize, IpCounts)>,
+    pub(crate) idle_timeout: Option<Duration>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// 连接占用的限额，连接结束时释放
+pub(crate) struct ConnectionPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+    ip: Option<(IpAddr, IpCounts)>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Some((ip, counts)) = self.ip.take() {
+            let mut counts = counts.lock().unwrap();
+            if let Some(count) = counts.get_mut(&ip) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&ip);
+                }
+            }
+        }
+    }
+}
+
+impl ConnectionTracker {
+    pub(crate) fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            semaphore: limits
+                .max_connections
+                .map(|max| Arc::new(Semaphore::new(max))),
+            per_ip: limits
+                .max_connections_per_ip
+                .map(|max| (max, Arc::new(Mutex::new(HashMap::new())))),
+            idle_timeout: limits.idle_timeout,
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 等待全局连接限额，达到上限时暂停接受新连接
+    pub(crate) fn acquire(&self) -> impl Future<Output = Option<OwnedSemaphorePermit>> + use<> {
+        let semaphore = self.semaphore.clone();
+        async move {
+            match semaphore {
+                Some(semaphore) => semaphore.acquire_owned().await.ok(),
+                None => None,
+            }
+        }
+    }
+
+    /// 登记新连接，超出单 IP 连接上限时返回 `None`
+    pub(crate) fn admit(
+        &self,
+        permit: Option<OwnedSemaphorePermit>,
+        peer_addr: &SocketAddr,
+    ) -> Option<(u64, ConnectionPermit)> {
+        let mut ip = None;
+        if let Some((max, counts)) = &self.per_ip
+            && let Some(addr) = peer_addr.tcp_addr()
+        {
+            let mut guard = counts.lock().unwrap();
+            let count = guard.entry(addr.ip()).or_default();
+            if *count >= *max {
+                return None;
+            }
+            *count += 1;
+            ip = Some((addr.ip(), counts.clone()));
+        }
+        Some((
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            ConnectionPermit {
+                _permit: permit,
+                ip,
+            },
+        ))
+    }
+}
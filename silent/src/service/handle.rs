@@ -0,0 +1,91 @@
+#[cfg(not(target_os = "windows"))]
+use crate::core::listen_fds::ListenFds;
+use crate::core::socket_addr::SocketAddr;
+use tokio::sync::watch;
+use tokio::task::{JoinError, JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+/// 运行中服务器的句柄
+///
+/// 由 [`Server::start`](crate::prelude::Server::start) 返回，可以获取实际绑定的地址、等待服务就绪，
+/// 以及在不依赖系统信号的情况下停止服务器。
+/// ```no_run
+/// use silent::prelude::*;
+/// # async fn run() -> std::io::Result<()> {
+/// let route = Route::new("").get(|_req| async { Ok("hello") });
+/// let handle = Server::new()
+///     .bind("127.0.0.1:0".parse().unwrap())
+///     .start(route)?;
+/// handle.ready().await;
+/// println!("listening on {:?}", handle.local_addrs());
+/// handle.shutdown();
+/// handle.join().await.unwrap();
+/// # Ok(())
+/// # }
+/// ```
+pub struct ServerHandle {
+    pub(crate) local_addrs: Vec<SocketAddr>,
+    #[cfg(not(target_os = "windows"))]
+    pub(crate) listen_fds: ListenFds,
+    #[cfg(feature = "http3")]
+    pub(crate) http3_addrs: Vec<std::net::SocketAddr>,
+    pub(crate) ready: watch::Receiver<bool>,
+    pub(crate) shutdown: CancellationToken,
+    pub(crate) abort: CancellationToken,
+    pub(crate) join: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// 实际绑定的地址，绑定 `127.0.0.1:0` 时可由此获取端口
+    #[inline]
+    pub fn local_addrs(&self) -> &[SocketAddr] {
+        &self.local_addrs
+    }
+    /// HTTP/3 监听器实际绑定的 UDP 地址
+    #[cfg(feature = "http3")]
+    pub fn http3_addrs(&self) -> &[std::net::SocketAddr] {
+        &self.http3_addrs
+    }
+    /// 等待服务器开始接收连接
+    pub async fn ready(&self) {
+        let mut ready = self.ready.clone();
+        let _ = ready.wait_for(|ready| *ready).await;
+    }
+    /// 优雅关闭：停止接收新连接并等待处理中的请求完成
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+    /// 立即关闭所有连接，不等待处理中的请求
+    pub fn abort(&self) {
+        self.abort.cancel();
+    }
+    /// 将监听 socket 移交给新进程，然后优雅关闭当前服务器
+    ///
+    /// 新进程通过 `LISTEN_FDS` 继承监听 socket（见 [`Server::bind_inherited`](crate::prelude::Server::bind_inherited)），
+    /// 移交期间到达的连接在内核队列中等待新进程接受，不会被拒绝。HTTP/3 监听器不会被移交。
+    /// ```no_run
+    /// use silent::prelude::*;
+    /// use tokio::signal::unix::{SignalKind, signal};
+    /// # async fn run() -> std::io::Result<()> {
+    /// let route = Route::new("").get(|_req| async { Ok("hello") });
+    /// let handle = Server::new()
+    ///     .bind_inherited_or("127.0.0.1:8000".parse().unwrap())
+    ///     .start(route)?;
+    /// // 收到 SIGHUP 时启动新版本的程序并移交监听器
+    /// signal(SignalKind::hangup())?.recv().await;
+    /// handle.handoff(std::process::Command::new(std::env::current_exe()?))?;
+    /// handle.join().await.unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_os = "windows"))]
+    pub fn handoff(&self, command: std::process::Command) -> std::io::Result<std::process::Child> {
+        let child = self.listen_fds.spawn(command)?;
+        self.shutdown();
+        Ok(child)
+    }
+    /// 等待服务器退出
+    pub async fn join(self) -> Result<(), JoinError> {
+        self.join.await
+    }
+}
@@ -0,0 +1,498 @@
+use crate::core::adapt::{RequestAdapt, ResponseAdapt};
+use crate::core::req_body::ReqBody;
+use crate::core::res_body::ResBody;
+use crate::core::socket_addr::SocketAddr;
+use crate::core::tls::TlsConfig;
+use crate::route::Route;
+use crate::service::connection::{ConnectionInfo, ConnectionPermit, ConnectionTracker, TlsInfo};
+use crate::service::hyper_service::HyperServiceHandler;
+use crate::service::serve::Watcher;
+use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result};
+use async_trait::async_trait;
+use bytes::{Buf, Bytes};
+use h3::server::{RequestResolver, RequestStream};
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::BodyExt;
+use hyper::header::{self, HeaderName, HeaderValue};
+use hyper::{Request as HyperRequest, Response as HyperResponse, Version};
+use quinn::crypto::rustls::{HandshakeData, QuicServerConfig};
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::task::JoinSet;
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// HTTP/3 监听器
+///
+/// 在 UDP 地址上接受 QUIC 连接，ALPN 固定为 `h3`，证书配置与 TCP 监听器共用 [`TlsConfig`]。
+/// ```no_run
+/// use silent::prelude::*;
+/// # async fn run() -> std::io::Result<()> {
+/// let tls = TlsConfig::new().cert("cert.pem", "key.pem");
+/// let acceptor = ReloadableTlsAcceptor::new(tls.clone())?;
+/// let listener: Listener = tokio::net::TcpListener::bind("0.0.0.0:443").await?.into();
+/// let route = Route::new("").get(|_req| async { Ok("hello") });
+/// Server::new()
+///     .listen(listener.tls_reloadable(acceptor))
+///     .bind_h3("0.0.0.0:443".parse().unwrap(), tls)
+///     .serve(route)
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Http3Listener {
+    endpoint: quinn::Endpoint,
+    tls: TlsConfig,
+}
+
+impl Http3Listener {
+    /// 绑定 UDP 地址，需要在 tokio 运行时中调用
+    pub fn bind(addr: std::net::SocketAddr, tls: TlsConfig) -> io::Result<Self> {
+        let endpoint = quinn::Endpoint::server(server_config(&tls)?, addr)
+            .map_err(|e| io::Error::new(e.kind(), format!("failed to bind {addr}: {e}")))?;
+        Ok(Self { endpoint, tls })
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.endpoint.local_addr()
+    }
+
+    /// 重新加载证书，只影响之后建立的连接
+    pub fn reload(&self) -> io::Result<()> {
+        self.endpoint
+            .set_server_config(Some(server_config(&self.tls)?));
+        Ok(())
+    }
+}
+
+fn server_config(tls: &TlsConfig) -> io::Result<quinn::ServerConfig> {
+    let config = tls.clone().alpn_protocols(vec![b"h3".to_vec()]).build()?;
+    let crypto = QuicServerConfig::try_from(config)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    Ok(quinn::ServerConfig::with_crypto(Arc::new(crypto)))
+}
+
+/// 待启动的 HTTP/3 监听器，绑定延迟到服务器启动时进行
+pub(crate) enum PendingHttp3 {
+    Bind(std::net::SocketAddr, TlsConfig),
+    Listener(Http3Listener),
+}
+
+impl PendingHttp3 {
+    pub(crate) fn bind(self) -> io::Result<Http3Listener> {
+        match self {
+            PendingHttp3::Bind(addr, tls) => Http3Listener::bind(addr, tls),
+            PendingHttp3::Listener(listener) => Ok(listener),
+        }
+    }
+}
+
+/// HTTP/3 请求体
+pub struct Http3Body {
+    stream: RequestStream<h3_quinn::RecvStream, Bytes>,
+    data_done: bool,
+    finished: bool,
+}
+
+impl fmt::Debug for Http3Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Http3Body")
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+impl Body for Http3Body {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        if !self.data_done {
+            match ready!(self.stream.poll_recv_data(cx)) {
+                Ok(Some(mut data)) => {
+                    return Poll::Ready(Some(Ok(Frame::data(
+                        data.copy_to_bytes(data.remaining()),
+                    ))));
+                }
+                Ok(None) => self.data_done = true,
+                Err(e) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(io::Error::other(e))));
+                }
+            }
+        }
+        let trailers = ready!(self.stream.poll_recv_trailers(cx));
+        self.finished = true;
+        match trailers {
+            Ok(Some(trailers)) => Poll::Ready(Some(Ok(Frame::trailers(trailers)))),
+            Ok(None) => Poll::Ready(None),
+            Err(e) => Poll::Ready(Some(Err(io::Error::other(e)))),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.finished
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+/// 在 TCP 连接的响应中通过 `Alt-Svc` 告知客户端可用的 HTTP/3 端口
+#[derive(Clone)]
+pub(crate) struct AltSvc(HeaderValue);
+
+impl AltSvc {
+    pub(crate) fn new(ports: impl IntoIterator<Item = u16>) -> Option<Self> {
+        let value = ports
+            .into_iter()
+            .map(|port| format!("h3=\":{port}\"; ma=86400"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        HeaderValue::from_str(&value)
+            .ok()
+            .filter(|_| !value.is_empty())
+            .map(Self)
+    }
+}
+
+#[async_trait]
+impl MiddleWareHandler for AltSvc {
+    async fn handle(&self, req: Request, next: &Next) -> Result<Response> {
+        let is_http3 = req.version() == Version::HTTP_3;
+        let mut res = next.call(req).await?;
+        if !is_http3 && !res.headers().contains_key(header::ALT_SVC) {
+            res.headers_mut().insert(header::ALT_SVC, self.0.clone());
+        }
+        Ok(res)
+    }
+}
+
+fn tls_info(conn: &quinn::Connection) -> TlsInfo {
+    let handshake = conn
+        .handshake_data()
+        .and_then(|data| data.downcast::<HandshakeData>().ok());
+    TlsInfo {
+        server_name: handshake.as_ref().and_then(|data| data.server_name.clone()),
+        alpn_protocol: handshake.and_then(|data| data.protocol),
+        protocol_version: Some("TLSv1_3".to_string()),
+        cipher_suite: None,
+        peer_certificates: conn
+            .peer_identity()
+            .and_then(|identity| {
+                identity
+                    .downcast::<Vec<tokio_rustls::rustls::pki_types::CertificateDer<'static>>>()
+                    .ok()
+            })
+            .map(|certs| certs.iter().map(|cert| cert.to_vec()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// 等待连接限额后接受 QUIC 连接
+async fn accept_incoming(
+    endpoint: &quinn::Endpoint,
+    permit: impl Future<Output = Option<OwnedSemaphorePermit>>,
+) -> Option<(Option<OwnedSemaphorePermit>, quinn::Incoming)> {
+    let permit = permit.await;
+    endpoint.accept().await.map(|incoming| (permit, incoming))
+}
+
+/// 接受 QUIC 连接，服务器开始优雅关闭后停止接受并等待已有连接结束
+///
+/// 与 TCP 监听器共用连接数量限制。
+pub(crate) async fn serve_http3(
+    listener: Http3Listener,
+    routes: Route,
+    tracker: ConnectionTracker,
+    mut watcher: Watcher,
+) {
+    let mut connections = JoinSet::new();
+    let mut accept = Box::pin(accept_incoming(&listener.endpoint, tracker.acquire()));
+    loop {
+        tokio::select! {
+            accepted = &mut accept => {
+                accept.set(accept_incoming(&listener.endpoint, tracker.acquire()));
+                let Some((permit, incoming)) = accepted else {
+                    break;
+                };
+                let peer_addr = SocketAddr::Quic(incoming.remote_address());
+                let Some((id, permit)) = tracker.admit(permit, &peer_addr) else {
+                    tracing::warn!("too many connections from {}, refusing", peer_addr);
+                    incoming.refuse();
+                    continue;
+                };
+                let routes = routes.clone();
+                let watcher = watcher.clone();
+                connections.spawn(async move {
+                    if let Err(err) = serve_connection(incoming, peer_addr, routes, id, permit, watcher).await {
+                        tracing::error!("Failed to serve http3 connection: {:?}", err);
+                    }
+                });
+            }
+            _ = watcher.shutdown_requested() => break,
+            // 回收已结束的连接任务
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+    drop(accept);
+    listener.endpoint.set_server_config(None);
+    while connections.join_next().await.is_some() {}
+    listener.endpoint.wait_idle().await;
+}
+
+async fn serve_connection(
+    incoming: quinn::Incoming,
+    peer_addr: SocketAddr,
+    routes: Route,
+    id: u64,
+    _permit: ConnectionPermit,
+    mut watcher: Watcher,
+) -> std::result::Result<(), BoxError> {
+    let conn = incoming.await?;
+    tracing::info!("Accepting http3 from: {}", peer_addr);
+    let info = ConnectionInfo::new(id, peer_addr.clone(), Some(tls_info(&conn)));
+    let handler = HyperServiceHandler::new(peer_addr, routes).with_connection(info);
+    let mut h3_conn = h3::server::Connection::new(h3_quinn::Connection::new(conn)).await?;
+    let mut requests = JoinSet::new();
+    let result = loop {
+        tokio::select! {
+            resolver = h3_conn.accept() => match resolver {
+                Ok(Some(resolver)) => {
+                    let handler = handler.clone();
+                    requests.spawn(async move {
+                        if let Err(err) = handle_request(resolver, handler).await {
+                            tracing::error!("Failed to serve http3 request: {:?}", err);
+                        }
+                    });
+                }
+                Ok(None) => break Ok(()),
+                Err(e) if e.is_h3_no_error() => break Ok(()),
+                Err(e) => break Err(e.into()),
+            },
+            // 发送 GOAWAY，处理中的请求完成后关闭连接
+            _ = watcher.shutdown_requested() => {
+                break h3_conn.shutdown(0).await.map_err(Into::into);
+            }
+            Some(_) = requests.join_next(), if !requests.is_empty() => {}
+        }
+    };
+    // 连接出错时也等待已接受的请求结束
+    while requests.join_next().await.is_some() {}
+    result
+}
+
+async fn handle_request(
+    resolver: RequestResolver<h3_quinn::Connection, Bytes>,
+    handler: HyperServiceHandler<Route>,
+) -> std::result::Result<(), BoxError> {
+    let (req, stream) = resolver.resolve_request().await?;
+    let (mut send, recv) = stream.split();
+    let (parts, _) = req.into_parts();
+    let body = ReqBody::Http3(Http3Body {
+        stream: recv,
+        data_done: false,
+        finished: false,
+    });
+    let req = HyperRequest::from_parts(parts, body).tran_to_request();
+    let res: HyperResponse<ResBody> = ResponseAdapt::tran_from_response(handler.handle(req).await);
+    let (mut parts, mut body) = res.into_parts();
+    // HTTP/3 不允许连接相关的头部
+    for name in [
+        header::CONNECTION,
+        header::TRANSFER_ENCODING,
+        header::UPGRADE,
+        HeaderName::from_static("keep-alive"),
+        HeaderName::from_static("proxy-connection"),
+    ] {
+        parts.headers.remove(name);
+    }
+    parts.version = Version::HTTP_3;
+    send.send_response(HyperResponse::from_parts(parts, ()))
+        .await?;
+    while let Some(frame) = body.frame().await {
+        match frame?.into_data() {
+            Ok(data) => send.send_data(data).await?,
+            Err(frame) => {
+                if let Ok(trailers) = frame.into_trailers() {
+                    send.send_trailers(trailers).await?;
+                }
+            }
+        }
+    }
+    send.finish().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tls::crypto_provider;
+    use crate::prelude::*;
+    use bytes::{Buf, Bytes};
+    use http_body_util::BodyExt;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::rustls::pki_types::CertificateDer;
+    use tokio_rustls::rustls::pki_types::pem::PemObject;
+    use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+
+    fn client_endpoint() -> quinn::Endpoint {
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(cert_path("ca.pem")).unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let mut config = ClientConfig::builder_with_provider(crypto_provider().unwrap())
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"h3".to_vec()];
+        let config = quinn::crypto::rustls::QuicClientConfig::try_from(config).unwrap();
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(config)));
+        endpoint
+    }
+
+    fn cert_path(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/certs")
+            .join(name)
+    }
+
+    async fn h3_request(
+        addr: std::net::SocketAddr,
+        method: Method,
+        path: &str,
+        body: &'static [u8],
+    ) -> (hyper::Response<()>, String) {
+        let conn = client_endpoint()
+            .connect(addr, "localhost")
+            .unwrap()
+            .await
+            .unwrap();
+        let (mut driver, mut sender) = h3::client::new(h3_quinn::Connection::new(conn))
+            .await
+            .unwrap();
+        tokio::spawn(async move { std::future::poll_fn(|cx| driver.poll_close(cx)).await });
+
+        let req = hyper::Request::builder()
+            .method(method)
+            .uri(format!("https://localhost{path}"))
+            .body(())
+            .unwrap();
+        let mut stream = sender.send_request(req).await.unwrap();
+        if !body.is_empty() {
+            stream.send_data(Bytes::from_static(body)).await.unwrap();
+        }
+        stream.finish().await.unwrap();
+        let res = stream.recv_response().await.unwrap();
+        let mut text = vec![];
+        while let Some(mut chunk) = stream.recv_data().await.unwrap() {
+            text.extend_from_slice(&chunk.copy_to_bytes(chunk.remaining()));
+        }
+        (res, String::from_utf8(text).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_http3() {
+        let route =
+            Route::new("")
+                .get(|_req| async { Ok("hello") })
+                .append(Route::new("echo").post(|mut req: Request| async move {
+                    let body = req.take_body().collect().await.unwrap().to_bytes();
+                    let info = req.extensions().get::<ConnectionInfo>().unwrap();
+                    assert!(matches!(info.peer_addr(), crate::SocketAddr::Quic(_)));
+                    let alpn = info.tls().unwrap().alpn_protocol.clone().unwrap();
+                    Ok(format!(
+                        "{}|{}",
+                        String::from_utf8_lossy(&body),
+                        String::from_utf8_lossy(&alpn)
+                    ))
+                }));
+        let tls = TlsConfig::new().cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"));
+        let localhost = "127.0.0.1:0".parse().unwrap();
+        let handle = Server::new()
+            .bind(localhost)
+            .bind_h3(localhost, tls)
+            .start(route)
+            .unwrap();
+        handle.ready().await;
+        let tcp_addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let h3_addr = handle.http3_addrs()[0];
+
+        // TCP 响应通过 Alt-Svc 告知 HTTP/3 端口
+        let mut stream = tokio::net::TcpStream::connect(tcp_addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let alt_svc = format!("alt-svc: h3=\":{}\"; ma=86400", h3_addr.port());
+        assert!(response.to_lowercase().contains(&alt_svc));
+
+        let (res, body) = h3_request(h3_addr, Method::GET, "/", b"").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::ALT_SVC).is_none());
+        assert_eq!(body, "hello");
+        let (res, body) = h3_request(h3_addr, Method::POST, "/echo", b"ping").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body, "ping|h3");
+
+        handle.shutdown();
+        tokio::time::timeout(Duration::from_secs(5), handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http3_connection_limits() {
+        let route = Route::new("").get(|_req| async { Ok("hello") });
+        let tls = TlsConfig::new().cert(cert_path("localhost.pem"), cert_path("localhost-key.pem"));
+        let handle = Server::new()
+            .with_max_connections_per_ip(1)
+            .bind_h3("127.0.0.1:0".parse().unwrap(), tls)
+            .start(route)
+            .unwrap();
+        handle.ready().await;
+        let h3_addr = handle.http3_addrs()[0];
+
+        // 单 IP 连接上限同样作用于 QUIC 连接
+        let endpoint = client_endpoint();
+        let first = endpoint
+            .connect(h3_addr, "localhost")
+            .unwrap()
+            .await
+            .unwrap();
+        let second = endpoint.connect(h3_addr, "localhost").unwrap().await;
+        assert!(second.is_err());
+        drop(first);
+        endpoint.close(0u32.into(), b"");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let (res, body) = h3_request(h3_addr, Method::GET, "/", b"").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body, "hello");
+
+        handle.shutdown();
+        tokio::time::timeout(Duration::from_secs(5), handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+/// 连接使用的 HTTP 协议
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpProtocol {
+    /// 自动识别 HTTP/1 与 HTTP/2（h2c prior knowledge）
+    #[default]
+    Auto,
+    /// 仅 HTTP/1
+    Http1Only,
+    /// 仅 HTTP/2，明文监听器上即 h2c prior knowledge
+    Http2Only,
+}
+
+/// HTTP 连接参数
+///
+/// 未设置的参数使用 hyper 的默认值。
+/// ```
+/// use silent::prelude::*;
+/// use std::time::Duration;
+/// let config = HttpConfig::new()
+///     .http1_keep_alive(true)
+///     .http1_header_read_timeout(Duration::from_secs(10))
+///     .http1_max_headers(64)
+///     .http2_max_concurrent_streams(256)
+///     .http2_keep_alive_interval(Duration::from_secs(20));
+/// let _server = Server::new()
+///     .with_http_config(config)
+///     .bind_with("127.0.0.1:8080".parse().unwrap(), HttpConfig::new().http2_only());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    pub(crate) protocol: HttpProtocol,
+    pub(crate) http1_keep_alive: Option<bool>,
+    pub(crate) http1_header_read_timeout: Option<Duration>,
+    pub(crate) http1_max_headers: Option<usize>,
+    pub(crate) http1_max_buf_size: Option<usize>,
+    pub(crate) http2_max_concurrent_streams: Option<u32>,
+    pub(crate) http2_initial_stream_window_size: Option<u32>,
+    pub(crate) http2_initial_connection_window_size: Option<u32>,
+    pub(crate) http2_adaptive_window: Option<bool>,
+    pub(crate) http2_keep_alive_interval: Option<Duration>,
+    pub(crate) http2_keep_alive_timeout: Option<Duration>,
+    pub(crate) http2_max_header_list_size: Option<u32>,
+}
+
+impl HttpConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 设置协议
+    pub fn protocol(mut self, protocol: HttpProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+    /// 仅接受 HTTP/1 连接
+    pub fn http1_only(self) -> Self {
+        self.protocol(HttpProtocol::Http1Only)
+    }
+    /// 仅接受 HTTP/2 连接
+    pub fn http2_only(self) -> Self {
+        self.protocol(HttpProtocol::Http2Only)
+    }
+    /// HTTP/1 是否启用 keep-alive，默认启用
+    pub fn http1_keep_alive(mut self, enabled: bool) -> Self {
+        self.http1_keep_alive = Some(enabled);
+        self
+    }
+    /// HTTP/1 读取完整请求头的超时时间，超时后关闭连接
+    pub fn http1_header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.http1_header_read_timeout = Some(timeout);
+        self
+    }
+    /// HTTP/1 最大请求头数量，超出时返回 431
+    pub fn http1_max_headers(mut self, max: usize) -> Self {
+        self.http1_max_headers = Some(max);
+        self
+    }
+    /// HTTP/1 最大读缓冲区大小
+    pub fn http1_max_buf_size(mut self, max: usize) -> Self {
+        self.http1_max_buf_size = Some(max);
+        self
+    }
+    /// HTTP/2 单连接最大并发流数量
+    pub fn http2_max_concurrent_streams(mut self, max: u32) -> Self {
+        self.http2_max_concurrent_streams = Some(max);
+        self
+    }
+    /// HTTP/2 流初始窗口大小
+    pub fn http2_initial_stream_window_size(mut self, size: u32) -> Self {
+        self.http2_initial_stream_window_size = Some(size);
+        self
+    }
+    /// HTTP/2 连接初始窗口大小
+    pub fn http2_initial_connection_window_size(mut self, size: u32) -> Self {
+        self.http2_initial_connection_window_size = Some(size);
+        self
+    }
+    /// HTTP/2 是否启用自适应窗口，启用后忽略初始窗口大小
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> Self {
+        self.http2_adaptive_window = Some(enabled);
+        self
+    }
+    /// HTTP/2 keep-alive ping 间隔
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+    /// HTTP/2 keep-alive ping 应答超时时间
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.http2_keep_alive_timeout = Some(timeout);
+        self
+    }
+    /// HTTP/2 最大请求头列表大小
+    pub fn http2_max_header_list_size(mut self, max: u32) -> Self {
+        self.http2_max_header_list_size = Some(max);
+        self
+    }
+}
+
+/// 将 HTTP/1 参数应用到 hyper 或 hyper-util 的 HTTP/1 构建器，两者方法签名一致
+macro_rules! apply_http1 {
+    ($config:expr, $builder:expr) => {{
+        let config = $config;
+        let builder = $builder;
+        builder.timer(hyper_util::rt::TokioTimer::new());
+        if let Some(enabled) = config.http1_keep_alive {
+            builder.keep_alive(enabled);
+        }
+        if let Some(timeout) = config.http1_header_read_timeout {
+            builder.header_read_timeout(timeout);
+        }
+        if let Some(max) = config.http1_max_headers {
+            builder.max_headers(max);
+        }
+        if let Some(max) = config.http1_max_buf_size {
+            builder.max_buf_size(max);
+        }
+    }};
+}
+
+/// 将 HTTP/2 参数应用到 hyper 或 hyper-util 的 HTTP/2 构建器，两者方法签名一致
+macro_rules! apply_http2 {
+    ($config:expr, $builder:expr) => {{
+        let config = $config;
+        let builder = $builder;
+        builder.timer(hyper_util::rt::TokioTimer::new());
+        if let Some(max) = config.http2_max_concurrent_streams {
+            builder.max_concurrent_streams(max);
+        }
+        if let Some(size) = config.http2_initial_stream_window_size {
+            builder.initial_stream_window_size(size);
+        }
+        if let Some(size) = config.http2_initial_connection_window_size {
+            builder.initial_connection_window_size(size);
+        }
+        if let Some(enabled) = config.http2_adaptive_window {
+            builder.adaptive_window(enabled);
+        }
+        if let Some(interval) = config.http2_keep_alive_interval {
+            builder.keep_alive_interval(interval);
+        }
+        if let Some(timeout) = config.http2_keep_alive_timeout {
+            builder.keep_alive_timeout(timeout);
+        }
+        if let Some(max) = config.http2_max_header_list_size {
+            builder.max_header_list_size(max);
+        }
+    }};
+}
+
+pub(crate) use {apply_http1, apply_http2};
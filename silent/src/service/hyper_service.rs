@@ -7,6 +7,7 @@ use hyper::{Request as HyperRequest, Response as HyperResponse};
 use crate::core::socket_addr::SocketAddr;
 use crate::core::{adapt::RequestAdapt, adapt::ResponseAdapt, res_body::ResBody};
 use crate::prelude::ReqBody;
+use crate::service::connection::ConnectionInfo;
 use crate::{Handler, Request, Response};
 
 #[doc(hidden)]
@@ -14,6 +15,7 @@ use crate::{Handler, Request, Response};
 pub struct HyperServiceHandler<H: Handler> {
     pub(crate) remote_addr: SocketAddr,
     pub(crate) routes: H,
+    pub(crate) connection: Option<ConnectionInfo>,
 }
 
 impl<H: Handler + Clone> HyperServiceHandler<H> {
@@ -22,18 +24,34 @@ impl<H: Handler + Clone> HyperServiceHandler<H> {
         Self {
             remote_addr,
             routes,
+            connection: None,
         }
     }
+    /// 设置连接信息，请求处理时放入请求拓展
+    #[inline]
+    pub(crate) fn with_connection(mut self, connection: ConnectionInfo) -> Self {
+        self.connection = Some(connection);
+        self
+    }
     /// Handle [`Request`] and returns [`Response`].
     #[inline]
     pub fn handle(&self, mut req: Request) -> impl Future<Output = Response> + use<H> {
         let Self {
             remote_addr,
             routes,
+            connection,
         } = self.clone();
         req.set_remote(remote_addr);
-        let routes = routes.clone();
-        async move { routes.call(req).await.unwrap_or_else(Into::into) }
+        let guard = connection.map(|connection| {
+            let guard = connection.request_started();
+            req.extensions_mut().insert(connection);
+            guard
+        });
+        async move {
+            let res = routes.call(req).await.unwrap_or_else(Into::into);
+            drop(guard);
+            res
+        }
     }
 }
 
@@ -76,4 +94,62 @@ mod tests {
         // Act
         let _ = hsh.call(req).await;
     }
+
+    #[tokio::test]
+    async fn test_trailers() {
+        use crate::prelude::*;
+        use bytes::Bytes;
+        use futures_util::{TryStreamExt, stream};
+        use http_body::Frame;
+        use http_body_util::{BodyExt, StreamBody};
+        use hyper_util::rt::{TokioExecutor, TokioIo};
+
+        let route = Route::new("").post(|mut req: Request| async move {
+            let (body, trailers) = req.body_with_trailers().await?;
+            let checksum = trailers
+                .and_then(|trailers| trailers.get("x-checksum").cloned())
+                .unwrap_or(header::HeaderValue::from_static("missing"));
+            let mut res = Response::empty().with_body(full(body));
+            let mut trailers = header::HeaderMap::new();
+            trailers.insert("x-checksum", checksum);
+            res.set_trailers(trailers);
+            Ok(res)
+        });
+        let remote_addr = "127.0.0.1:8080"
+            .parse::<std::net::SocketAddr>()
+            .unwrap()
+            .into();
+        let hsh = HyperServiceHandler::new(remote_addr, route);
+        let (client, server) = tokio::io::duplex(1024);
+        tokio::spawn(
+            hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(server), hsh),
+        );
+        let (mut sender, conn) =
+            hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(client))
+                .await
+                .unwrap();
+        tokio::spawn(conn);
+
+        let mut trailers = header::HeaderMap::new();
+        trailers.insert("x-checksum", "abc".parse().unwrap());
+        let frames = vec![
+            Ok::<_, std::io::Error>(Frame::data(Bytes::from("hello "))),
+            Ok(Frame::data(Bytes::from("world"))),
+            Ok(Frame::trailers(trailers)),
+        ];
+        let req = hyper::Request::post("http://localhost/")
+            .body(StreamBody::new(stream::iter(frames)))
+            .unwrap();
+        let res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.headers()["trailer"], "x-checksum");
+        let collected = res.into_body().collect().await.unwrap();
+        assert_eq!(collected.trailers().unwrap()["x-checksum"], "abc");
+        assert_eq!(collected.to_bytes(), "hello world");
+
+        let mut res = Response::text("hello");
+        res.set_trailers_with(|| None);
+        let chunks: Vec<Bytes> = res.take_body().try_collect().await.unwrap();
+        assert_eq!(chunks, vec![Bytes::from("hello")]);
+    }
 }
@@ -1,22 +1,62 @@
-use crate::core::listener::ListenersBuilder;
+use crate::core::connection::Connection;
+#[cfg(not(target_os = "windows"))]
+use crate::core::listen_fds::ListenFds;
+use crate::core::listener::{Accepted, HandshakeFuture, Listeners, ListenersBuilder};
+mod connection;
+mod handle;
+#[cfg(feature = "http3")]
+mod http3;
+mod http_config;
 mod hyper_service;
 mod serve;
 
+pub use connection::{ConnectionInfo, TlsInfo};
+pub use handle::ServerHandle;
+pub use http_config::{HttpConfig, HttpProtocol};
+#[cfg(feature = "http3")]
+pub use http3::{Http3Body, Http3Listener};
+
 use crate::Configs;
+#[cfg(feature = "tls")]
+use crate::core::tls::TlsConfig;
 use crate::prelude::Listen;
-use crate::route::RouteService;
+use crate::route::{Route, RouteService};
 #[cfg(feature = "scheduler")]
 use crate::scheduler::{SCHEDULER, Scheduler, middleware::SchedulerMiddleware};
-use crate::service::serve::Serve;
+use crate::service::connection::{ConnectionLimits, ConnectionTracker, tls_info};
+#[cfg(feature = "http3")]
+use crate::service::http3::{AltSvc, PendingHttp3, serve_http3};
+use crate::service::serve::{GracefulShutdown, Serve, Watcher};
+#[cfg(feature = "settings")]
+use crate::settings::{ReloadableSettings, Settings};
+use std::future::Future;
 use std::net::SocketAddr;
 #[cfg(not(target_os = "windows"))]
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
+use tokio::sync::{OwnedSemaphorePermit, watch};
 use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+type ShutdownSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// 默认的优雅关闭等待时间
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct Server {
     listeners_builder: ListenersBuilder,
     shutdown_callback: Option<Box<dyn Fn() + Send + Sync>>,
+    shutdown_signal: Option<ShutdownSignal>,
+    shutdown_timeout: Duration,
+    http_config: HttpConfig,
+    limits: ConnectionLimits,
+    #[cfg(feature = "http3")]
+    http3_listeners: Vec<PendingHttp3>,
+    #[cfg(feature = "settings")]
+    reloadable_settings: Option<ReloadableSettings>,
     configs: Option<Configs>,
 }
 
@@ -31,6 +71,14 @@ impl Server {
         Self {
             listeners_builder: ListenersBuilder::new(),
             shutdown_callback: None,
+            shutdown_signal: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            http_config: HttpConfig::default(),
+            limits: ConnectionLimits::default(),
+            #[cfg(feature = "http3")]
+            http3_listeners: vec![],
+            #[cfg(feature = "settings")]
+            reloadable_settings: None,
             configs: None,
         }
     }
@@ -66,6 +114,170 @@ impl Server {
         self
     }
 
+    /// 使用通过 `LISTEN_FDS` 继承的监听器
+    ///
+    /// 支持 systemd socket activation 与 [`ServerHandle::handoff`] 移交的监听器，
+    /// 详见 [`Listener::from_env`](crate::prelude::Listener::from_env)。
+    #[cfg(not(target_os = "windows"))]
+    #[inline]
+    pub fn bind_inherited(mut self) -> Self {
+        self.listeners_builder.bind_inherited(None);
+        self
+    }
+
+    /// 使用继承的监听器，没有继承监听器时绑定 `addr`
+    #[cfg(not(target_os = "windows"))]
+    #[inline]
+    pub fn bind_inherited_or(mut self, addr: SocketAddr) -> Self {
+        self.listeners_builder.bind_inherited(Some(addr));
+        self
+    }
+
+    /// 绑定地址并使用 TLS，证书加载失败时 [`Server::start`] 返回错误
+    #[cfg(feature = "tls")]
+    #[inline]
+    pub fn bind_tls(mut self, addr: SocketAddr, config: TlsConfig) -> Self {
+        self.listeners_builder.bind_tls(addr, config);
+        self
+    }
+
+    /// 按 [`Settings`] 中的 `server` 段添加监听地址并设置超时与连接限制，
+    /// 同时将所有配置段放入 [`Configs`]
+    #[cfg(feature = "settings")]
+    pub fn with_settings(mut self, settings: &Settings) -> Self {
+        let server = settings.server();
+        for addr in server.bind.iter().copied() {
+            #[cfg(feature = "tls")]
+            if let Some(tls) = &server.tls {
+                let mut config = TlsConfig::new().cert(&tls.cert, &tls.key);
+                if let Some(client_ca) = &tls.client_ca {
+                    config = config.client_ca(client_ca);
+                }
+                self = self.bind_tls(addr, config);
+                continue;
+            }
+            self = self.bind(addr);
+        }
+        if let Some(timeout) = server.shutdown_timeout {
+            self.shutdown_timeout = timeout;
+        }
+        if server.idle_timeout.is_some() {
+            self.limits.idle_timeout = server.idle_timeout;
+        }
+        if server.max_connections.is_some() {
+            self.limits.max_connections = server.max_connections;
+        }
+        if server.max_connections_per_ip.is_some() {
+            self.limits.max_connections_per_ip = server.max_connections_per_ip;
+        }
+        let mut configs = self.configs.take().unwrap_or_default();
+        configs.extend(settings.configs());
+        self.configs = Some(configs);
+        self
+    }
+
+    /// 使用可热重载的配置，重新加载后处理函数通过 `req.get_config::<T>()` 读取到最新的配置段
+    ///
+    /// 监听地址、超时等服务器参数只在启动时生效。
+    #[cfg(feature = "settings")]
+    pub fn with_reloadable_settings(mut self, settings: ReloadableSettings) -> Self {
+        self = self.with_settings(&settings.settings());
+        self.reloadable_settings = Some(settings);
+        self
+    }
+
+    /// 绑定 UDP 地址提供 HTTP/3 服务，TCP 监听器的响应会通过 `Alt-Svc` 告知该端口
+    #[cfg(feature = "http3")]
+    #[inline]
+    pub fn bind_h3(mut self, addr: SocketAddr, tls: TlsConfig) -> Self {
+        self.http3_listeners.push(PendingHttp3::Bind(addr, tls));
+        self
+    }
+
+    /// 添加 HTTP/3 监听器
+    #[cfg(feature = "http3")]
+    #[inline]
+    pub fn listen_h3(mut self, listener: Http3Listener) -> Self {
+        self.http3_listeners.push(PendingHttp3::Listener(listener));
+        self
+    }
+
+    /// 设置所有监听器默认使用的 HTTP 参数
+    #[inline]
+    pub fn with_http_config(mut self, config: HttpConfig) -> Self {
+        self.http_config = config;
+        self
+    }
+
+    /// 绑定地址并使用独立的 HTTP 参数，覆盖 [`Server::with_http_config`] 的设置
+    #[inline]
+    pub fn bind_with(mut self, addr: SocketAddr, config: HttpConfig) -> Self {
+        self.listeners_builder.bind(addr);
+        self.listeners_builder.set_http_config(config);
+        self
+    }
+
+    /// 绑定 unix socket 并使用独立的 HTTP 参数
+    #[cfg(not(target_os = "windows"))]
+    #[inline]
+    pub fn bind_unix_with<P: AsRef<Path>>(mut self, path: P, config: HttpConfig) -> Self {
+        self.listeners_builder.bind_unix(path);
+        self.listeners_builder.set_http_config(config);
+        self
+    }
+
+    /// 添加监听器并使用独立的 HTTP 参数
+    #[inline]
+    pub fn listen_with<T: Listen + Send + Sync + 'static>(
+        mut self,
+        listener: T,
+        config: HttpConfig,
+    ) -> Self {
+        self.listeners_builder.add_listener(Box::new(listener));
+        self.listeners_builder.set_http_config(config);
+        self
+    }
+
+    /// 绑定地址并使用独立的路由，其他监听器仍使用 [`Server::serve`] 传入的路由
+    ///
+    /// 所有路由共享服务器的 [`Configs`] 与关闭流程。
+    /// ```no_run
+    /// use silent::prelude::*;
+    /// let api = Route::new("api").get(|_req| async { Ok("api") });
+    /// let health = Route::new("health").get(|_req| async { Ok("ok") });
+    /// Server::new()
+    ///     .bind("0.0.0.0:8000".parse().unwrap())
+    ///     .bind_route("0.0.0.0:8081".parse().unwrap(), health)
+    ///     .run(api);
+    /// ```
+    #[inline]
+    pub fn bind_route<R: RouteService>(mut self, addr: SocketAddr, route: R) -> Self {
+        self.listeners_builder.bind(addr);
+        self.listeners_builder.set_route(route.route());
+        self
+    }
+
+    /// 绑定 unix socket 并使用独立的路由
+    #[cfg(not(target_os = "windows"))]
+    #[inline]
+    pub fn bind_unix_route<P: AsRef<Path>, R: RouteService>(mut self, path: P, route: R) -> Self {
+        self.listeners_builder.bind_unix(path);
+        self.listeners_builder.set_route(route.route());
+        self
+    }
+
+    /// 添加监听器并使用独立的路由
+    #[inline]
+    pub fn listen_route<T: Listen + Send + Sync + 'static, R: RouteService>(
+        mut self,
+        listener: T,
+        route: R,
+    ) -> Self {
+        self.listeners_builder.add_listener(Box::new(listener));
+        self.listeners_builder.set_route(route.route());
+        self
+    }
+
     pub fn set_shutdown_callback<F>(mut self, callback: F) -> Self
     where
         F: Fn() + Send + Sync + 'static,
@@ -74,78 +286,171 @@ impl Server {
         self
     }
 
-    pub async fn serve<S>(self, service: S)
+    /// 设置额外的关闭信号，信号完成后服务器开始优雅关闭
+    ///
+    /// 除 ctrl-c/SIGTERM 外，便于测试或嵌入的应用主动停止服务器，例如传入
+    /// `CancellationToken::cancelled_owned()` 或 oneshot 接收端。
+    pub fn with_shutdown_signal<F>(mut self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_signal = Some(Box::pin(signal));
+        self
+    }
+
+    /// 设置优雅关闭时等待处理中请求完成的最长时间，超时后强制关闭剩余连接，默认 30 秒
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// 设置最大并发连接数，达到上限时暂停接受新连接，直到已有连接关闭
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.limits.max_connections = Some(max);
+        self
+    }
+
+    /// 设置单个 IP 的最大并发连接数，超出时直接关闭新连接
+    pub fn with_max_connections_per_ip(mut self, max: usize) -> Self {
+        self.limits.max_connections_per_ip = Some(max);
+        self
+    }
+
+    /// 设置空闲连接超时时间，连接上没有处理中的请求且超过该时间无读写时关闭连接
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// 绑定监听器并在后台开始服务，返回 [`ServerHandle`]
+    ///
+    /// 需要在 tokio 运行时中调用，绑定失败时返回错误。
+    pub fn start<S>(self, service: S) -> std::io::Result<ServerHandle>
     where
         S: RouteService,
     {
         let Self {
             listeners_builder,
             configs,
-            ..
+            shutdown_callback,
+            shutdown_signal,
+            shutdown_timeout,
+            http_config,
+            limits,
+            #[cfg(feature = "http3")]
+            http3_listeners,
+            #[cfg(feature = "settings")]
+            reloadable_settings,
         } = self;
 
-        let mut listener = listeners_builder.listen().expect("failed to listen");
+        let listener = listeners_builder.listen()?;
         for addr in listener.local_addrs().iter() {
             tracing::info!("listening on: {:?}", addr);
         }
-        let mut root_route = service.route();
-
-        // 只有当configs不是None时才设置，避免覆盖已有的configs
-        if let Some(config) = configs {
-            root_route.set_configs(Some(config));
+        let local_addrs = listener.local_addrs().clone();
+        #[cfg(not(target_os = "windows"))]
+        let listen_fds = ListenFds::new(listener.raw_fds());
+        #[cfg(feature = "http3")]
+        let http3_listeners = http3_listeners
+            .into_iter()
+            .map(PendingHttp3::bind)
+            .collect::<std::io::Result<Vec<_>>>()?;
+        #[cfg(feature = "http3")]
+        let http3_addrs = http3_listeners
+            .iter()
+            .map(|listener| listener.local_addr())
+            .collect::<std::io::Result<Vec<_>>>()?;
+        #[cfg(feature = "http3")]
+        for addr in http3_addrs.iter() {
+            tracing::info!("listening on: {:?} (http3)", addr);
         }
+        #[cfg(feature = "http3")]
+        let alt_svc = AltSvc::new(http3_addrs.iter().map(|addr| addr.port()));
 
-        #[cfg(feature = "session")]
-        root_route.check_session();
-        #[cfg(feature = "cookie")]
-        root_route.check_cookie();
-        #[cfg(feature = "scheduler")]
-        root_route.hook_first(SchedulerMiddleware::new());
+        // 各监听器的路由共享服务器配置与内置中间件
+        let prepare = |mut route: Route| {
+            // 只有当configs不是None时才设置，避免覆盖已有的configs
+            if let Some(config) = &configs {
+                route.set_configs(Some(config.clone()));
+            }
+            #[cfg(feature = "session")]
+            route.check_session();
+            #[cfg(feature = "cookie")]
+            route.check_cookie();
+            #[cfg(feature = "scheduler")]
+            route.hook_first(SchedulerMiddleware::new());
+            #[cfg(feature = "settings")]
+            if let Some(settings) = &reloadable_settings {
+                route.hook_first(settings.clone());
+            }
+            #[cfg(feature = "http3")]
+            if let Some(alt_svc) = &alt_svc {
+                route.hook_first(alt_svc.clone());
+            }
+            route
+        };
+        let root_route = prepare(service.route());
         #[cfg(feature = "scheduler")]
         tokio::spawn(async move {
             let scheduler = SCHEDULER.clone();
             Scheduler::schedule(scheduler).await;
         });
-        let mut join_set = JoinSet::new();
-        loop {
-            #[cfg(unix)]
-            let terminate = async {
-                signal::unix::signal(signal::unix::SignalKind::terminate())
-                    .expect("failed to install signal handler")
-                    .recv()
-                    .await;
-            };
-
-            #[cfg(not(unix))]
-            let terminate = async {
-                let _ = std::future::pending::<()>().await;
-            };
-            tokio::select! {
-                _ = signal::ctrl_c() => {
-                    if let Some(ref callback) = self.shutdown_callback { callback() };
-                    break;
-                }
-                _ = terminate => {
-                    if let Some(ref callback) = self.shutdown_callback { callback() };
-                    break;
-                }
-                Some(s) = listener.accept() =>{
-                    match s{
-                        Ok((stream, peer_addr)) => {
-                            tracing::info!("Accepting from: {}", peer_addr);
-                            let routes = root_route.clone();
-                            join_set.spawn(async move {
-                                if let Err(err) = Serve::new(routes).call(stream,peer_addr).await {
-                                    tracing::error!("Failed to serve connection: {:?}", err);
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            tracing::error!(error = ?e, "accept connection failed");
-                        }
-                    }
-                }
-            }
+
+        let (ready_tx, ready) = watch::channel(false);
+        let shutdown = CancellationToken::new();
+        let abort = CancellationToken::new();
+        let shutdown_signal = shutdown_signal.unwrap_or_else(|| Box::pin(std::future::pending()));
+        let serves = listener
+            .options()
+            .iter()
+            .map(|options| {
+                let route = match &options.route {
+                    Some(route) => prepare(route.clone()),
+                    None => root_route.clone(),
+                };
+                Arc::new(Serve::new(
+                    route,
+                    options.http_config.as_ref().unwrap_or(&http_config),
+                ))
+            })
+            .collect();
+        let join = tokio::spawn(serve_connections(
+            listener,
+            serves,
+            ServeControl {
+                ready: ready_tx,
+                shutdown_signal,
+                shutdown: shutdown.clone(),
+                abort: abort.clone(),
+                shutdown_callback,
+                shutdown_timeout,
+                limits,
+                #[cfg(not(target_os = "windows"))]
+                listen_fds: listen_fds.clone(),
+                #[cfg(feature = "http3")]
+                http3: (http3_listeners, root_route),
+            },
+        ));
+        Ok(ServerHandle {
+            local_addrs,
+            #[cfg(not(target_os = "windows"))]
+            listen_fds,
+            #[cfg(feature = "http3")]
+            http3_addrs,
+            ready,
+            shutdown,
+            abort,
+            join,
+        })
+    }
+
+    pub async fn serve<S>(self, service: S)
+    where
+        S: RouteService,
+    {
+        let handle = self.start(service).expect("failed to listen");
+        if let Err(e) = handle.join().await {
+            tracing::error!("server task failed: {:?}", e);
         }
     }
 
@@ -160,3 +465,558 @@ impl Server {
             .block_on(self.serve(service));
     }
 }
+
+struct ServeControl {
+    ready: watch::Sender<bool>,
+    shutdown_signal: ShutdownSignal,
+    shutdown: CancellationToken,
+    abort: CancellationToken,
+    shutdown_callback: Option<Box<dyn Fn() + Send + Sync>>,
+    shutdown_timeout: Duration,
+    limits: ConnectionLimits,
+    #[cfg(not(target_os = "windows"))]
+    listen_fds: ListenFds,
+    #[cfg(feature = "http3")]
+    http3: (Vec<Http3Listener>, Route),
+}
+
+/// TLS 等握手的超时时间，超时的连接直接关闭
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 先获取连接限额再接受连接，达到上限时不再从监听器接受连接
+async fn accept_connection(
+    listener: &Listeners,
+    permit: impl Future<Output = Option<OwnedSemaphorePermit>>,
+) -> Option<(Option<OwnedSemaphorePermit>, Accepted)> {
+    let permit = permit.await;
+    listener.accept().await.map(|accepted| (permit, accepted))
+}
+
+/// 在连接任务中完成握手，慢速客户端不会阻塞监听器，超时、失败或服务器关闭时返回 `None`
+async fn handshake_connection(
+    handshake: HandshakeFuture,
+    watcher: &mut Watcher,
+    peer_addr: &crate::core::socket_addr::SocketAddr,
+) -> Option<Box<dyn Connection + Send + Sync>> {
+    tokio::select! {
+        result = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake) => match result {
+            Ok(Ok(stream)) => Some(stream),
+            Ok(Err(e)) => {
+                tracing::debug!(error = ?e, "handshake with {} failed", peer_addr);
+                None
+            }
+            Err(_) => {
+                tracing::debug!("handshake with {} timed out", peer_addr);
+                None
+            }
+        },
+        _ = watcher.shutdown_requested() => None,
+    }
+}
+
+async fn serve_connections(listener: Listeners, serves: Vec<Arc<Serve>>, control: ServeControl) {
+    let ServeControl {
+        ready,
+        mut shutdown_signal,
+        shutdown,
+        abort,
+        shutdown_callback,
+        mut shutdown_timeout,
+        limits,
+        #[cfg(not(target_os = "windows"))]
+        listen_fds,
+        #[cfg(feature = "http3")]
+        http3,
+    } = control;
+    let tracker = ConnectionTracker::new(limits);
+    let graceful = GracefulShutdown::new();
+    let mut join_set = JoinSet::new();
+    #[cfg(feature = "http3")]
+    {
+        let (http3_listeners, routes) = http3;
+        for listener in http3_listeners {
+            join_set.spawn(serve_http3(
+                listener,
+                routes.clone(),
+                tracker.clone(),
+                graceful.watcher(),
+            ));
+        }
+    }
+    let _ = ready.send(true);
+    // 接受连接的 future 在循环外长期保留，其他分支就绪时不会被丢弃
+    let mut accept = Box::pin(accept_connection(&listener, tracker.acquire()));
+    loop {
+        #[cfg(unix)]
+        let terminate = async {
+            signal::unix::signal(signal::unix::SignalKind::terminate())
+                .expect("failed to install signal handler")
+                .recv()
+                .await;
+        };
+
+        #[cfg(not(unix))]
+        let terminate = async {
+            let _ = std::future::pending::<()>().await;
+        };
+        tokio::select! {
+            _ = signal::ctrl_c() => break,
+            _ = terminate => break,
+            _ = &mut shutdown_signal => break,
+            _ = shutdown.cancelled() => break,
+            _ = abort.cancelled() => {
+                shutdown_timeout = Duration::ZERO;
+                break;
+            }
+            accepted = &mut accept => {
+                accept.set(accept_connection(&listener, tracker.acquire()));
+                let Some((permit, accepted)) = accepted else {
+                    tracing::error!("no listener available");
+                    break;
+                };
+                match accepted {
+                    (index, Ok((stream, peer_addr))) => {
+                        let Some((id, connection_permit)) = tracker.admit(permit, &peer_addr) else {
+                            tracing::warn!("too many connections from {}, closing", peer_addr);
+                            continue;
+                        };
+                        tracing::info!("Accepting from: {}", peer_addr);
+                        let serve = serves[index].clone();
+                        let mut watcher = graceful.watcher();
+                        let handshake = listener.handshake(index, stream);
+                        let idle_timeout = tracker.idle_timeout;
+                        join_set.spawn(async move {
+                            let _permit = connection_permit;
+                            let Some(stream) = handshake_connection(handshake, &mut watcher, &peer_addr).await else {
+                                return;
+                            };
+                            let info = ConnectionInfo::new(id, peer_addr.clone(), tls_info(stream.as_ref()));
+                            if let Err(err) = serve
+                                .call(stream, peer_addr, watcher, info, idle_timeout)
+                                .await
+                            {
+                                tracing::error!("Failed to serve connection: {:?}", err);
+                            }
+                        });
+                    }
+                    (_, Err(e)) => {
+                        tracing::error!(error = ?e, "accept connection failed");
+                    }
+                }
+            }
+            // 回收已结束的连接任务
+            Some(_) = join_set.join_next(), if !join_set.is_empty() => {}
+        }
+    }
+
+    // 停止接收新连接，等待处理中的请求完成
+    #[cfg(not(target_os = "windows"))]
+    listen_fds.close();
+    drop(accept);
+    drop(listener);
+    if let Some(ref callback) = shutdown_callback {
+        callback()
+    };
+    tracing::info!(
+        "graceful shutdown: waiting for {} connections",
+        graceful.count()
+    );
+    tokio::select! {
+        _ = graceful.shutdown() => {
+            tracing::info!("all connections closed");
+        }
+        _ = abort.cancelled() => {
+            tracing::warn!("server aborted, force closing connections");
+        }
+        _ = tokio::time::sleep(shutdown_timeout) => {
+            tracing::warn!(
+                "graceful shutdown timed out after {:?}, force closing connections",
+                shutdown_timeout
+            );
+        }
+    }
+    join_set.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    fn slow_route(delay: Duration) -> Route {
+        Route::new("").get(move |_req| async move {
+            tokio::time::sleep(delay).await;
+            Ok("done")
+        })
+    }
+
+    async fn send_request(addr: SocketAddr) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_drains_in_flight_requests() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(
+            Server::new()
+                .listen(Listener::from(listener))
+                .with_shutdown_signal(async move {
+                    rx.await.ok();
+                })
+                .serve(slow_route(Duration::from_millis(300))),
+        );
+        let mut stream = send_request(addr).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        tx.send(()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.to_lowercase().contains("connection: close"));
+        assert!(response.ends_with("done"));
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_timeout_force_closes() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(
+            Server::new()
+                .listen(Listener::from(listener))
+                .with_shutdown_signal(async move {
+                    rx.await.ok();
+                })
+                .with_shutdown_timeout(Duration::from_millis(100))
+                .serve(slow_route(Duration::from_secs(30))),
+        );
+        let mut stream = send_request(addr).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.ok();
+        assert!(response.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_server_handle() {
+        let handle = Server::new()
+            .bind("127.0.0.1:0".parse().unwrap())
+            .start(slow_route(Duration::ZERO))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        assert_ne!(addr.port(), 0);
+        let mut stream = send_request(addr).await;
+        let mut buf = [0; 15];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"HTTP/1.1 200 OK");
+
+        // 端口已被占用时返回绑定错误
+        let result = Server::new().bind(addr).start(slow_route(Duration::ZERO));
+        assert!(result.is_err());
+
+        handle.shutdown();
+        tokio::time::timeout(Duration::from_secs(5), handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_handle_abort() {
+        let handle = Server::new()
+            .start(slow_route(Duration::from_secs(30)))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let mut stream = send_request(addr).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.abort();
+        tokio::time::timeout(Duration::from_secs(5), handle.join())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.ok();
+        assert!(response.is_empty());
+    }
+
+    async fn raw_request(addr: SocketAddr, request: &[u8]) -> Vec<u8> {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request).await.unwrap();
+        let mut response = vec![];
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .ok();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_http_protocol_per_listener() {
+        let localhost = "127.0.0.1:0".parse().unwrap();
+        let handle = Server::new()
+            .bind_with(localhost, HttpConfig::new().http1_only())
+            .bind_with(localhost, HttpConfig::new().http2_only())
+            .start(slow_route(Duration::ZERO))
+            .unwrap();
+        handle.ready().await;
+        let http1_addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let http2_addr = handle.local_addrs()[1].tcp_addr().unwrap();
+        let preface = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+        // 仅 HTTP/1 的监听器不接受 h2c prior knowledge
+        let response = raw_request(http1_addr, preface).await;
+        assert!(response.is_empty() || response.starts_with(b"HTTP/1.1 "));
+        let response =
+            raw_request(http1_addr, b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n").await;
+        assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+
+        // 仅 HTTP/2 的监听器不接受 HTTP/1 请求
+        let response =
+            raw_request(http2_addr, b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n").await;
+        assert!(!response.starts_with(b"HTTP/1.1"));
+        // 服务端首先发送 SETTINGS 帧
+        let mut stream = TcpStream::connect(http2_addr).await.unwrap();
+        stream.write_all(preface).await.unwrap();
+        let mut frame_header = [0; 9];
+        stream.read_exact(&mut frame_header).await.unwrap();
+        assert_eq!(frame_header[3], 0x4);
+        drop(stream);
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http1_limits() {
+        let handle = Server::new()
+            .with_http_config(
+                HttpConfig::new()
+                    .http1_max_headers(2)
+                    .http1_header_read_timeout(Duration::from_millis(200)),
+            )
+            .start(slow_route(Duration::ZERO))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+
+        let response = raw_request(
+            addr,
+            b"GET / HTTP/1.1\r\nhost: localhost\r\na: 1\r\nb: 2\r\nc: 3\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with(b"HTTP/1.1 431"));
+
+        // 请求头未在超时时间内读取完成时关闭连接
+        let response = raw_request(addr, b"GET / HTTP/1.1\r\nhost: localhost\r\n").await;
+        assert!(!response.starts_with(b"HTTP/1.1 200"));
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    /// 读取 keep-alive 连接上的一个响应，连接关闭时返回 `None`
+    async fn read_response(stream: &mut TcpStream) -> Option<String> {
+        let mut buf = vec![];
+        loop {
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length: ")
+                            .map(str::to_string)
+                    })
+                    .and_then(|len| len.parse::<usize>().ok())
+                    .unwrap_or_default();
+                if body.len() >= length {
+                    return Some(text);
+                }
+            }
+            let mut chunk = [0; 1024];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_backpressure() {
+        let handle = Server::new()
+            .with_max_connections(1)
+            .start(slow_route(Duration::ZERO))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+
+        let mut first = send_request(addr).await;
+        assert!(read_response(&mut first).await.is_some());
+        // 达到上限时新连接在监听队列中等待
+        let mut second = send_request(addr).await;
+        let pending = tokio::time::timeout(Duration::from_millis(300), read_response(&mut second));
+        assert!(pending.await.is_err());
+        drop(first);
+        let response = tokio::time::timeout(Duration::from_secs(5), read_response(&mut second))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        handle.abort();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_per_ip() {
+        let handle = Server::new()
+            .with_max_connections_per_ip(1)
+            .start(slow_route(Duration::ZERO))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+
+        let mut first = send_request(addr).await;
+        assert!(read_response(&mut first).await.is_some());
+        let mut second = send_request(addr).await;
+        assert!(read_response(&mut second).await.is_none());
+        drop(first);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let mut third = send_request(addr).await;
+        assert!(read_response(&mut third).await.is_some());
+
+        handle.abort();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout() {
+        let handle =
+            Server::new()
+                .with_idle_timeout(Duration::from_millis(200))
+                .start(Route::new("").get(|_req| async { Ok("fast") }).append(
+                    Route::new("slow").get(|_req| async {
+                        tokio::time::sleep(Duration::from_millis(500)).await;
+                        Ok("slow")
+                    }),
+                ))
+                .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+
+        // 处理中的请求不计入空闲时间
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_response(&mut stream).await.unwrap();
+        assert!(response.ends_with("slow"));
+
+        // 空闲的 keep-alive 连接被关闭
+        let mut stream = send_request(addr).await;
+        assert!(read_response(&mut stream).await.is_some());
+        let closed = tokio::time::timeout(Duration::from_secs(2), read_response(&mut stream));
+        assert!(closed.await.unwrap().is_none());
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connection_info() {
+        let handle = Server::new()
+            .start(Route::new("").get(|req: Request| async move {
+                let info = req.extensions().get::<ConnectionInfo>().unwrap();
+                assert!(info.tls().is_none());
+                assert!(info.accepted_at() <= std::time::SystemTime::now());
+                Ok(format!("{}:{}", info.id(), info.requests_served()))
+            }))
+            .unwrap();
+        handle.ready().await;
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+
+        let mut stream = send_request(addr).await;
+        assert!(read_response(&mut stream).await.unwrap().ends_with("1:0"));
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(read_response(&mut stream).await.unwrap().ends_with("1:1"));
+        let mut stream = send_request(addr).await;
+        assert!(read_response(&mut stream).await.unwrap().ends_with("2:0"));
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_per_listener_routes() {
+        #[derive(Clone)]
+        struct Name(&'static str);
+        let api = Route::new("")
+            .get(|req: Request| async move { Ok(format!("api {}", req.get_config::<Name>()?.0)) });
+        let health = Route::new("health").get(|req: Request| async move {
+            Ok(format!("health {}", req.get_config::<Name>()?.0))
+        });
+        let admin = Route::new("metrics").get(|_req| async { Ok("admin") });
+        let mut configs = Configs::default();
+        configs.insert(Name("silent"));
+        let path = std::env::temp_dir().join(format!("silent-admin-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let localhost: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let handle = Server::new()
+            .with_configs(configs)
+            .bind(localhost)
+            .bind_route(localhost, health)
+            .bind_unix_route(&path, admin)
+            .start(api)
+            .unwrap();
+        handle.ready().await;
+        let api_addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        let health_addr = handle.local_addrs()[1].tcp_addr().unwrap();
+        let get = |path: &str| format!("GET {path} HTTP/1.1\r\nconnection: close\r\n\r\n");
+
+        let response = raw_request(api_addr, get("/").as_bytes()).await;
+        assert!(response.ends_with(b"api silent"));
+        let response = raw_request(api_addr, get("/health").as_bytes()).await;
+        assert!(response.starts_with(b"HTTP/1.1 404"));
+        let response = raw_request(health_addr, get("/health").as_bytes()).await;
+        assert!(response.ends_with(b"health silent"));
+        let response = raw_request(health_addr, get("/").as_bytes()).await;
+        assert!(response.starts_with(b"HTTP/1.1 404"));
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream.write_all(get("/metrics").as_bytes()).await.unwrap();
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await.unwrap();
+        assert!(response.ends_with(b"admin"));
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
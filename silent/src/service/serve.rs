@@ -1,34 +1,150 @@
 use crate::core::connection::Connection;
 use crate::core::socket_addr::SocketAddr;
 use crate::route::Route;
+use crate::service::connection::{ConnectionInfo, IdleStream};
+use crate::service::http_config::{HttpConfig, HttpProtocol, apply_http1, apply_http2};
 use crate::service::hyper_service::HyperServiceHandler;
+use hyper::server::conn::{http1, http2};
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder;
 use std::error::Error as StdError;
+use std::pin::{Pin, pin};
+use std::time::Duration;
+use tokio::sync::watch;
 
-pub(crate) struct Serve<E = TokioExecutor> {
-    pub(crate) routes: Route,
-    pub(crate) builder: Builder<E>,
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// 按监听器的 HTTP 参数构建的连接服务
+pub(crate) enum Serve {
+    Auto(Route, Builder<TokioExecutor>),
+    Http1(Route, http1::Builder),
+    Http2(Route, http2::Builder<TokioExecutor>),
 }
 
 impl Serve {
-    pub(crate) fn new(routes: Route) -> Self {
-        Self {
-            routes,
-            builder: Builder::new(TokioExecutor::new()),
+    pub(crate) fn new(routes: Route, config: &HttpConfig) -> Self {
+        match config.protocol {
+            HttpProtocol::Auto => {
+                let mut builder = Builder::new(TokioExecutor::new());
+                apply_http1!(config, &mut builder.http1());
+                apply_http2!(config, &mut builder.http2());
+                Serve::Auto(routes, builder)
+            }
+            HttpProtocol::Http1Only => {
+                let mut builder = http1::Builder::new();
+                apply_http1!(config, &mut builder);
+                Serve::Http1(routes, builder)
+            }
+            HttpProtocol::Http2Only => {
+                let mut builder = http2::Builder::new(TokioExecutor::new());
+                apply_http2!(config, &mut builder);
+                Serve::Http2(routes, builder)
+            }
         }
     }
+
     pub(crate) async fn call<S: Connection + Send + Sync + 'static>(
         &self,
         stream: S,
         peer_addr: SocketAddr,
-    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
-        let io = TokioIo::new(stream);
-        self.builder
-            .serve_connection_with_upgrades(
-                io,
-                HyperServiceHandler::new(peer_addr, self.routes.clone()),
-            )
-            .await
+        watcher: Watcher,
+        info: ConnectionInfo,
+        idle_timeout: Option<Duration>,
+    ) -> Result<(), BoxError> {
+        let io = TokioIo::new(IdleStream::new(stream, info.clone()));
+        let service = HyperServiceHandler::new(peer_addr, self.routes().clone())
+            .with_connection(info.clone());
+        let idle = info.idle_expired(idle_timeout);
+        // 由 watcher 跟踪连接，关闭时通知 keep-alive 连接（HTTP/1 Connection: close，HTTP/2 GOAWAY）
+        match self {
+            Serve::Auto(_, builder) => {
+                let conn = builder.serve_connection_with_upgrades(io, service);
+                watcher
+                    .watch(conn, idle, |conn| conn.graceful_shutdown())
+                    .await
+            }
+            Serve::Http1(_, builder) => {
+                let conn = builder.serve_connection(io, service).with_upgrades();
+                watcher
+                    .watch(conn, idle, |conn| conn.graceful_shutdown())
+                    .await
+            }
+            Serve::Http2(_, builder) => {
+                let conn = builder.serve_connection(io, service);
+                watcher
+                    .watch(conn, idle, |conn| conn.graceful_shutdown())
+                    .await
+            }
+        }
+    }
+
+    fn routes(&self) -> &Route {
+        match self {
+            Serve::Auto(routes, _) | Serve::Http1(routes, _) | Serve::Http2(routes, _) => routes,
+        }
+    }
+}
+
+/// 优雅关闭通知，等待所有被跟踪的连接结束
+pub(crate) struct GracefulShutdown {
+    tx: watch::Sender<()>,
+}
+
+impl GracefulShutdown {
+    pub(crate) fn new() -> Self {
+        let (tx, _) = watch::channel(());
+        Self { tx }
+    }
+
+    pub(crate) fn watcher(&self) -> Watcher {
+        Watcher(self.tx.subscribe())
+    }
+
+    /// 仍在跟踪的连接数量
+    pub(crate) fn count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// 通知所有连接优雅关闭，并等待连接全部结束
+    pub(crate) async fn shutdown(self) {
+        let _ = self.tx.send(());
+        self.tx.closed().await;
+    }
+}
+
+/// 单个连接的关闭通知接收端，连接结束时释放
+#[derive(Clone)]
+pub(crate) struct Watcher(watch::Receiver<()>);
+
+impl Watcher {
+    /// 等待服务器开始优雅关闭
+    pub(crate) async fn shutdown_requested(&mut self) {
+        let _ = self.0.changed().await;
+    }
+
+    /// 服务器关闭或连接空闲超时时通知连接优雅关闭
+    async fn watch<C, E>(
+        mut self,
+        conn: C,
+        idle: impl Future<Output = ()>,
+        graceful_shutdown: impl FnOnce(Pin<&mut C>),
+    ) -> Result<(), BoxError>
+    where
+        C: Future<Output = Result<(), E>>,
+        E: Into<BoxError>,
+    {
+        let mut conn = pin!(conn);
+        tokio::select! {
+            result = conn.as_mut() => result.map_err(Into::into),
+            _ = self.0.changed() => {
+                graceful_shutdown(conn.as_mut());
+                conn.await.map_err(Into::into)
+            }
+            _ = idle => {
+                tracing::debug!("closing idle connection");
+                graceful_shutdown(conn.as_mut());
+                conn.await.map_err(Into::into)
+            }
+        }
     }
 }
@@ -0,0 +1,126 @@
+use cookie::{Cookie, SameSite};
+use std::time::Duration;
+
+/// 会话配置
+///
+/// 控制会话 cookie 的属性以及会话的过期、续期策略。
+/// ```
+/// use silent::prelude::{SameSite, SessionConfig};
+/// use std::time::Duration;
+/// let _ = SessionConfig::new()
+///     .cookie_name("sid")
+///     .secure(true)
+///     .same_site(SameSite::Strict)
+///     .idle_timeout(Duration::from_secs(30 * 60))
+///     .absolute_timeout(Duration::from_secs(12 * 60 * 60))
+///     .rolling(true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub(crate) cookie_name: String,
+    pub(crate) path: String,
+    pub(crate) domain: Option<String>,
+    pub(crate) secure: bool,
+    pub(crate) http_only: bool,
+    pub(crate) same_site: Option<SameSite>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) absolute_timeout: Option<Duration>,
+    pub(crate) rolling: bool,
+    pub(crate) save_uninitialized: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: "silent-web-session".to_string(),
+            path: "/".to_string(),
+            domain: None,
+            secure: false,
+            http_only: true,
+            same_site: Some(SameSite::Lax),
+            idle_timeout: Some(Duration::from_secs(2 * 60 * 60)),
+            absolute_timeout: None,
+            rolling: false,
+            save_uninitialized: false,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 会话 cookie 名称
+    pub fn cookie_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+    /// 会话 cookie 路径
+    pub fn path<T: Into<String>>(mut self, path: T) -> Self {
+        self.path = path.into();
+        self
+    }
+    /// 会话 cookie 域名
+    pub fn domain<T: Into<String>>(mut self, domain: T) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+    /// 会话 cookie 是否仅通过 HTTPS 发送
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+    /// 会话 cookie 是否禁止脚本访问
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+    /// 会话 cookie 的 SameSite 属性
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+    /// 空闲过期时间，同时作为 cookie 的 Max-Age；`None` 表示浏览器会话 cookie
+    pub fn idle_timeout<T: Into<Option<Duration>>>(mut self, timeout: T) -> Self {
+        self.idle_timeout = timeout.into();
+        self
+    }
+    /// 绝对过期时间，自会话创建起计算，到期后无论是否活跃都会重建会话
+    pub fn absolute_timeout<T: Into<Option<Duration>>>(mut self, timeout: T) -> Self {
+        self.absolute_timeout = timeout.into();
+        self
+    }
+    /// 每次请求都续期会话并重新下发 cookie
+    pub fn rolling(mut self, rolling: bool) -> Self {
+        self.rolling = rolling;
+        self
+    }
+    /// 未写入数据的新会话也进行保存并下发 cookie
+    pub fn save_uninitialized(mut self, save_uninitialized: bool) -> Self {
+        self.save_uninitialized = save_uninitialized;
+        self
+    }
+
+    pub(crate) fn build_cookie(&self, value: String) -> Cookie<'static> {
+        let mut cookie = Cookie::build((self.cookie_name.clone(), value))
+            .path(self.path.clone())
+            .secure(self.secure)
+            .http_only(self.http_only);
+        if let Some(ref domain) = self.domain {
+            cookie = cookie.domain(domain.clone());
+        }
+        if let Some(same_site) = self.same_site {
+            cookie = cookie.same_site(same_site);
+        }
+        if let Some(timeout) = self.idle_timeout {
+            cookie = cookie.max_age(cookie::time::Duration::seconds(timeout.as_secs() as i64));
+        }
+        cookie.build()
+    }
+
+    pub(crate) fn removal_cookie(&self) -> Cookie<'static> {
+        let mut cookie = self.build_cookie(String::new());
+        cookie.make_removal();
+        cookie
+    }
+}
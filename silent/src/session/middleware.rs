@@ -1,15 +1,32 @@
+use crate::session::config::SessionConfig;
+use crate::session::session_ext::SessionControl;
 use crate::{CookieExt, Handler, MiddleWareHandler, Next, Request, Response};
 use async_session::{MemoryStore, Session, SessionStore};
 use async_trait::async_trait;
-use cookie::{Cookie, CookieJar};
+use chrono::Utc;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
+/// 会话创建时间在会话数据中的键，用于计算绝对过期时间
+pub(crate) const SESSION_CREATED_AT: &str = "__silent_session_created_at";
+
+/// 会话中间件
+///
+/// 请求进入时从 cookie 中加载会话，响应返回后将处理器对会话的修改保存到存储中。
+/// ```
+/// use silent::prelude::*;
+/// use std::time::Duration;
+/// let mut route = Route::new_root();
+/// route.set_session(
+///     SessionMiddleware::new(MemoryStore::new())
+///         .with_config(SessionConfig::new().idle_timeout(Duration::from_secs(1800))),
+/// );
+/// ```
 pub struct SessionMiddleware<T>
 where
     T: SessionStore,
 {
-    pub session_store: Arc<RwLock<T>>,
+    pub session_store: Arc<T>,
+    pub config: SessionConfig,
 }
 
 impl Default for SessionMiddleware<MemoryStore> {
@@ -24,8 +41,44 @@ where
     T: SessionStore,
 {
     pub fn new(session: T) -> Self {
-        let session_store = Arc::new(RwLock::new(session));
-        SessionMiddleware { session_store }
+        let session_store = Arc::new(session);
+        SessionMiddleware {
+            session_store,
+            config: SessionConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: SessionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    async fn load(&self, cookie_value: Option<String>) -> crate::Result<Option<Session>> {
+        let Some(cookie_value) = cookie_value else {
+            return Ok(None);
+        };
+        let session = match self.session_store.load_session(cookie_value).await {
+            Ok(session) => session.and_then(Session::validate),
+            Err(e) => {
+                tracing::debug!("session load failed: {}", e);
+                None
+            }
+        };
+        let Some(session) = session else {
+            return Ok(None);
+        };
+        if let Some(timeout) = self.config.absolute_timeout {
+            let expired = session
+                .get::<i64>(SESSION_CREATED_AT)
+                .is_none_or(|created_at| {
+                    Utc::now().timestamp() - created_at >= timeout.as_secs() as i64
+                });
+            if expired {
+                self.session_store.destroy_session(session).await?;
+                return Ok(None);
+            }
+        }
+        Ok(Some(session))
     }
 }
 
@@ -35,48 +88,189 @@ where
     T: SessionStore,
 {
     async fn handle(&self, mut req: Request, next: &Next) -> crate::Result<Response> {
-        let mut cookies = req.cookies().clone();
-        let cookie = cookies.get("silent-web-session");
-        let session_store = self.session_store.read().await;
-        let mut session_key_exists = false;
-        let mut cookie_value = if cookie.is_some() {
-            session_key_exists = true;
-            cookie.unwrap().value().to_string()
-        } else {
-            session_store.store_session(Session::new()).await?.unwrap()
-        };
-        let session =
-            if let Ok(Some(session)) = session_store.load_session(cookie_value.clone()).await {
-                session
-            } else {
-                session_key_exists = false;
-                cookie_value = session_store.store_session(Session::new()).await?.unwrap();
-                session_store
-                    .load_session(cookie_value.clone())
-                    .await?
-                    .unwrap()
-            };
+        let config = &self.config;
+        let cookie_value = req
+            .cookie(&config.cookie_name)
+            .map(|cookie| cookie.value().to_string());
+        let loaded = self.load(cookie_value.clone()).await?;
+        let is_new = loaded.is_none();
+        let mut session = loaded.unwrap_or_default();
+        let control = SessionControl::default();
         req.extensions_mut().insert(session.clone());
-        let session_copied = session.clone();
-        if !session_key_exists {
-            cookies.add(
-                Cookie::build(("silent-web-session", cookie_value))
-                    .max_age(cookie::time::Duration::hours(2)),
-            );
-        }
+        req.extensions_mut().insert(control.clone());
+
         let mut res = next.call(req).await?;
         if res.extensions().get::<Session>().is_none() {
-            res.extensions_mut().insert(session_copied);
+            res.extensions_mut().insert(session.clone());
         }
-        if res.extensions().get::<CookieJar>().is_none() {
-            res.extensions_mut().insert(cookies);
-        } else {
-            let cookie_jar = res.extensions_mut().get_mut::<CookieJar>().unwrap();
-            for cookie in cookie_jar.iter() {
-                cookies.add(cookie.clone());
+
+        if session.is_destroyed() {
+            if !is_new {
+                self.session_store.destroy_session(session).await?;
+            }
+            if cookie_value.is_some() {
+                res.cookies_mut().add(config.removal_cookie());
             }
-            res.extensions_mut().insert(cookies.clone());
+            return Ok(res);
+        }
+
+        let regenerate = control.regenerate_requested()
+            || res
+                .extensions()
+                .get::<SessionControl>()
+                .is_some_and(SessionControl::regenerate_requested);
+        if regenerate && !is_new {
+            let old_session = session.clone();
+            session.regenerate();
+            self.session_store.destroy_session(old_session).await?;
+        }
+
+        let changed = session.data_changed();
+        let should_store = if is_new {
+            changed || config.save_uninitialized
+        } else {
+            changed || regenerate || config.rolling
+        };
+        if !should_store {
+            return Ok(res);
+        }
+        if is_new && config.absolute_timeout.is_some() {
+            session.insert(SESSION_CREATED_AT, Utc::now().timestamp())?;
+        }
+        if let Some(timeout) = config.idle_timeout {
+            session.expire_in(timeout);
+        }
+        let stored_value = self.session_store.store_session(session).await?;
+        let cookie_value = match stored_value {
+            Some(value) => Some(value),
+            None if config.rolling => cookie_value,
+            None => None,
+        };
+        if let Some(value) = cookie_value {
+            res.cookies_mut().add(config.build_cookie(value));
         }
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Cookie, HandlerAppend, Route, SessionExt};
+    use std::time::Duration;
+
+    fn build_route(middleware: SessionMiddleware<MemoryStore>) -> Route {
+        let mut route = Route::new_root();
+        route.push(
+            Route::new("")
+                .get(|req: Request| async move {
+                    Ok(req.session::<i64>("count").unwrap_or_default().to_string())
+                })
+                .post(|mut req: Request| async move {
+                    let count = req.session::<i64>("count").unwrap_or_default() + 1;
+                    req.sessions_mut().insert("count", count)?;
+                    Ok(count.to_string())
+                })
+                .put(|mut req: Request| async move {
+                    req.regenerate_session();
+                    Ok("regenerated")
+                })
+                .delete(|mut req: Request| async move {
+                    req.sessions_mut().destroy();
+                    Ok("destroyed")
+                }),
+        );
+        route.set_session(middleware);
+        route
+    }
+
+    async fn call(route: &Route, method: http::Method, cookie: Option<&str>) -> Response {
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        *req.method_mut() = method;
+        if let Some(cookie) = cookie {
+            req.cookies_mut()
+                .add_original(Cookie::new("silent-web-session", cookie.to_string()));
+        }
+        route.call(req).await.unwrap()
+    }
+
+    fn session_cookie(res: &Response) -> Option<Cookie<'static>> {
+        res.cookie("silent-web-session").cloned()
+    }
+
+    #[tokio::test]
+    async fn test_session_persisted_after_response() {
+        let store = MemoryStore::new();
+        let route = build_route(SessionMiddleware::new(store.clone()));
+
+        // 未写入数据的会话不会保存
+        let res = call(&route, http::Method::GET, None).await;
+        assert!(session_cookie(&res).is_none());
+        assert_eq!(store.count().await, 0);
+
+        let res = call(&route, http::Method::POST, None).await;
+        let cookie = session_cookie(&res).unwrap();
+        assert!(cookie.http_only().unwrap());
+        assert_eq!(cookie.same_site(), Some(cookie::SameSite::Lax));
+        assert_eq!(store.count().await, 1);
+
+        let res = call(&route, http::Method::POST, Some(cookie.value())).await;
+        assert!(session_cookie(&res).is_none());
+        let session = store
+            .load_session(cookie.value().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.get::<i64>("count"), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_session_regenerate_and_destroy() {
+        let store = MemoryStore::new();
+        let route = build_route(SessionMiddleware::new(store.clone()));
+        let res = call(&route, http::Method::POST, None).await;
+        let cookie = session_cookie(&res).unwrap();
+
+        let res = call(&route, http::Method::PUT, Some(cookie.value())).await;
+        let regenerated = session_cookie(&res).unwrap();
+        assert_ne!(regenerated.value(), cookie.value());
+        assert!(
+            store
+                .load_session(cookie.value().to_string())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let session = store
+            .load_session(regenerated.value().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.get::<i64>("count"), Some(1));
+
+        let res = call(&route, http::Method::DELETE, Some(regenerated.value())).await;
+        let removal = session_cookie(&res).unwrap();
+        assert_eq!(removal.value(), "");
+        assert_eq!(store.count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_rolling_and_absolute_timeout() {
+        let store = MemoryStore::new();
+        let config = SessionConfig::new()
+            .cookie_name("silent-web-session")
+            .rolling(true)
+            .secure(true)
+            .absolute_timeout(Duration::from_secs(0));
+        let route = build_route(SessionMiddleware::new(store.clone()).with_config(config));
+        let res = call(&route, http::Method::POST, None).await;
+        let cookie = session_cookie(&res).unwrap();
+        assert!(cookie.secure().unwrap());
+
+        // 超过绝对过期时间后重建会话
+        let res = call(&route, http::Method::GET, Some(cookie.value())).await;
+        assert!(session_cookie(&res).is_none());
+        assert_eq!(store.count().await, 0);
+    }
+}
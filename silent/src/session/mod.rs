@@ -1,2 +1,8 @@
+mod config;
 pub(crate) mod middleware;
 pub mod session_ext;
+mod store;
+
+pub use config::SessionConfig;
+pub use middleware::SessionMiddleware;
+pub use store::{FileStore, RespStore, SignedCookieStore};
@@ -2,6 +2,24 @@ use crate::{Request, Response};
 use async_session::Session;
 use http_body::Body;
 use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 会话控制标记，由会话中间件在响应返回后读取
+#[derive(Clone, Default)]
+pub(crate) struct SessionControl {
+    regenerate: Arc<AtomicBool>,
+}
+
+impl SessionControl {
+    pub(crate) fn request_regenerate(&self) {
+        self.regenerate.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn regenerate_requested(&self) -> bool {
+        self.regenerate.load(Ordering::SeqCst)
+    }
+}
 
 pub trait SessionExt {
     /// Get `Session` reference.
@@ -10,6 +28,8 @@ pub trait SessionExt {
     fn sessions_mut(&mut self) -> &mut Session;
     /// Get `Session` from session.
     fn session<V: DeserializeOwned>(&self, name: &str) -> Option<V>;
+    /// Regenerate the session id after the response, e.g. after login to prevent session fixation.
+    fn regenerate_session(&mut self);
 }
 
 impl SessionExt for Request {
@@ -24,6 +44,16 @@ impl SessionExt for Request {
     fn session<V: DeserializeOwned>(&self, name: &str) -> Option<V> {
         self.sessions().get(name.as_ref())
     }
+
+    fn regenerate_session(&mut self) {
+        if self.extensions().get::<SessionControl>().is_none() {
+            self.extensions_mut().insert(SessionControl::default());
+        }
+        self.extensions()
+            .get::<SessionControl>()
+            .unwrap()
+            .request_regenerate();
+    }
 }
 
 impl<B: Body> SessionExt for Response<B> {
@@ -38,4 +68,14 @@ impl<B: Body> SessionExt for Response<B> {
     fn session<V: DeserializeOwned>(&self, name: &str) -> Option<V> {
         self.sessions().get(name.as_ref())
     }
+
+    fn regenerate_session(&mut self) {
+        if self.extensions().get::<SessionControl>().is_none() {
+            self.extensions_mut().insert(SessionControl::default());
+        }
+        self.extensions()
+            .get::<SessionControl>()
+            .unwrap()
+            .request_regenerate();
+    }
 }
@@ -0,0 +1,88 @@
+use crate::CookieKeys;
+use async_session::base64;
+use async_session::{Result, Session, SessionStore};
+use async_trait::async_trait;
+use cookie::{Cookie, CookieJar};
+
+const SIGNED_COOKIE_NAME: &str = "session";
+
+/// 签名 cookie 会话存储
+///
+/// 会话数据序列化后签名保存在 cookie 中，服务端不保存任何状态。
+/// 数据只签名不加密，且受浏览器 cookie 长度限制，不适合保存敏感或大量数据。
+/// ```
+/// use silent::prelude::*;
+/// let mut route = Route::new_root();
+/// route.set_session_store(SignedCookieStore::new(Key::generate()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SignedCookieStore {
+    keys: CookieKeys,
+}
+
+impl SignedCookieStore {
+    pub fn new<K: Into<CookieKeys>>(keys: K) -> Self {
+        Self { keys: keys.into() }
+    }
+}
+
+#[async_trait]
+impl SessionStore for SignedCookieStore {
+    async fn load_session(&self, cookie_value: String) -> Result<Option<Session>> {
+        let mut jar = CookieJar::new();
+        jar.add_original(Cookie::new(SIGNED_COOKIE_NAME, cookie_value));
+        let Some(cookie) = self.keys.verify(&jar, SIGNED_COOKIE_NAME) else {
+            return Ok(None);
+        };
+        let serialized = base64::decode_config(cookie.value(), base64::URL_SAFE_NO_PAD)?;
+        let session: Session = serde_json::from_slice(&serialized)?;
+        Ok(session.validate())
+    }
+
+    async fn store_session(&self, session: Session) -> Result<Option<String>> {
+        let serialized = serde_json::to_vec(&session)?;
+        let value = base64::encode_config(serialized, base64::URL_SAFE_NO_PAD);
+        let mut jar = CookieJar::new();
+        jar.signed_mut(self.keys.current())
+            .add(Cookie::new(SIGNED_COOKIE_NAME, value));
+        Ok(jar
+            .get(SIGNED_COOKIE_NAME)
+            .map(|cookie| cookie.value().to_string()))
+    }
+
+    async fn destroy_session(&self, _session: Session) -> Result {
+        Ok(())
+    }
+
+    async fn clear_store(&self) -> Result {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cookie::Key;
+
+    #[tokio::test]
+    async fn test_signed_cookie_store() {
+        let key = Key::generate();
+        let store = SignedCookieStore::new(key.clone());
+        let mut session = Session::new();
+        session.insert("user", "silent").unwrap();
+        let value = store.store_session(session.clone()).await.unwrap().unwrap();
+        let loaded = store.load_session(value.clone()).await.unwrap().unwrap();
+        assert_eq!(loaded.id(), session.id());
+        assert_eq!(loaded.get::<String>("user").unwrap(), "silent");
+
+        // 篡改或密钥不匹配时不加载
+        let tampered = format!("{}A", value);
+        assert!(store.load_session(tampered).await.unwrap().is_none());
+        let other = SignedCookieStore::new(Key::generate());
+        assert!(other.load_session(value.clone()).await.unwrap().is_none());
+
+        // 轮换密钥后旧 cookie 仍然有效
+        let rotated = SignedCookieStore::new(CookieKeys::new(Key::generate()).with_old_key(key));
+        assert!(rotated.load_session(value).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,142 @@
+use async_session::{Result, Session, SessionStore};
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const SESSION_FILE_EXTENSION: &str = "session";
+
+/// 文件会话存储
+///
+/// 每个会话以 JSON 文件的形式保存在指定目录中，文件名由会话 id 生成。
+/// ```
+/// use silent::prelude::*;
+/// let mut route = Route::new_root();
+/// route.set_session_store(FileStore::new("./sessions"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn session_path(&self, id: &str) -> PathBuf {
+        let file_name = id.replace('/', "_").replace('+', "-");
+        self.dir
+            .join(file_name)
+            .with_extension(SESSION_FILE_EXTENSION)
+    }
+
+    async fn session_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == SESSION_FILE_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+
+    async fn remove_file(path: &Path) -> Result {
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 清理已过期的会话文件
+    pub async fn cleanup(&self) -> Result {
+        for path in self.session_files().await? {
+            let expired = match tokio::fs::read(&path).await {
+                Ok(content) => serde_json::from_slice::<Session>(&content)
+                    .map(|session| session.is_expired())
+                    .unwrap_or(true),
+                Err(_) => continue,
+            };
+            if expired {
+                Self::remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileStore {
+    async fn load_session(&self, cookie_value: String) -> Result<Option<Session>> {
+        let id = Session::id_from_cookie_value(&cookie_value)?;
+        let content = match tokio::fs::read(self.session_path(&id)).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let session: Session = serde_json::from_slice(&content)?;
+        Ok(session.validate())
+    }
+
+    async fn store_session(&self, session: Session) -> Result<Option<String>> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let content = serde_json::to_vec(&session)?;
+        tokio::fs::write(self.session_path(session.id()), content).await?;
+        session.reset_data_changed();
+        Ok(session.into_cookie_value())
+    }
+
+    async fn destroy_session(&self, session: Session) -> Result {
+        Self::remove_file(&self.session_path(session.id())).await
+    }
+
+    async fn clear_store(&self) -> Result {
+        for path in self.session_files().await? {
+            Self::remove_file(&path).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().simple().to_string());
+        let store = FileStore::new(&dir);
+        let mut session = Session::new();
+        session.insert("user", "silent").unwrap();
+        let cookie_value = store.store_session(session).await.unwrap().unwrap();
+        let loaded = store
+            .load_session(cookie_value.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.get::<String>("user").unwrap(), "silent");
+
+        let mut expired = Session::new();
+        expired.expire_in(Duration::from_secs(0));
+        store.store_session(expired).await.unwrap();
+        assert_eq!(store.session_files().await.unwrap().len(), 2);
+        store.cleanup().await.unwrap();
+        assert_eq!(store.session_files().await.unwrap().len(), 1);
+
+        store.destroy_session(loaded).await.unwrap();
+        assert!(store.load_session(cookie_value).await.unwrap().is_none());
+        store.clear_store().await.unwrap();
+        tokio::fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -0,0 +1,7 @@
+mod cookie;
+mod file;
+mod resp;
+
+pub use cookie::SignedCookieStore;
+pub use file::FileStore;
+pub use resp::RespStore;
@@ -0,0 +1,459 @@
+use async_session::{Result, Session, SessionStore};
+use async_trait::async_trait;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+/// 单个字符串回复的最大长度，超出时视为协议错误，避免按服务端返回的长度分配过大的内存
+const MAX_BULK_LEN: i64 = 64 * 1024 * 1024;
+/// 清空会话时每批删除的键数量
+const BATCH_SIZE: usize = 100;
+
+/// RESP 协议返回值
+#[derive(Debug, Clone, PartialEq)]
+enum RespValue {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<RespValue>>),
+}
+
+impl RespValue {
+    fn into_bulk(self) -> Result<Option<Vec<u8>>> {
+        match self {
+            RespValue::Bulk(value) => Ok(value),
+            value => Err(anyhow::anyhow!("unexpected resp value: {:?}", value)),
+        }
+    }
+}
+
+fn invalid_data<T: Into<String>>(msg: T) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+async fn read_line<S>(stream: &mut S) -> io::Result<String>
+where
+    S: AsyncBufReadExt + Unpin,
+{
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_value<'a, S>(
+    stream: &'a mut S,
+) -> Pin<Box<dyn Future<Output = io::Result<RespValue>> + Send + 'a>>
+where
+    S: AsyncBufReadExt + Unpin + Send,
+{
+    Box::pin(async move {
+        let line = read_line(stream).await?;
+        let (kind, content) = line.split_at_checked(1).ok_or(invalid_data("empty line"))?;
+        let length = || {
+            content
+                .parse::<i64>()
+                .map_err(|_| invalid_data(format!("invalid length: {content}")))
+        };
+        match kind {
+            "+" => Ok(RespValue::Simple(content.to_string())),
+            "-" => Ok(RespValue::Error(content.to_string())),
+            ":" => Ok(RespValue::Integer(length()?)),
+            "$" => {
+                let len = length()?;
+                if len < 0 {
+                    return Ok(RespValue::Bulk(None));
+                }
+                if len > MAX_BULK_LEN {
+                    return Err(invalid_data(format!("bulk string too large: {len}")));
+                }
+                let mut buf = vec![0; len as usize + 2];
+                stream.read_exact(&mut buf).await?;
+                buf.truncate(len as usize);
+                Ok(RespValue::Bulk(Some(buf)))
+            }
+            "*" => {
+                let len = length()?;
+                if len < 0 {
+                    return Ok(RespValue::Array(None));
+                }
+                let mut values = Vec::with_capacity(len.min(BATCH_SIZE as i64) as usize);
+                for _ in 0..len {
+                    values.push(read_value(stream).await?);
+                }
+                Ok(RespValue::Array(Some(values)))
+            }
+            _ => Err(invalid_data(format!("unknown resp type: {kind}"))),
+        }
+    })
+}
+
+/// 转义 glob 模式中的特殊字符
+fn escape_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
+fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg);
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+/// RESP 协议会话存储
+///
+/// 通过 RESP 协议连接 Redis 及其兼容服务保存会话，
+/// 会话的过期时间同步设置为键的过期时间。
+/// ```
+/// use silent::prelude::*;
+/// let mut route = Route::new_root();
+/// route.set_session_store(
+///     RespStore::new("127.0.0.1:6379")
+///         .password("secret")
+///         .database(1)
+///         .prefix("app:session:"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RespStore {
+    addr: String,
+    password: Option<String>,
+    database: Option<u32>,
+    prefix: String,
+    connection: Arc<Mutex<Option<BufStream<TcpStream>>>>,
+}
+
+impl RespStore {
+    pub fn new<T: Into<String>>(addr: T) -> Self {
+        Self {
+            addr: addr.into(),
+            password: None,
+            database: None,
+            prefix: "silent:session:".to_string(),
+            connection: Arc::new(Mutex::new(None)),
+        }
+    }
+    /// 连接后发送 AUTH 认证
+    pub fn password<T: Into<String>>(mut self, password: T) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+    /// 连接后通过 SELECT 切换数据库
+    pub fn database(mut self, database: u32) -> Self {
+        self.database = Some(database);
+        self
+    }
+    /// 会话键前缀
+    pub fn prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    fn key(&self, id: &str) -> String {
+        format!("{}{}", self.prefix, id)
+    }
+
+    async fn send(stream: &mut BufStream<TcpStream>, args: &[&[u8]]) -> io::Result<RespValue> {
+        stream.write_all(&encode_command(args)).await?;
+        stream.flush().await?;
+        read_value(stream).await
+    }
+
+    async fn connect(&self) -> Result<BufStream<TcpStream>> {
+        let mut stream = BufStream::new(TcpStream::connect(&self.addr).await?);
+        if let Some(ref password) = self.password
+            && let RespValue::Error(e) =
+                Self::send(&mut stream, &[b"AUTH", password.as_bytes()]).await?
+        {
+            return Err(anyhow::anyhow!("resp auth failed: {e}"));
+        }
+        if let Some(database) = self.database
+            && let RespValue::Error(e) =
+                Self::send(&mut stream, &[b"SELECT", database.to_string().as_bytes()]).await?
+        {
+            return Err(anyhow::anyhow!("resp select failed: {e}"));
+        }
+        Ok(stream)
+    }
+
+    async fn command(&self, args: &[&[u8]]) -> Result<RespValue> {
+        let mut connection = self.connection.lock().await;
+        // 命令进行中连接不放回共享状态，调用被取消或连接异常时连同未读取的回复一起丢弃，
+        // 下次命令重新建立连接
+        let mut stream = match connection.take() {
+            Some(stream) => stream,
+            None => self.connect().await?,
+        };
+        let value = Self::send(&mut stream, args).await?;
+        *connection = Some(stream);
+        match value {
+            RespValue::Error(e) => Err(anyhow::anyhow!("resp command failed: {e}")),
+            value => Ok(value),
+        }
+    }
+
+    /// 通过 SCAN 遍历会话键，不会像 KEYS 一样阻塞服务端
+    async fn scan_keys(&self) -> Result<Vec<Vec<u8>>> {
+        let pattern = format!("{}*", escape_pattern(&self.prefix));
+        let count = BATCH_SIZE.to_string();
+        let mut cursor = b"0".to_vec();
+        let mut keys = vec![];
+        loop {
+            let reply = self
+                .command(&[
+                    b"SCAN",
+                    &cursor,
+                    b"MATCH",
+                    pattern.as_bytes(),
+                    b"COUNT",
+                    count.as_bytes(),
+                ])
+                .await?;
+            let RespValue::Array(Some(mut reply)) = reply else {
+                return Err(anyhow::anyhow!("unexpected resp value: {reply:?}"));
+            };
+            if reply.len() != 2 {
+                return Err(anyhow::anyhow!("unexpected scan reply: {reply:?}"));
+            }
+            if let RespValue::Array(Some(batch)) = reply.pop().unwrap() {
+                keys.extend(
+                    batch
+                        .into_iter()
+                        .filter_map(|key| key.into_bulk().ok().flatten()),
+                );
+            }
+            cursor = reply.pop().unwrap().into_bulk()?.unwrap_or_default();
+            if cursor == b"0" || cursor.is_empty() {
+                return Ok(keys);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SessionStore for RespStore {
+    async fn load_session(&self, cookie_value: String) -> Result<Option<Session>> {
+        let id = Session::id_from_cookie_value(&cookie_value)?;
+        let key = self.key(&id);
+        let Some(content) = self.command(&[b"GET", key.as_bytes()]).await?.into_bulk()? else {
+            return Ok(None);
+        };
+        let session: Session = serde_json::from_slice(&content)?;
+        Ok(session.validate())
+    }
+
+    async fn store_session(&self, session: Session) -> Result<Option<String>> {
+        let key = self.key(session.id());
+        let content = serde_json::to_vec(&session)?;
+        match session.expires_in() {
+            Some(ttl) => {
+                let ttl = ttl.as_millis().max(1).to_string();
+                self.command(&[b"SET", key.as_bytes(), &content, b"PX", ttl.as_bytes()])
+                    .await?;
+            }
+            None => {
+                self.command(&[b"SET", key.as_bytes(), &content]).await?;
+            }
+        }
+        session.reset_data_changed();
+        Ok(session.into_cookie_value())
+    }
+
+    async fn destroy_session(&self, session: Session) -> Result {
+        let key = self.key(session.id());
+        self.command(&[b"DEL", key.as_bytes()]).await?;
+        Ok(())
+    }
+
+    async fn clear_store(&self) -> Result {
+        let keys = self.scan_keys().await?;
+        for batch in keys.chunks(BATCH_SIZE) {
+            let mut args: Vec<&[u8]> = vec![b"DEL"];
+            args.extend(batch.iter().map(Vec::as_slice));
+            self.command(&args).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+
+    type Data = Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>;
+
+    /// 存在该键时替身服务延迟回复 GET
+    const DELAY: &str = "delay";
+
+    /// 本地 RESP 替身服务，只实现会话存储用到的命令
+    async fn serve(stream: TcpStream, data: Data, commands: Arc<Mutex<Vec<String>>>) {
+        let mut stream = BufStream::new(stream);
+        while let Ok(RespValue::Array(Some(args))) = read_value(&mut stream).await {
+            let args: Vec<Vec<u8>> = args
+                .into_iter()
+                .filter_map(|arg| arg.into_bulk().ok().flatten())
+                .collect();
+            let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+            commands.lock().await.push(name.clone());
+            if name == "GET" && data.lock().await.contains_key(DELAY.as_bytes()) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            let mut data = data.lock().await;
+            let reply = match name.as_str() {
+                "AUTH" if args[1] == b"secret" => "+OK\r\n".to_string(),
+                "AUTH" => "-ERR invalid password\r\n".to_string(),
+                "SELECT" => "+OK\r\n".to_string(),
+                "SET" => {
+                    data.insert(args[1].clone(), args[2].clone());
+                    "+OK\r\n".to_string()
+                }
+                "GET" => match data.get(&args[1]) {
+                    Some(value) => {
+                        let mut reply = format!("${}\r\n", value.len()).into_bytes();
+                        reply.extend_from_slice(value);
+                        reply.extend_from_slice(b"\r\n");
+                        String::from_utf8(reply).unwrap()
+                    }
+                    None => "$-1\r\n".to_string(),
+                },
+                "DEL" => {
+                    let count = args[1..]
+                        .iter()
+                        .filter(|key| data.remove(*key).is_some())
+                        .count();
+                    format!(":{count}\r\n")
+                }
+                "SCAN" => {
+                    // 每次只返回一个键，游标为下一个键的序号
+                    let cursor: usize = String::from_utf8_lossy(&args[1]).parse().unwrap();
+                    let prefix = &args[3][..args[3].len() - 1];
+                    let mut keys: Vec<&Vec<u8>> =
+                        data.keys().filter(|key| key.starts_with(prefix)).collect();
+                    keys.sort();
+                    let next = if cursor + 1 < keys.len() {
+                        cursor + 1
+                    } else {
+                        0
+                    };
+                    let next = next.to_string();
+                    let page: Vec<&[u8]> = keys
+                        .get(cursor)
+                        .map(|key| key.as_slice())
+                        .into_iter()
+                        .collect();
+                    let mut reply = format!("*2\r\n${}\r\n{next}\r\n", next.len()).into_bytes();
+                    reply.extend(encode_command(&page));
+                    String::from_utf8(reply).unwrap()
+                }
+                _ => "-ERR unknown command\r\n".to_string(),
+            };
+            drop(data);
+            if stream.write_all(reply.as_bytes()).await.is_err() || stream.flush().await.is_err() {
+                break;
+            }
+        }
+    }
+
+    async fn start_server() -> (String, Data, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let data = Data::default();
+        let commands = Arc::new(Mutex::new(vec![]));
+        let (server_data, server_commands) = (data.clone(), commands.clone());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, server_data.clone(), server_commands.clone()));
+            }
+        });
+        (addr, data, commands)
+    }
+
+    #[tokio::test]
+    async fn test_resp_store() {
+        let (addr, data, commands) = start_server().await;
+        let store = RespStore::new(addr).password("secret").database(2);
+        let mut session = Session::new();
+        session.insert("user", "silent").unwrap();
+        session.expire_in(Duration::from_secs(60));
+        let key = format!("silent:session:{}", session.id());
+        let cookie_value = store.store_session(session).await.unwrap().unwrap();
+        assert!(data.lock().await.contains_key(key.as_bytes()));
+        let loaded = store
+            .load_session(cookie_value.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.get::<String>("user").unwrap(), "silent");
+        assert_eq!(
+            commands.lock().await[..3],
+            ["AUTH".to_string(), "SELECT".to_string(), "SET".to_string()]
+        );
+
+        store.destroy_session(loaded).await.unwrap();
+        assert!(store.load_session(cookie_value).await.unwrap().is_none());
+
+        store.store_session(Session::new()).await.unwrap();
+        store.store_session(Session::new()).await.unwrap();
+        data.lock()
+            .await
+            .insert(b"other".to_vec(), b"value".to_vec());
+        store.clear_store().await.unwrap();
+        assert_eq!(data.lock().await.len(), 1);
+        assert!(!commands.lock().await.contains(&"KEYS".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_resp_store_cancelled() {
+        let (addr, data, _) = start_server().await;
+        let store = RespStore::new(addr);
+        let first = Session::new();
+        let first_id = first.id().to_string();
+        let first = store.store_session(first).await.unwrap().unwrap();
+        let second = Session::new();
+        let second_id = second.id().to_string();
+        let second = store.store_session(second).await.unwrap().unwrap();
+
+        // 命令已发送但回复未到达时取消
+        data.lock().await.insert(DELAY.as_bytes().to_vec(), vec![]);
+        let cancelled = tokio::time::timeout(Duration::from_millis(20), store.load_session(first));
+        assert!(cancelled.await.is_err());
+        data.lock().await.remove(DELAY.as_bytes());
+
+        let loaded = store.load_session(second).await.unwrap().unwrap();
+        assert_eq!(loaded.id(), second_id);
+        assert_ne!(loaded.id(), first_id);
+    }
+
+    #[tokio::test]
+    async fn test_resp_value_limits() {
+        let mut reply = BufStream::new(std::io::Cursor::new(b"$1073741824\r\n".to_vec()));
+        let error = read_value(&mut reply).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_resp_store_auth_failed() {
+        let (addr, _, _) = start_server().await;
+        let store = RespStore::new(addr).password("wrong");
+        assert!(store.store_session(Session::new()).await.is_err());
+    }
+}
@@ -0,0 +1,523 @@
+//! 配置加载
+//!
+//! 按默认值、配置文件、`SILENT_*` 环境变量的顺序合并配置，并将类型化的配置段注册到 [`Configs`] 中，
+//! 处理函数通过 `req.get_config::<T>()` 读取。
+mod reload;
+mod sections;
+
+pub use reload::ReloadableSettings;
+pub use sections::{LogSettings, ServerSettings, TlsSettings};
+
+use crate::Configs;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 配置加载错误
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    /// 读取配置文件失败
+    #[error("failed to read settings file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// 配置文件格式错误
+    #[error("failed to parse settings file {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    /// 不支持的配置文件格式
+    #[error("unsupported settings file format: {0}")]
+    UnsupportedFormat(PathBuf),
+    /// 配置项校验失败，`key` 为出错配置项的路径，例如 `server.bind[0]`
+    #[error("invalid setting `{key}`: {message}")]
+    Invalid { key: String, message: String },
+}
+
+type SectionLoader = Arc<dyn Fn(&Value, &mut Configs) -> Result<(), SettingsError> + Send + Sync>;
+
+/// 配置加载器
+///
+/// 后加载的来源覆盖先加载的来源：默认值 < 配置文件 < 环境变量。
+/// 环境变量名去掉前缀后以 `__` 分隔层级，例如 `SILENT_SERVER__SHUTDOWN_TIMEOUT=10s`
+/// 对应 `server.shutdown_timeout`；变量值按字符串读取，目标类型不是字符串时再按 JSON 解析。
+/// ```no_run
+/// use serde::Deserialize;
+/// use silent::prelude::*;
+///
+/// #[derive(Clone, Deserialize)]
+/// struct AppConfig {
+///     name: String,
+/// }
+///
+/// let settings = SettingsLoader::new()
+///     .optional_file("config.toml")
+///     .section::<AppConfig>("app")
+///     .load()
+///     .unwrap();
+/// let route = Route::new("").get(|req: Request| async move {
+///     Ok(req.get_config::<AppConfig>()?.name.clone())
+/// });
+/// Server::new().with_settings(&settings).run(route);
+/// ```
+#[derive(Clone)]
+pub struct SettingsLoader {
+    defaults: Value,
+    files: Vec<(PathBuf, bool)>,
+    env_prefix: Option<String>,
+    env_source: Option<Vec<(String, String)>>,
+    sections: Vec<SectionLoader>,
+}
+
+impl Default for SettingsLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SettingsLoader {
+    /// 创建加载器，默认读取 `SILENT_` 前缀的环境变量
+    pub fn new() -> Self {
+        Self {
+            defaults: Value::Object(Map::new()),
+            files: vec![],
+            env_prefix: Some("SILENT".to_string()),
+            env_source: None,
+            sections: vec![],
+        }
+    }
+
+    /// 设置默认值，需要序列化为 map
+    pub fn defaults<T: Serialize>(mut self, defaults: T) -> Self {
+        let defaults = serde_json::to_value(defaults).expect("failed to serialize defaults");
+        merge(&mut self.defaults, defaults);
+        self
+    }
+
+    /// 添加配置文件，根据扩展名识别 TOML、YAML 或 JSON，文件不存在时加载失败
+    pub fn file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.files.push((path.as_ref().to_path_buf(), true));
+        self
+    }
+
+    /// 添加可选的配置文件，文件不存在时忽略
+    pub fn optional_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.files.push((path.as_ref().to_path_buf(), false));
+        self
+    }
+
+    /// 设置环境变量前缀，默认为 `SILENT`
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// 不读取环境变量
+    pub fn without_env(mut self) -> Self {
+        self.env_prefix = None;
+        self
+    }
+
+    /// 使用给定的变量代替进程的环境变量，仍按前缀筛选
+    pub fn env_source<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env_source = Some(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// 注册类型化的配置段，加载后放入 [`Configs`]
+    ///
+    /// 配置段不存在时按空 map 反序列化，可以配合 `#[serde(default)]` 使用。
+    pub fn section<T>(mut self, key: &str) -> Self
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let key = key.to_string();
+        self.sections.push(Arc::new(move |value, configs| {
+            configs.insert(deserialize::<T>(value, &key)?);
+            Ok(())
+        }));
+        self
+    }
+
+    /// 配置文件路径，用于监听文件变化
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// 合并所有来源并校验配置
+    pub fn load(&self) -> Result<Settings, SettingsError> {
+        let mut value = self.defaults.clone();
+        for (path, required) in self.files.iter() {
+            if let Some(file) = read_file(path, *required)? {
+                merge(&mut value, file);
+            }
+        }
+        if let Some(prefix) = &self.env_prefix {
+            let vars = match &self.env_source {
+                Some(vars) => vars.clone(),
+                None => std::env::vars().collect(),
+            };
+            merge(&mut value, env_value(prefix, vars));
+        }
+
+        let server = deserialize::<ServerSettings>(&value, "server")?;
+        server.validate()?;
+        let log = deserialize::<LogSettings>(&value, "log")?;
+        let mut configs = Configs::new();
+        configs.insert(server.clone());
+        configs.insert(log.clone());
+        for section in self.sections.iter() {
+            section(&value, &mut configs)?;
+        }
+        Ok(Settings {
+            value,
+            server,
+            log,
+            configs,
+        })
+    }
+}
+
+/// 加载完成的配置
+#[derive(Clone)]
+pub struct Settings {
+    value: Value,
+    server: ServerSettings,
+    log: LogSettings,
+    configs: Configs,
+}
+
+impl Settings {
+    /// 服务器配置，对应 `server` 段
+    pub fn server(&self) -> &ServerSettings {
+        &self.server
+    }
+    /// 日志配置，对应 `log` 段
+    pub fn log(&self) -> &LogSettings {
+        &self.log
+    }
+    /// 包含服务器、日志与已注册配置段的 [`Configs`]
+    pub fn configs(&self) -> Configs {
+        self.configs.clone()
+    }
+    /// 按路径读取任意配置段，例如 `settings.get::<u64>("app.page_size")`
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<T, SettingsError> {
+        deserialize(&self.value, key)
+    }
+}
+
+fn read_file(path: &Path, required: bool) -> Result<Option<Value>, SettingsError> {
+    type Parser = fn(&str) -> Result<Value, String>;
+    let parse: Parser = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => |content| toml::from_str(content).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => |content| serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        Some("json") => |content| serde_json::from_str(content).map_err(|e| e.to_string()),
+        _ => return Err(SettingsError::UnsupportedFormat(path.to_path_buf())),
+    };
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(SettingsError::Io {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    };
+    parse(&content)
+        .map(Some)
+        .map_err(|message| SettingsError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+}
+
+/// 将 `PREFIX_A__B=value` 形式的环境变量转换为嵌套的 map
+fn env_value(prefix: &str, vars: impl IntoIterator<Item = (String, String)>) -> Value {
+    let prefix = format!("{prefix}_");
+    let mut root = Value::Object(Map::new());
+    for (name, raw) in vars {
+        let Some(name) = name.strip_prefix(&prefix) else {
+            continue;
+        };
+        let value = name
+            .rsplit("__")
+            .fold(Value::String(raw), |value, segment| {
+                let mut map = Map::new();
+                map.insert(segment.to_lowercase(), value);
+                Value::Object(map)
+            });
+        merge(&mut root, value);
+    }
+    root
+}
+
+/// 深度合并，`other` 中的值覆盖 `base`
+fn merge(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Object(base), Value::Object(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, other) => *base = other,
+    }
+}
+
+/// 反序列化 `key` 路径下的配置，错误信息包含完整的配置项路径
+///
+/// 环境变量的值均为字符串，出错位置的字符串可以按 JSON 解析为其他类型时，替换后重试。
+fn deserialize<T: DeserializeOwned>(value: &Value, key: &str) -> Result<T, SettingsError> {
+    let section = key
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |value, segment| value.get(segment));
+    let mut section = section.cloned().unwrap_or(Value::Object(Map::new()));
+    let mut previous: Option<serde_path_to_error::Error<serde_json::Error>> = None;
+    loop {
+        let e = match serde_path_to_error::deserialize(&section) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        // 替换后在同一位置仍然出错时，报告替换前的错误
+        if let Some(previous) = previous.take()
+            && previous.path().to_string() == e.path().to_string()
+        {
+            return Err(invalid(key, previous));
+        }
+        if !coerce(&mut section, e.path()) {
+            return Err(invalid(key, e));
+        }
+        previous = Some(e);
+    }
+}
+
+/// 将 `path` 处的字符串按 JSON 解析为非字符串的值
+fn coerce(value: &mut Value, path: &serde_path_to_error::Path) -> bool {
+    let mut value = Some(value);
+    for segment in path.iter() {
+        value = match segment {
+            serde_path_to_error::Segment::Seq { index } => value.and_then(|v| v.get_mut(*index)),
+            serde_path_to_error::Segment::Map { key } => value.and_then(|v| v.get_mut(key)),
+            _ => None,
+        };
+    }
+    let Some(value) = value else {
+        return false;
+    };
+    let Value::String(raw) = value else {
+        return false;
+    };
+    match serde_json::from_str(raw) {
+        Ok(Value::String(_)) | Err(_) => false,
+        Ok(parsed) => {
+            *value = parsed;
+            true
+        }
+    }
+}
+
+/// 转换为包含完整配置项路径的错误
+fn invalid(key: &str, e: serde_path_to_error::Error<serde_json::Error>) -> SettingsError {
+    let message = e.inner().to_string();
+    let mut segments: Vec<String> = key
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+    for segment in e.path().iter() {
+        match segment {
+            serde_path_to_error::Segment::Seq { index } => match segments.last_mut() {
+                Some(last) => last.push_str(&format!("[{index}]")),
+                None => segments.push(format!("[{index}]")),
+            },
+            segment => segments.push(segment.to_string()),
+        }
+    }
+    // 缺失字段的错误路径只到所在的结构体
+    if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+    {
+        segments.push(field.to_string());
+    }
+    SettingsError::Invalid {
+        key: segments.join("."),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        #[serde(default)]
+        page_size: u32,
+    }
+
+    fn write(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("silent-{}-{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_merge_sources() {
+        let path = write(
+            "settings.toml",
+            r#"
+            [server]
+            bind = ["127.0.0.1:8000"]
+            shutdown_timeout = "5s"
+
+            [app]
+            name = "file"
+            page_size = 20
+            "#,
+        );
+        let settings = SettingsLoader::new()
+            .defaults(serde_json::json!({"app": {"name": "default", "page_size": 10}, "log": {"level": "warn"}}))
+            .file(&path)
+            .section::<AppConfig>("app")
+            .env_source([
+                ("SILENT_APP__NAME", "env"),
+                ("SILENT_SERVER__IDLE_TIMEOUT", "250ms"),
+                ("SILENT_SERVER__MAX_CONNECTIONS", "100"),
+                ("OTHER_APP__NAME", "ignored"),
+            ])
+            .load()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let app = settings.configs().get::<AppConfig>().cloned().unwrap();
+        assert_eq!(
+            app,
+            AppConfig {
+                name: "env".to_string(),
+                page_size: 20
+            }
+        );
+        let server = settings.server();
+        assert_eq!(server.bind, vec!["127.0.0.1:8000".parse().unwrap()]);
+        assert_eq!(server.shutdown_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(server.idle_timeout, Some(Duration::from_millis(250)));
+        assert_eq!(server.max_connections, Some(100));
+        assert_eq!(settings.log().level, tracing::Level::WARN);
+        assert_eq!(settings.get::<u32>("app.page_size").unwrap(), 20);
+    }
+
+    #[test]
+    fn test_env_types() {
+        #[derive(Debug, Clone, Deserialize, PartialEq)]
+        struct Env {
+            name: String,
+            flag: String,
+            version: String,
+            page_size: u32,
+            enabled: bool,
+            tags: Vec<String>,
+            limit: Option<u64>,
+        }
+        let settings = SettingsLoader::new()
+            .env_source([
+                ("SILENT_ENV__NAME", "12345"),
+                ("SILENT_ENV__FLAG", "true"),
+                ("SILENT_ENV__VERSION", "1.0"),
+                ("SILENT_ENV__PAGE_SIZE", "20"),
+                ("SILENT_ENV__ENABLED", "true"),
+                ("SILENT_ENV__TAGS", r#"["a", "1"]"#),
+                ("SILENT_ENV__LIMIT", "5"),
+            ])
+            .section::<Env>("env")
+            .load()
+            .unwrap();
+        assert_eq!(
+            settings.configs().get::<Env>().cloned().unwrap(),
+            Env {
+                name: "12345".to_string(),
+                flag: "true".to_string(),
+                version: "1.0".to_string(),
+                page_size: 20,
+                enabled: true,
+                tags: vec!["a".to_string(), "1".to_string()],
+                limit: Some(5),
+            }
+        );
+    }
+
+    #[test]
+    fn test_file_formats() {
+        let yaml = write("settings.yaml", "app:\n  name: yaml\n");
+        let json = write("settings.json", r#"{"app": {"page_size": 5}}"#);
+        let settings = SettingsLoader::new()
+            .without_env()
+            .file(&yaml)
+            .file(&json)
+            .optional_file("missing.toml")
+            .section::<AppConfig>("app")
+            .load()
+            .unwrap();
+        std::fs::remove_file(&yaml).unwrap();
+        std::fs::remove_file(&json).unwrap();
+        let app = settings.configs().get::<AppConfig>().cloned().unwrap();
+        assert_eq!(app.name, "yaml");
+        assert_eq!(app.page_size, 5);
+
+        let result = SettingsLoader::new().file("missing.toml").load();
+        assert!(matches!(result, Err(SettingsError::Io { .. })));
+        let result = SettingsLoader::new().file("settings.ini").load();
+        assert!(matches!(result, Err(SettingsError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let invalid_key = |vars: &[(&str, &str)]| match SettingsLoader::new()
+            .env_source(vars.iter().copied())
+            .section::<AppConfig>("app")
+            .load()
+        {
+            Err(SettingsError::Invalid { key, .. }) => key,
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("settings should be invalid"),
+        };
+        assert_eq!(
+            invalid_key(&[("SILENT_SERVER__BIND", r#"["127.0.0.1:80", "bad"]"#)]),
+            "server.bind[1]"
+        );
+        assert_eq!(
+            invalid_key(&[("SILENT_SERVER__SHUTDOWN_TIMEOUT", "soon")]),
+            "server.shutdown_timeout"
+        );
+        assert_eq!(invalid_key(&[("SILENT_LOG__LEVEL", "loud")]), "log.level");
+        assert_eq!(invalid_key(&[("SILENT_APP__PAGE_SIZE", "1")]), "app.name");
+        assert_eq!(
+            invalid_key(&[("SILENT_APP__NAME", "x"), ("SILENT_APP__PAGE_SIZE", "-1")]),
+            "app.page_size"
+        );
+        assert_eq!(
+            invalid_key(&[("SILENT_APP__NAME", "x"), ("SILENT_SERVER__PORT", "80")]),
+            "server.port"
+        );
+    }
+}
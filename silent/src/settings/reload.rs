@@ -0,0 +1,226 @@
+use super::{Settings, SettingsError, SettingsLoader};
+use crate::{Handler, LiveConfig, MiddleWareHandler, Next, Request, Response, Result};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 可热重载的配置
+///
+/// 作为中间件使用时，将当前配置中的配置段放入请求的 [`Configs`](crate::Configs)，
+/// 处理函数通过 `req.get_config::<T>()` 读取到最新的值；重新加载失败时保留原配置。
+/// ```no_run
+/// use silent::prelude::*;
+/// use std::time::Duration;
+/// # async fn run() -> std::result::Result<(), SettingsError> {
+/// let settings = ReloadableSettings::new(SettingsLoader::new().file("config.toml"))?;
+/// settings.watch(Duration::from_secs(5));
+/// let mut updates = settings.subscribe();
+/// tokio::spawn(async move {
+///     while updates.changed().await.is_ok() {
+///         println!("log level: {}", updates.borrow().log().level);
+///     }
+/// });
+/// let route = Route::new("").get(|_req| async { Ok("hello") });
+/// Server::new().with_reloadable_settings(settings).serve(route).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReloadableSettings {
+    inner: Arc<ReloadableInner>,
+}
+
+struct ReloadableInner {
+    loader: SettingsLoader,
+    current: LiveConfig<Settings>,
+    modified: Mutex<Vec<Option<SystemTime>>>,
+}
+
+impl ReloadableInner {
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        self.loader
+            .paths()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+impl ReloadableSettings {
+    pub fn new(loader: SettingsLoader) -> std::result::Result<Self, SettingsError> {
+        let settings = loader.load()?;
+        let inner = ReloadableInner {
+            loader,
+            current: LiveConfig::new(settings),
+            modified: Mutex::new(vec![]),
+        };
+        *inner.modified.lock().unwrap() = inner.modified();
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// 当前的配置
+    pub fn settings(&self) -> Arc<Settings> {
+        self.inner.current.load()
+    }
+
+    /// 以 [`LiveConfig`] 形式共享当前配置
+    pub fn live(&self) -> LiveConfig<Settings> {
+        self.inner.current.clone()
+    }
+
+    /// 订阅配置更新
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Settings>> {
+        self.inner.current.subscribe()
+    }
+
+    /// 立即重新加载配置
+    pub fn reload(&self) -> std::result::Result<(), SettingsError> {
+        let modified = self.inner.modified();
+        let settings = self.inner.loader.load()?;
+        self.inner.current.store(settings);
+        *self.inner.modified.lock().unwrap() = modified;
+        tracing::info!("settings reloaded");
+        Ok(())
+    }
+
+    /// 定期检查配置文件的修改时间，变化时自动重新加载
+    ///
+    /// 需要在 tokio 运行时中调用，所有句柄被释放后检查任务自动退出。
+    pub fn watch(&self, interval: Duration) -> JoinHandle<()> {
+        let inner = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(inner) = Weak::upgrade(&inner) else {
+                    break;
+                };
+                if inner.modified() == *inner.modified.lock().unwrap() {
+                    continue;
+                }
+                if let Err(e) = (Self { inner }).reload() {
+                    tracing::error!("reload settings failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl MiddleWareHandler for ReloadableSettings {
+    async fn handle(&self, mut req: Request, next: &Next) -> Result<Response> {
+        req.configs_mut().extend(self.settings().configs());
+        next.call(req).await
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use serde::Deserialize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Clone, Deserialize)]
+    struct AppConfig {
+        name: String,
+    }
+
+    async fn get(addr: std::net::SocketAddr) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_reloadable_settings() {
+        let path = std::env::temp_dir().join(format!("silent-reload-{}.toml", std::process::id()));
+        let write = |name: &str| {
+            let content = format!(
+                "[server]\nbind = \"127.0.0.1:0\"\nidle_timeout = 30\n\n[app]\nname = \"{name}\"\n"
+            );
+            std::fs::write(&path, content).unwrap();
+        };
+        write("v1");
+        let settings = ReloadableSettings::new(
+            SettingsLoader::new()
+                .without_env()
+                .file(&path)
+                .section::<AppConfig>("app"),
+        )
+        .unwrap();
+        let mut updates = settings.subscribe();
+        let route = Route::new("")
+            .get(|req: Request| async move { Ok(req.get_config::<AppConfig>()?.name.clone()) });
+        let handle = Server::new()
+            .with_reloadable_settings(settings.clone())
+            .start(route)
+            .unwrap();
+        handle.ready().await;
+        assert_eq!(handle.local_addrs().len(), 1);
+        let addr = handle.local_addrs()[0].tcp_addr().unwrap();
+        assert!(get(addr).await.ends_with("v1"));
+
+        write("v2");
+        settings.reload().unwrap();
+        assert!(updates.has_changed().unwrap());
+        assert_eq!(
+            updates
+                .borrow_and_update()
+                .configs()
+                .get::<AppConfig>()
+                .unwrap()
+                .name,
+            "v2"
+        );
+        assert!(get(addr).await.ends_with("v2"));
+
+        // 加载失败时保留原配置
+        std::fs::write(&path, "[app]\nname = 1\n").unwrap();
+        assert!(settings.reload().is_err());
+        assert!(get(addr).await.ends_with("v2"));
+
+        handle.shutdown();
+        handle.join().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_tls_settings() {
+        let certs = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/certs");
+        let settings = SettingsLoader::new()
+            .without_env()
+            .defaults(serde_json::json!({"server": {
+                "bind": ["127.0.0.1:0"],
+                "tls": {"cert": certs.join("localhost.pem"), "key": certs.join("localhost-key.pem")},
+            }}))
+            .load()
+            .unwrap();
+        let handle = Server::new()
+            .with_settings(&settings)
+            .start(Route::new(""))
+            .unwrap();
+        assert!(matches!(
+            handle.local_addrs()[0],
+            crate::SocketAddr::TlsTcp(_)
+        ));
+        handle.shutdown();
+        handle.join().await.unwrap();
+
+        let result = SettingsLoader::new()
+            .without_env()
+            .defaults(serde_json::json!({"server": {"tls": {"cert": "missing.pem", "key": "missing.pem"}}}))
+            .load();
+        assert!(
+            matches!(result, Err(SettingsError::Invalid { key, .. }) if key == "server.tls.cert")
+        );
+    }
+}
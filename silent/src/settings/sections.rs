@@ -0,0 +1,176 @@
+use super::SettingsError;
+use serde::{Deserialize, Deserializer, de};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 服务器配置，对应 `server` 段
+///
+/// ```toml
+/// [server]
+/// bind = ["0.0.0.0:8000"]
+/// shutdown_timeout = "30s"
+/// idle_timeout = "60s"
+/// max_connections = 10000
+///
+/// [server.tls]
+/// cert = "certs/server.pem"
+/// key = "certs/server-key.pem"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    /// 监听地址，可以是单个地址或地址列表
+    #[serde(deserialize_with = "one_or_many")]
+    pub bind: Vec<SocketAddr>,
+    /// TLS 证书配置，设置后所有监听地址均使用 TLS
+    pub tls: Option<TlsSettings>,
+    /// 优雅关闭等待时间
+    #[serde(deserialize_with = "duration")]
+    pub shutdown_timeout: Option<Duration>,
+    /// 空闲连接超时时间
+    #[serde(deserialize_with = "duration")]
+    pub idle_timeout: Option<Duration>,
+    /// 最大连接数
+    pub max_connections: Option<usize>,
+    /// 单个 IP 的最大连接数
+    pub max_connections_per_ip: Option<usize>,
+}
+
+/// TLS 证书路径，对应 `server.tls` 段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsSettings {
+    /// 证书链文件（PEM）
+    pub cert: PathBuf,
+    /// 私钥文件（PEM）
+    pub key: PathBuf,
+    /// 用于校验客户端证书的 CA 文件，设置后要求客户端提供证书
+    #[serde(default)]
+    pub client_ca: Option<PathBuf>,
+}
+
+impl ServerSettings {
+    pub(crate) fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(tls) = &self.tls {
+            if !cfg!(feature = "tls") {
+                return Err(SettingsError::Invalid {
+                    key: "server.tls".to_string(),
+                    message: "the `tls` feature is not enabled".to_string(),
+                });
+            }
+            let files = [("cert", Some(&tls.cert)), ("key", Some(&tls.key))];
+            let client_ca = [("client_ca", tls.client_ca.as_ref())];
+            for (name, path) in files.into_iter().chain(client_ca) {
+                if let Some(path) = path
+                    && !path.is_file()
+                {
+                    return Err(SettingsError::Invalid {
+                        key: format!("server.tls.{name}"),
+                        message: format!("file not found: {}", path.display()),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 日志配置，对应 `log` 段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSettings {
+    /// 日志级别，默认为 `info`
+    #[serde(deserialize_with = "level")]
+    pub level: tracing::Level,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: tracing::Level::INFO,
+        }
+    }
+}
+
+impl LogSettings {
+    /// 按配置的级别初始化全局日志，已经初始化时忽略
+    pub fn init_logger(&self) {
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(self.level)
+            .try_init();
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<SocketAddr>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct AddrsVisitor;
+
+    impl<'de> de::Visitor<'de> for AddrsVisitor {
+        type Value = Vec<SocketAddr>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an address or a list of addresses")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            value.parse().map(|addr| vec![addr]).map_err(E::custom)
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+            Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    deserializer.deserialize_any(AddrsVisitor)
+}
+
+fn duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Seconds(f64),
+        Text(String),
+    }
+    let duration = match Option::<Raw>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Raw::Seconds(secs)) => Duration::try_from_secs_f64(secs).ok(),
+        Some(Raw::Text(text)) => parse_duration(&text),
+    };
+    duration.map(Some).ok_or_else(|| {
+        de::Error::custom("expected seconds or a duration such as `500ms`, `30s`, `5m`, `1h`")
+    })
+}
+
+/// 解析带单位的时长，例如 `500ms`、`30s`、`5m`、`1h`
+fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, unit) = text.split_at(split);
+    let value: f64 = value.parse().ok()?;
+    let secs = match unit.trim() {
+        "ms" => value / 1000.0,
+        "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+fn level<'de, D>(deserializer: D) -> Result<tracing::Level, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let level = String::deserialize(deserializer)?;
+    level.parse().map_err(|_| {
+        de::Error::custom(format!(
+            "unknown log level `{level}`, expected one of trace, debug, info, warn, error"
+        ))
+    })
+}
@@ -63,6 +63,34 @@ impl SSEEvent {
         self.id = Some(id.into());
         self
     }
+
+    /// Get Server-sent event data
+    pub fn get_data(&self) -> Option<&str> {
+        match &self.data {
+            Some(DataType::Text(data)) | Some(DataType::Json(data)) => Some(data),
+            None => None,
+        }
+    }
+
+    /// Get Server-sent event comment
+    pub fn get_comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Get Server-sent event name
+    pub fn get_event(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+
+    /// Get Server-sent event retry duration
+    pub fn get_retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Get Server-sent event id
+    pub fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
 }
 
 impl fmt::Display for SSEEvent {
@@ -1,3 +1,4 @@
+use crate::middlewares::CspNonce;
 use crate::{Handler, MiddleWareHandler, Next, Request, Response, Result, SilentError, StatusCode};
 use async_trait::async_trait;
 use serde::Serialize;
@@ -42,19 +43,22 @@ impl TemplateMiddleware {
 #[async_trait]
 impl MiddleWareHandler for TemplateMiddleware {
     async fn handle(&self, req: Request, next: &Next) -> Result<Response> {
+        let nonce = req.extensions().get::<CspNonce>().cloned();
         let mut res = next.call(req).await?;
+        let nonce = nonce.or_else(|| res.extensions.get::<CspNonce>().cloned());
         let template = res.extensions.get::<TemplateResponse>().unwrap();
+        let mut context = Context::from_serialize(&template.data).map_err(|e| {
+            SilentError::business_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to render template: {e}"),
+            )
+        })?;
+        if let Some(nonce) = nonce {
+            context.insert("csp_nonce", nonce.as_str());
+        }
         res.set_body(
             self.template
-                .render(
-                    &template.template,
-                    &Context::from_serialize(&template.data).map_err(|e| {
-                        SilentError::business_error(
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            format!("Failed to render template: {e}"),
-                        )
-                    })?,
-                )
+                .render(&template.template, &context)
                 .map_err(|e| {
                     SilentError::business_error(
                         StatusCode::INTERNAL_SERVER_ERROR,
@@ -116,4 +120,34 @@ mod tests {
             &Bytes::from("<h1>templates</h1>")
         );
     }
+
+    #[tokio::test]
+    async fn templates_csp_nonce_test() {
+        use crate::middlewares::SecurityHeaders;
+        let mut tera = Tera::default();
+        tera.add_raw_template("nonce.html", "<script nonce=\"{{ csp_nonce }}\"></script>")
+            .unwrap();
+        let temp_middleware = TemplateMiddleware {
+            template: Arc::new(tera),
+        };
+        let route = Route::default()
+            .get(|_req| async {
+                Ok(TemplateResponse::from((
+                    "nonce.html".to_string(),
+                    serde_json::json!({}),
+                )))
+            })
+            .hook(SecurityHeaders::none().content_security_policy("script-src 'nonce-{nonce}'"))
+            .hook(temp_middleware);
+        let mut routes = Route::new_root();
+        routes.push(route);
+        let mut req = Request::empty();
+        req.set_remote("127.0.0.1:8080".parse().unwrap());
+        let mut res = routes.call(req).await.unwrap();
+        let nonce = res.extensions().get::<CspNonce>().cloned().unwrap();
+        assert_eq!(
+            res.body.frame().await.unwrap().unwrap().data_ref().unwrap(),
+            &Bytes::from(format!("<script nonce=\"{nonce}\"></script>"))
+        );
+    }
 }
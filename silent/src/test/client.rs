@@ -0,0 +1,408 @@
+#[cfg(feature = "cookie")]
+use super::response::set_cookies;
+use super::{MultipartForm, TestResponse};
+use crate::core::adapt::{RequestAdapt, ResponseAdapt};
+use crate::core::req_body::ReqBody;
+use crate::core::res_body::ResBody;
+use crate::core::socket_addr::SocketAddr;
+use crate::route::{Route, RouteService};
+#[cfg(feature = "scheduler")]
+use crate::scheduler::middleware::SchedulerMiddleware;
+use crate::{Configs, Handler, Method};
+use bytes::Bytes;
+#[cfg(feature = "cookie")]
+use cookie::{Cookie, CookieJar};
+#[cfg(feature = "cookie")]
+use http::HeaderMap;
+use http::{HeaderName, HeaderValue, header};
+use http_body_util::BodyExt;
+use serde::Serialize;
+#[cfg(feature = "cookie")]
+use std::sync::{Arc, Mutex};
+
+/// 在进程内直接调用路由的测试客户端
+///
+/// 与 [`Server`](crate::prelude::Server) 一样为路由挂载会话、cookie 等中间件，
+/// 请求默认带有远程地址 `127.0.0.1:12345`，启用 `cookie` 特性时在请求之间保存 cookie。
+/// ```
+/// use silent::prelude::*;
+/// # tokio_test();
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn tokio_test() {
+/// let route = Route::new("users/<id:i64>").get(|req: Request| async move {
+///     let id: i64 = req.get_path_params("id")?;
+///     Ok(serde_json::json!({ "id": id }))
+/// });
+/// let client = TestClient::new(route);
+/// let res = client.get("/users/1").send().await;
+/// assert_eq!(res.status(), StatusCode::OK);
+/// assert_eq!(res.json::<serde_json::Value>().unwrap()["id"], 1);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TestClient {
+    route: Route,
+    remote: SocketAddr,
+    #[cfg(feature = "cookie")]
+    jar: Arc<Mutex<CookieJar>>,
+}
+
+impl TestClient {
+    /// 使用路由创建测试客户端
+    pub fn new(route: impl RouteService) -> Self {
+        #[allow(unused_mut)]
+        let mut route = route.route();
+        #[cfg(feature = "session")]
+        route.check_session();
+        #[cfg(feature = "cookie")]
+        route.check_cookie();
+        #[cfg(feature = "scheduler")]
+        route.hook_first(SchedulerMiddleware::new());
+        Self {
+            route,
+            remote: std::net::SocketAddr::from(([127, 0, 0, 1], 12345)).into(),
+            #[cfg(feature = "cookie")]
+            jar: Arc::default(),
+        }
+    }
+    /// 设置路由配置
+    pub fn with_configs(mut self, configs: Configs) -> Self {
+        self.route.set_configs(Some(configs));
+        self
+    }
+    /// 设置请求的远程地址
+    pub fn with_remote(mut self, remote: impl Into<SocketAddr>) -> Self {
+        self.remote = remote.into();
+        self
+    }
+    /// 当前保存的 cookie
+    #[cfg(feature = "cookie")]
+    pub fn cookies(&self) -> CookieJar {
+        self.jar.lock().unwrap().clone()
+    }
+    /// 清空保存的 cookie
+    #[cfg(feature = "cookie")]
+    pub fn clear_cookies(&self) {
+        *self.jar.lock().unwrap() = CookieJar::new();
+    }
+    /// 构造指定方法的请求
+    pub fn request(&self, method: Method, uri: &str) -> TestRequest<'_> {
+        TestRequest {
+            client: self,
+            builder: http::Request::builder().method(method).uri(uri),
+            body: Bytes::new(),
+        }
+    }
+    /// 构造 GET 请求
+    pub fn get(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::GET, uri)
+    }
+    /// 构造 POST 请求
+    pub fn post(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::POST, uri)
+    }
+    /// 构造 PUT 请求
+    pub fn put(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::PUT, uri)
+    }
+    /// 构造 PATCH 请求
+    pub fn patch(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::PATCH, uri)
+    }
+    /// 构造 DELETE 请求
+    pub fn delete(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::DELETE, uri)
+    }
+    /// 构造 HEAD 请求
+    pub fn head(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::HEAD, uri)
+    }
+    /// 构造 OPTIONS 请求
+    pub fn options(&self, uri: &str) -> TestRequest<'_> {
+        self.request(Method::OPTIONS, uri)
+    }
+
+    #[cfg(feature = "cookie")]
+    fn cookie_header(&self) -> Option<HeaderValue> {
+        let jar = self.jar.lock().unwrap();
+        let cookies = jar
+            .iter()
+            .map(|cookie| cookie.encoded().stripped().to_string())
+            .collect::<Vec<_>>();
+        if cookies.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&cookies.join("; ")).ok()
+    }
+
+    /// 与服务器一样处理请求，处理器返回的错误转换为响应
+    pub(super) async fn dispatch(&self, req: http::Request<ReqBody>) -> http::Response<ResBody> {
+        let mut req = req.tran_to_request();
+        req.set_remote(self.remote.clone());
+        let res = self.route.call(req).await.unwrap_or_else(Into::into);
+        http::Response::tran_from_response(res)
+    }
+
+    #[cfg(feature = "cookie")]
+    pub(super) fn store_cookies(&self, headers: &HeaderMap) {
+        let now = cookie::time::OffsetDateTime::now_utc();
+        let mut jar = self.jar.lock().unwrap();
+        for cookie in set_cookies(headers) {
+            let expired = cookie
+                .max_age()
+                .is_some_and(|age| age <= cookie::time::Duration::ZERO)
+                || cookie.expires_datetime().is_some_and(|at| at <= now);
+            if expired {
+                jar.remove(Cookie::from(cookie.name().to_string()));
+            } else {
+                jar.add(cookie);
+            }
+        }
+    }
+}
+
+/// [`TestClient`] 的请求构造器，通过 [`TestRequest::send`] 发送
+///
+/// 请求构造失败（例如非法的 URI 或请求头）时 `send` 会 panic。
+pub struct TestRequest<'a> {
+    client: &'a TestClient,
+    builder: http::request::Builder,
+    body: Bytes,
+}
+
+impl<'a> TestRequest<'a> {
+    /// 添加请求头
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        <K as TryInto<HeaderName>>::Error: Into<http::Error>,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: Into<http::Error>,
+    {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+    /// 追加查询参数
+    pub fn query<T: Serialize>(mut self, query: &T) -> Self {
+        let query = serde_html_form::to_string(query).expect("failed to serialize query");
+        if let Some(uri) = self.builder.uri_ref().map(ToString::to_string) {
+            let separator = if uri.contains('?') { '&' } else { '?' };
+            self.builder = self.builder.uri(format!("{uri}{separator}{query}"));
+        }
+        self
+    }
+    /// 设置原始请求体
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+    /// 设置 JSON 请求体
+    pub fn json<T: Serialize>(self, json: &T) -> Self {
+        let body = serde_json::to_vec(json).expect("failed to serialize json");
+        self.content_type(mime::APPLICATION_JSON.as_ref())
+            .body(body)
+    }
+    /// 设置 `application/x-www-form-urlencoded` 请求体
+    pub fn form<T: Serialize>(self, form: &T) -> Self {
+        let body = serde_html_form::to_string(form).expect("failed to serialize form");
+        self.content_type(mime::APPLICATION_WWW_FORM_URLENCODED.as_ref())
+            .body(body)
+    }
+    /// 设置 `multipart/form-data` 请求体
+    pub fn multipart(self, form: MultipartForm) -> Self {
+        let content_type = form.content_type();
+        self.header(header::CONTENT_TYPE, content_type)
+            .body(form.into_bytes())
+    }
+    /// 发送请求，处理器返回的错误会像服务器一样转换为响应
+    pub async fn send(self) -> TestResponse {
+        let (client, req) = self.into_request();
+        let (parts, body) = client.dispatch(req).await.into_parts();
+        let collected = body.collect().await.expect("failed to read response body");
+        let trailers = collected.trailers().cloned();
+        #[cfg(feature = "cookie")]
+        client.store_cookies(&parts.headers);
+        TestResponse::new(parts, collected.to_bytes(), trailers)
+    }
+
+    pub(super) fn into_request(self) -> (&'a TestClient, http::Request<ReqBody>) {
+        let Self {
+            client,
+            mut builder,
+            body,
+        } = self;
+        if let Some(headers) = builder.headers_mut()
+            && !body.is_empty()
+        {
+            headers
+                .entry(header::CONTENT_LENGTH)
+                .or_insert_with(|| body.len().into());
+        }
+        #[cfg(feature = "cookie")]
+        if let Some(headers) = builder.headers_mut()
+            && !headers.contains_key(header::COOKIE)
+            && let Some(cookies) = client.cookie_header()
+        {
+            headers.insert(header::COOKIE, cookies);
+        }
+        let body = match body.is_empty() {
+            true => ReqBody::Empty,
+            false => ReqBody::Once(body),
+        };
+        (client, builder.body(body).expect("invalid test request"))
+    }
+
+    fn content_type(mut self, content_type: &str) -> Self {
+        if let Some(headers) = self.builder.headers_mut() {
+            headers
+                .entry(header::CONTENT_TYPE)
+                .or_insert(HeaderValue::from_str(content_type).unwrap());
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn test_client_requests() {
+        let route = Route::new("users")
+            .post(|mut req: Request| async move {
+                let user: User = req.json_parse().await?;
+                Ok(user)
+            })
+            .append(Route::new("<id:i64>").get(|req: Request| async move {
+                let id: i64 = req.get_path_params("id")?;
+                let token = req
+                    .headers()
+                    .get("x-token")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                Ok(User { id, name: token })
+            }))
+            .append(Route::new("search").get(|mut req: Request| async move {
+                let params = req.params().clone();
+                Ok(params.get("q").cloned().unwrap_or_default())
+            }));
+        let client = TestClient::new(route);
+
+        let res = client
+            .get("/users/1")
+            .header("x-token", "secret")
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.json::<User>().unwrap(),
+            User {
+                id: 1,
+                name: "secret".to_string()
+            }
+        );
+
+        let user = User {
+            id: 2,
+            name: "silent".to_string(),
+        };
+        let res = client.post("/users").json(&user).send().await;
+        assert_eq!(res.json::<User>().unwrap(), user);
+
+        let res = client
+            .get("/users/search")
+            .query(&[("q", "a b")])
+            .send()
+            .await;
+        assert_eq!(res.text(), "a b");
+
+        // 处理器错误与服务器一样转换为响应
+        let res = client.get("/missing").send().await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "multipart")]
+    #[tokio::test]
+    async fn test_client_multipart() {
+        let route = Route::new("upload").post(|mut req: Request| async move {
+            let name: String = req.form_field("name").await.unwrap_or_default();
+            let file = &req.files("file").await.unwrap()[0];
+            let content = std::fs::read_to_string(file.path())?;
+            Ok(format!(
+                "{name}:{}:{content}",
+                file.name().unwrap_or_default()
+            ))
+        });
+        let client = TestClient::new(route);
+        let form = MultipartForm::new().text("name", "silent").file(
+            "file",
+            "hello.txt",
+            "text/plain",
+            "hello world",
+        );
+        let res = client.post("/upload").multipart(form).send().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.text(), "silent:hello.txt:hello world");
+    }
+
+    #[cfg(feature = "session")]
+    #[tokio::test]
+    async fn test_client_session() {
+        let route = Route::new("").get(|mut req: Request| async move {
+            let count = req.session::<i64>("count").unwrap_or_default() + 1;
+            req.sessions_mut().insert("count", count)?;
+            Ok(count)
+        });
+        let client = TestClient::new(route);
+        for expected in 1..=3 {
+            let res = client.get("/").send().await;
+            assert_eq!(res.json::<i64>().unwrap(), expected);
+        }
+        assert!(client.cookies().iter().count() > 0);
+
+        // 清空 cookie 后开始新的会话
+        client.clear_cookies();
+        let res = client.get("/").send().await;
+        assert_eq!(res.json::<i64>().unwrap(), 1);
+    }
+
+    #[cfg(feature = "cookie")]
+    #[tokio::test]
+    async fn test_client_cookies() {
+        let route = Route::new("")
+            .append(Route::new("login").post(|_req: Request| async move {
+                let mut res = Response::text("ok");
+                res.cookies_mut().add(Cookie::new("user", "silent"));
+                Ok(res)
+            }))
+            .append(Route::new("logout").post(|_req: Request| async move {
+                let mut res = Response::text("bye");
+                let cookie = Cookie::build(("user", "")).max_age(CookieTime::Duration::ZERO);
+                res.cookies_mut().add(cookie.build());
+                Ok(res)
+            }))
+            .append(Route::new("me").get(|req: Request| async move {
+                Ok(req
+                    .cookie("user")
+                    .map(|cookie| cookie.value().to_string())
+                    .unwrap_or_default())
+            }));
+        let client = TestClient::new(route);
+
+        let res = client.post("/login").send().await;
+        assert_eq!(res.cookie("user").unwrap().value(), "silent");
+        assert_eq!(client.get("/me").send().await.text(), "silent");
+
+        client.post("/logout").send().await;
+        assert!(client.cookies().get("user").is_none());
+        assert_eq!(client.get("/me").send().await.text(), "");
+    }
+}
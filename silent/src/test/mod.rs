@@ -0,0 +1,19 @@
+mod client;
+mod multipart;
+mod response;
+#[cfg(feature = "sse")]
+mod sse;
+#[cfg(feature = "upgrade")]
+mod websocket;
+
+pub use client::{TestClient, TestRequest};
+pub use multipart::MultipartForm;
+pub use response::TestResponse;
+#[cfg(feature = "sse")]
+pub use sse::TestEventStream;
+#[cfg(feature = "upgrade")]
+pub use websocket::TestWebSocket;
+
+/// 读取 SSE 事件与 WebSocket 消息的默认等待时间
+#[cfg(any(feature = "sse", feature = "upgrade"))]
+const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
@@ -0,0 +1,89 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// 测试请求使用的 `multipart/form-data` 请求体
+/// ```
+/// use silent::prelude::*;
+/// let form = MultipartForm::new()
+///     .text("name", "silent")
+///     .file("avatar", "avatar.png", "image/png", vec![0u8; 16]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultipartForm {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+struct Part {
+    name: String,
+    file: Option<(String, String)>,
+    data: Bytes,
+}
+
+impl Default for MultipartForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultipartForm {
+    /// 创建空表单，使用随机分隔符
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("silent-{}", uuid::Uuid::new_v4().simple()),
+            parts: vec![],
+        }
+    }
+    /// 添加文本字段
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(Part {
+            name: name.into(),
+            file: None,
+            data: Bytes::from(value.into()),
+        });
+        self
+    }
+    /// 添加文件字段
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        file_name: impl Into<String>,
+        content_type: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        self.parts.push(Part {
+            name: name.into(),
+            file: Some((file_name.into(), content_type.into())),
+            data: data.into(),
+        });
+        self
+    }
+    /// 请求的 `content-type`，包含分隔符
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(crate) fn into_bytes(self) -> Bytes {
+        let mut body = BytesMut::new();
+        for part in self.parts {
+            body.put_slice(format!("--{}\r\n", self.boundary).as_bytes());
+            match part.file {
+                Some((file_name, content_type)) => body.put_slice(
+                    format!(
+                        "content-disposition: form-data; name=\"{}\"; filename=\"{}\"\r\ncontent-type: {}\r\n\r\n",
+                        part.name, file_name, content_type
+                    )
+                    .as_bytes(),
+                ),
+                None => body.put_slice(
+                    format!("content-disposition: form-data; name=\"{}\"\r\n\r\n", part.name)
+                        .as_bytes(),
+                ),
+            }
+            body.put_slice(&part.data);
+            body.put_slice(b"\r\n");
+        }
+        body.put_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        body.freeze()
+    }
+}
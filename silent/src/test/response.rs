@@ -0,0 +1,98 @@
+use crate::{Result, StatusCode};
+use bytes::Bytes;
+#[cfg(feature = "cookie")]
+use cookie::Cookie;
+use http::{Extensions, HeaderMap, Version, header};
+use serde::de::DeserializeOwned;
+
+/// [`TestClient`](super::TestClient) 返回的响应，响应体已完整读取
+#[derive(Debug)]
+pub struct TestResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    extensions: Extensions,
+    body: Bytes,
+    trailers: Option<HeaderMap>,
+}
+
+impl TestResponse {
+    pub(crate) fn new(
+        parts: http::response::Parts,
+        body: Bytes,
+        trailers: Option<HeaderMap>,
+    ) -> Self {
+        Self {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers,
+            extensions: parts.extensions,
+            body,
+            trailers,
+        }
+    }
+    /// 响应状态码
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+    /// 响应 HTTP 版本
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// 响应头
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+    /// 获取响应头的字符串值，不存在或不是合法字符串时返回 `None`
+    pub fn header(&self, name: impl header::AsHeaderName) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+    /// 响应拓展
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+    /// 响应体原始字节
+    #[inline]
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+    /// 响应体结束后发送的 trailers
+    #[inline]
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        self.trailers.as_ref()
+    }
+    /// 响应体文本，非法的 UTF-8 字符会被替换
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+    /// 按 JSON 解析响应体
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body).map_err(Into::into)
+    }
+    /// 响应通过 `set-cookie` 设置的 cookie
+    #[cfg(feature = "cookie")]
+    pub fn cookies(&self) -> Vec<Cookie<'static>> {
+        set_cookies(&self.headers)
+    }
+    /// 获取响应设置的指定 cookie
+    #[cfg(feature = "cookie")]
+    pub fn cookie(&self, name: &str) -> Option<Cookie<'static>> {
+        self.cookies()
+            .into_iter()
+            .find(|cookie| cookie.name() == name)
+    }
+}
+
+#[cfg(feature = "cookie")]
+pub(super) fn set_cookies(headers: &HeaderMap) -> Vec<Cookie<'static>> {
+    headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| Cookie::parse_encoded(value.to_string()).ok())
+        .collect()
+}
@@ -0,0 +1,241 @@
+use super::{DEFAULT_TIMEOUT, TestRequest};
+use crate::core::res_body::ResBody;
+use crate::sse::SSEEvent;
+use crate::{Result, SilentError, StatusCode};
+use http::HeaderMap;
+use http_body::Body;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// 测试中订阅的 SSE 事件流，按事件逐个读取
+///
+/// 响应体在读取事件时才被消费，因此可以订阅不会结束的事件流。
+pub struct TestEventStream {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: ResBody,
+    buffer: Vec<u8>,
+    events: VecDeque<SSEEvent>,
+    ended: bool,
+    timeout: Duration,
+}
+
+impl TestRequest<'_> {
+    /// 发送请求并以 SSE 事件流读取响应
+    pub async fn sse(self) -> TestEventStream {
+        let (client, req) = self
+            .header(http::header::ACCEPT, "text/event-stream")
+            .into_request();
+        let (parts, body) = client.dispatch(req).await.into_parts();
+        #[cfg(feature = "cookie")]
+        client.store_cookies(&parts.headers);
+        TestEventStream {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            buffer: Vec::new(),
+            events: VecDeque::new(),
+            ended: false,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl TestEventStream {
+    /// 响应状态码
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+    /// 响应头
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+    /// 设置 [`TestEventStream::recv`] 的等待时间，默认为 5 秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// 读取下一个事件，事件流结束时返回 `None`，超时返回错误
+    pub async fn recv(&mut self) -> Result<Option<SSEEvent>> {
+        self.recv_timeout(self.timeout).await
+    }
+    /// 在指定时间内读取下一个事件
+    pub async fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<SSEEvent>> {
+        tokio::time::timeout(timeout, self.next_event())
+            .await
+            .map_err(|_| {
+                SilentError::business_error(
+                    StatusCode::REQUEST_TIMEOUT,
+                    "timed out waiting for sse event".to_string(),
+                )
+            })?
+    }
+
+    async fn next_event(&mut self) -> Result<Option<SSEEvent>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+            if self.ended {
+                return Ok(None);
+            }
+            let frame =
+                std::future::poll_fn(|cx| std::pin::Pin::new(&mut self.body).poll_frame(cx))
+                    .await
+                    .transpose()
+                    .map_err(|e| {
+                        SilentError::business_error(StatusCode::BAD_GATEWAY, e.to_string())
+                    })?;
+            match frame.map(|frame| frame.into_data()) {
+                // 按字节缓冲，只解码完整的事件块，避免跨帧的多字节字符或 `\r\n` 被拆开
+                Some(Ok(data)) => self.buffer.extend_from_slice(&data),
+                Some(Err(_)) => continue,
+                // 未以空行结束的事件按规范丢弃
+                None => self.ended = true,
+            }
+            while let Some((end, next)) = block_end(&self.buffer, self.ended) {
+                let block = String::from_utf8_lossy(&self.buffer[..end])
+                    .replace("\r\n", "\n")
+                    .replace('\r', "\n");
+                self.buffer.drain(..next);
+                if let Some(event) = parse_event(&block) {
+                    self.events.push_back(event);
+                }
+            }
+        }
+    }
+}
+
+/// 查找第一个空行，返回事件块的结束位置与下一个事件块的起始位置
+///
+/// 行尾可以是 `\n`、`\r\n` 或 `\r`，事件流结束前缓冲末尾的 `\r` 需要等待下一帧才能确定。
+fn block_end(buffer: &[u8], ended: bool) -> Option<(usize, usize)> {
+    let mut line_start = 0;
+    let mut i = 0;
+    while i < buffer.len() {
+        let len = match buffer[i] {
+            b'\n' => 1,
+            b'\r' => match buffer.get(i + 1) {
+                Some(b'\n') => 2,
+                Some(_) => 1,
+                None if ended => 1,
+                None => return None,
+            },
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if i == line_start {
+            return Some((line_start, i + len));
+        }
+        i += len;
+        line_start = i;
+    }
+    None
+}
+
+/// 按 EventSource 规范解析单个事件块
+fn parse_event(block: &str) -> Option<SSEEvent> {
+    let mut event = SSEEvent::default();
+    let mut data: Option<String> = None;
+    let mut empty = true;
+    for line in block.lines() {
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "" => event = event.comment(value),
+            "event" => event = event.event(value),
+            "id" => event = event.id(value),
+            "retry" => match value.parse() {
+                Ok(millis) => event = event.retry(Duration::from_millis(millis)),
+                Err(_) => continue,
+            },
+            "data" => match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            },
+            _ => continue,
+        }
+        empty = false;
+    }
+    if let Some(data) = data {
+        event = event.data(data);
+    }
+    (!empty).then_some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use futures_util::{StreamExt, stream};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_sse_stream() {
+        let route = Route::new("events").get(|_req| async {
+            let events = stream::iter(vec![
+                Ok(SSEEvent::default().data("first")),
+                Ok(SSEEvent::default()
+                    .event("chat")
+                    .id("2")
+                    .data("line 1\nline 2")
+                    .retry(Duration::from_millis(1500))),
+            ])
+            .chain(stream::pending());
+            sse_reply(events)
+        });
+        let client = TestClient::new(route);
+        let mut events = client.get("/events").sse().await;
+        assert_eq!(events.status(), StatusCode::OK);
+        assert_eq!(
+            events.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let event = events.recv().await.unwrap().unwrap();
+        assert_eq!(event.get_data(), Some("first"));
+        assert_eq!(event.get_event(), None);
+
+        let event = events.recv().await.unwrap().unwrap();
+        assert_eq!(event.get_event(), Some("chat"));
+        assert_eq!(event.get_id(), Some("2"));
+        assert_eq!(event.get_data(), Some("line 1\nline 2"));
+        assert_eq!(event.get_retry(), Some(Duration::from_millis(1500)));
+
+        // 事件流未结束时等待超时
+        let err = events
+            .recv_timeout(Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_sse_split_frames() {
+        // 多字节字符与 `\r\n` 被拆到不同的帧中
+        let route = Route::new("events").get(|_req| async {
+            let raw = "data: 你好\r\nid: 3\r\n\r\ndata: end\r\r".as_bytes();
+            let split = raw.iter().position(|b| *b == 0xe4).unwrap() + 1;
+            let crlf = raw.iter().position(|b| *b == b'\r').unwrap() + 1;
+            let chunks = [&raw[..split], &raw[split..crlf], &raw[crlf..]]
+                .map(|chunk| Ok::<_, std::io::Error>(bytes::Bytes::copy_from_slice(chunk)));
+            Ok(Response::empty()
+                .with_header(header::CONTENT_TYPE, "text/event-stream".parse().unwrap())
+                .with_body(stream_body(stream::iter(chunks))))
+        });
+        let client = TestClient::new(route);
+        let mut events = client.get("/events").sse().await;
+        let event = events.recv().await.unwrap().unwrap();
+        assert_eq!(event.get_data(), Some("你好"));
+        assert_eq!(event.get_id(), Some("3"));
+        let event = events.recv().await.unwrap().unwrap();
+        assert_eq!(event.get_data(), Some("end"));
+        assert!(events.recv().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,154 @@
+use super::{DEFAULT_TIMEOUT, TestRequest};
+use crate::core::req_body::ReqBody;
+use crate::ws::Message;
+use crate::{Result, SilentError, StatusCode};
+use futures_util::{SinkExt, StreamExt};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::io::DuplexStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+/// 测试中建立的 WebSocket 连接，基于内存中的 [`tokio::io::duplex`] 而非真实 socket
+pub struct TestWebSocket {
+    stream: WebSocketStream<DuplexStream>,
+    timeout: Duration,
+}
+
+impl TestRequest<'_> {
+    /// 发起 WebSocket 握手，握手失败时返回错误
+    pub async fn websocket(self) -> Result<TestWebSocket> {
+        let (client, req) = self.into_request();
+        let (parts, _) = req.into_parts();
+        let path = parts.uri.path_and_query().map_or("/", |path| path.as_str());
+        let mut request = format!("ws://localhost{path}")
+            .into_client_request()
+            .map_err(|e| SilentError::WsError(e.to_string()))?;
+        request.headers_mut().extend(parts.headers);
+
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = client.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req: hyper::Request<Incoming>| {
+                let server = server.clone();
+                async move { Ok::<_, Infallible>(server.dispatch(req.map(ReqBody::from)).await) }
+            });
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(server_io), service)
+                .with_upgrades()
+                .await;
+        });
+        let (stream, _response) = tokio_tungstenite::client_async(request, client_io)
+            .await
+            .map_err(|e| SilentError::WsError(format!("handshake error: {e}")))?;
+        #[cfg(feature = "cookie")]
+        client.store_cookies(_response.headers());
+        Ok(TestWebSocket {
+            stream,
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+}
+
+impl TestWebSocket {
+    /// 设置 [`TestWebSocket::recv`] 的等待时间，默认为 5 秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// 发送消息
+    pub async fn send(&mut self, msg: Message) -> Result<()> {
+        self.stream
+            .send(msg.inner)
+            .await
+            .map_err(|e| SilentError::WsError(format!("send error: {e}")))
+    }
+    /// 接收下一条消息，连接关闭时返回 `None`，超时返回错误
+    pub async fn recv(&mut self) -> Result<Option<Message>> {
+        self.recv_timeout(self.timeout).await
+    }
+    /// 在指定时间内接收下一条消息
+    pub async fn recv_timeout(&mut self, timeout: Duration) -> Result<Option<Message>> {
+        let msg = tokio::time::timeout(timeout, self.stream.next())
+            .await
+            .map_err(|_| {
+                SilentError::business_error(
+                    StatusCode::REQUEST_TIMEOUT,
+                    "timed out waiting for websocket message".to_string(),
+                )
+            })?;
+        match msg {
+            Some(Ok(inner)) => Ok(Some(Message { inner })),
+            Some(Err(e)) => Err(SilentError::WsError(format!("receive error: {e}"))),
+            None => Ok(None),
+        }
+    }
+    /// 发送关闭帧并断开连接，不等待服务端的关闭帧
+    pub async fn close(mut self) -> Result<()> {
+        self.send(Message::close()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use tokio::sync::mpsc::UnboundedSender;
+
+    #[tokio::test]
+    async fn test_websocket() {
+        let closed = Arc::new(AtomicBool::new(false));
+        let on_close = closed.clone();
+        let route = Route::new("ws").ws(
+            None,
+            WebSocketHandler::new()
+                .on_connect(|parts, sender: UnboundedSender<Message>| async move {
+                    let mut parts = parts.write().await;
+                    let name = parts.params().get("name").cloned().unwrap_or_default();
+                    sender.send(Message::text(format!("hello {name}"))).unwrap();
+                    parts.extensions_mut().insert(sender);
+                    Ok(())
+                })
+                .on_send(|msg, _| async { Ok(msg) })
+                .on_receive(|msg, parts| async move {
+                    let parts = parts.read().await;
+                    let sender = parts.extensions().get::<UnboundedSender<Message>>();
+                    sender.unwrap().send(msg).unwrap();
+                    Ok(())
+                })
+                .on_close(move |_| {
+                    let closed = on_close.clone();
+                    async move { closed.store(true, Ordering::SeqCst) }
+                }),
+        );
+        let client = TestClient::new(route);
+
+        let mut ws = client.get("/ws?name=silent").websocket().await.unwrap();
+        let msg = ws.recv().await.unwrap().unwrap();
+        assert_eq!(msg.to_str().unwrap(), "hello silent");
+
+        ws.send(Message::text("echo")).await.unwrap();
+        let msg = ws.recv().await.unwrap().unwrap();
+        assert_eq!(msg.to_str().unwrap(), "echo");
+
+        // 没有消息时等待超时
+        let err = ws
+            .recv_timeout(Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::REQUEST_TIMEOUT);
+
+        ws.close().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(closed.load(Ordering::SeqCst));
+
+        // 非 WebSocket 路由握手失败
+        assert!(client.get("/missing").websocket().await.is_err());
+    }
+}
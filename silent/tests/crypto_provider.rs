@@ -0,0 +1,29 @@
+//! 独立进程中不安装进程默认的加密实现，检查未启用 `aws-lc-rs` 与 `ring` 特性时返回错误而不是 panic
+#![cfg(feature = "tls")]
+use silent::prelude::*;
+use std::path::Path;
+
+#[test]
+fn test_tls_config_provider() {
+    let certs = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/certs");
+    let config = TlsConfig::new()
+        .cert(certs.join("localhost.pem"), certs.join("localhost-key.pem"))
+        .build();
+    if cfg!(any(feature = "aws-lc-rs", feature = "ring")) {
+        assert!(config.is_ok());
+    } else {
+        assert_eq!(config.unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+    }
+}
+
+#[cfg(feature = "client")]
+#[test]
+fn test_client_provider() {
+    let client = Client::builder().build();
+    if cfg!(any(feature = "aws-lc-rs", feature = "ring")) {
+        assert!(client.is_ok());
+    } else {
+        let err = client.err().unwrap();
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}